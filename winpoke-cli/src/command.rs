@@ -0,0 +1,78 @@
+pub mod find;
+pub mod hotkey;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod menu;
+pub mod pick;
+pub mod record;
+pub mod regjump;
+pub mod run;
+#[cfg(feature = "rhai")]
+pub mod script;
+#[cfg(feature = "rpc")]
+pub mod serve;
+pub mod shell;
+
+use clap::Subcommand;
+
+use crate::error::Result;
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// 按选择器路径查找窗口，可依次高亮每个匹配的窗口
+    Find(find::FindArgs),
+
+    /// 按配置文件注册全局热键，按下时执行对应的命令
+    Hotkey(hotkey::HotkeyArgs),
+
+    /// 通过 HTTP 接口查找和操作窗口，可输出 OpenAPI 文档
+    #[cfg(feature = "http")]
+    Http(http::HttpArgs),
+
+    /// 输出窗口的菜单树及各菜单项的命令 ID
+    Menu(menu::MenuArgs),
+
+    /// 跟随鼠标显示光标下的控件，按键时输出其窗口层级和选择器
+    Pick(pick::PickArgs),
+
+    /// 录制键盘和鼠标操作，生成可以回放的脚本
+    Record(record::RecordArgs),
+
+    /// 在注册表编辑器中定位键和值，注册表编辑器未运行时自动启动
+    Regjump(regjump::RegjumpArgs),
+
+    /// 执行自动化脚本（.poke）
+    Run(run::RunArgs),
+
+    /// 执行 Rhai 自动化脚本
+    #[cfg(feature = "rhai")]
+    Script(script::ScriptArgs),
+
+    /// 以 JSON-RPC 服务的形式提供查找和操作窗口的接口
+    #[cfg(feature = "rpc")]
+    Serve(serve::ServeArgs),
+
+    /// 交互式查找和操作窗口，支持历史记录和 Tab 补全
+    Shell(shell::ShellArgs),
+}
+
+impl Command {
+    pub fn run(self) -> Result<()> {
+        match self {
+            Command::Find(args) => find::run(args),
+            Command::Hotkey(args) => hotkey::run(args),
+            #[cfg(feature = "http")]
+            Command::Http(args) => http::run(args),
+            Command::Menu(args) => menu::run(args),
+            Command::Pick(args) => pick::run(args),
+            Command::Record(args) => record::run(args),
+            Command::Regjump(args) => regjump::run(args),
+            Command::Run(args) => run::run(args),
+            #[cfg(feature = "rhai")]
+            Command::Script(args) => script::run(args),
+            #[cfg(feature = "rpc")]
+            Command::Serve(args) => serve::run(args),
+            Command::Shell(args) => shell::run(args),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use clap::Args;
+use winpoke::color::Color;
+use winpoke::prelude::*;
+
+use crate::error::Result;
+
+#[derive(Args)]
+pub struct FindArgs {
+    /// 选择器路径，各级以 `>` 分隔，如 `#32770[caption="另存为"] > ComboBox > Edit`
+    path: SelectorPath,
+
+    /// 依次高亮每个匹配的窗口
+    #[arg(long)]
+    highlight: bool,
+
+    /// 每个窗口的高亮时长（毫秒）
+    #[arg(long, default_value_t = 800)]
+    duration: u64,
+
+    /// 高亮框颜色，如 `red` 或 `#FF8000`
+    #[arg(long, default_value_t = Color::RED)]
+    color: Color,
+}
+
+pub fn run(args: FindArgs) -> Result<()> {
+    let windows = WindowInfo::find_path(&args.path)?;
+    if windows.is_empty() {
+        return Err(Error::WindowNotFound.into());
+    }
+
+    let total = windows.len();
+    for (index, window) in windows.iter().enumerate() {
+        let (top, right, bottom, left) = window.position;
+        println!(
+            "[{}/{total}] {} {:?} (0x{:X}) pid={} ({left},{top})-({right},{bottom})",
+            index + 1,
+            window.class_name,
+            window.caption,
+            window.hwnd.0 as usize,
+            window.pid,
+        );
+
+        // 某个窗口无法高亮（如大小为零）时继续处理其余窗口
+        if args.highlight
+            && let Err(e) = window.highlight(Duration::from_millis(args.duration), args.color)
+        {
+            eprintln!("无法高亮 0x{:X}: {e}", window.hwnd.0 as usize);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use std::path::PathBuf;
+use std::process;
+
+use clap::Args;
+use serde::Deserialize;
+use winpoke::hotkey::HotkeyManager;
+
+use crate::error::Result;
+
+#[derive(Args)]
+pub struct HotkeyArgs {
+    /// 热键配置文件（TOML）
+    config: PathBuf,
+}
+
+/// 热键配置文件
+///
+/// ```toml
+/// [[hotkey]]
+/// keys = "Ctrl+Alt+J"
+/// command = "winpoke-cli regjump HKCU\\Software"
+/// ```
+#[derive(Debug, Deserialize, PartialEq)]
+struct HotkeyConfig {
+    #[serde(default, rename = "hotkey")]
+    hotkeys: Vec<HotkeyBinding>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct HotkeyBinding {
+    /// 组合键，如 `Ctrl+Alt+J`
+    keys: String,
+
+    /// 热键按下时通过 `cmd /C` 执行的命令
+    command: String,
+}
+
+pub fn run(args: HotkeyArgs) -> Result<()> {
+    let config: HotkeyConfig = toml::from_str(&std::fs::read_to_string(&args.config)?)?;
+
+    let manager = HotkeyManager::new()?;
+    for binding in config.hotkeys {
+        println!("{} => {}", binding.keys, binding.command);
+
+        let command = binding.command;
+        manager.register(&binding.keys, move || execute(&command))?;
+    }
+
+    println!("已注册 {} 个热键，按 Ctrl+C 退出", manager.hotkeys().len());
+
+    loop {
+        std::thread::park();
+    }
+}
+
+fn execute(command: &str) {
+    match process::Command::new("cmd").args(["/C", command]).spawn() {
+        Ok(child) => println!("执行: {command} (pid {})", child.id()),
+        Err(e) => eprintln!("执行失败: {command}: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_config() {
+        let config: HotkeyConfig = toml::from_str(
+            r#"
+            [[hotkey]]
+            keys = "Ctrl+Alt+J"
+            command = "regedit.exe"
+
+            [[hotkey]]
+            keys = "Win+F5"
+            command = "echo hi"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.hotkeys,
+            vec![
+                HotkeyBinding {
+                    keys: "Ctrl+Alt+J".to_string(),
+                    command: "regedit.exe".to_string(),
+                },
+                HotkeyBinding {
+                    keys: "Win+F5".to_string(),
+                    command: "echo hi".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_config() {
+        let config: HotkeyConfig = toml::from_str("").unwrap();
+        assert!(config.hotkeys.is_empty());
+    }
+}
@@ -0,0 +1,40 @@
+use std::net::TcpListener;
+
+use clap::Args;
+use winpoke::http::{Server, openapi};
+use winpoke::script::Desktop;
+
+use crate::error::Result;
+
+#[derive(Args)]
+pub struct HttpArgs {
+    /// 监听的地址
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    listen: String,
+
+    /// 客户端需要在 `Authorization: Bearer` 头中带上此令牌
+    #[arg(
+        long,
+        env = "WINPOKE_TOKEN",
+        hide_env_values = true,
+        required_unless_present = "openapi"
+    )]
+    token: Option<String>,
+
+    /// 只输出 OpenAPI 文档，不启动服务
+    #[arg(long)]
+    openapi: bool,
+}
+
+pub fn run(args: HttpArgs) -> Result<()> {
+    if args.openapi {
+        println!("{:#}", openapi());
+        return Ok(());
+    }
+
+    let token = args.token.expect("未指定 --openapi 时必须有令牌");
+    let listener = TcpListener::bind(&args.listen)?;
+    eprintln!("正在监听 http://{}", listener.local_addr()?);
+
+    Ok(Server::new(Desktop::new(), token).serve(listener)?)
+}
@@ -0,0 +1,23 @@
+use clap::Args;
+use winpoke::error::Error;
+use winpoke::menu::Menu;
+use winpoke::prelude::*;
+
+use crate::error::Result;
+
+#[derive(Args)]
+pub struct MenuArgs {
+    /// 目标窗口的选择器，如 `RegEdit_RegEdit` 或 `*[caption="记事本"]`
+    selector: Selector,
+}
+
+pub fn run(args: MenuArgs) -> Result<()> {
+    let window = WindowInfo::find(&args.selector)?
+        .into_iter()
+        .next()
+        .ok_or(Error::WindowNotFound)?;
+
+    print!("{}", Menu::of(&window)?);
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use std::io::Write;
+use std::thread::sleep;
+use std::time::Duration;
+
+use clap::Args;
+use winpoke::input::is_key_down;
+use winpoke::keys::vk_from_name;
+use winpoke::prelude::*;
+
+use crate::error::Result;
+
+/// 跟随鼠标的刷新间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 退出拾取的按键
+const VK_ESCAPE: u16 = 0x1B;
+
+#[derive(Args)]
+pub struct PickArgs {
+    /// 拾取鼠标下控件的按键
+    #[arg(short, long, default_value = "Ctrl", value_parser = parse_key)]
+    key: u16,
+}
+
+fn parse_key(name: &str) -> std::result::Result<u16, String> {
+    vk_from_name(name)
+        .map(|vk| vk as u16)
+        .ok_or_else(|| format!("未知的按键 {name:?}"))
+}
+
+pub fn run(args: PickArgs) -> Result<()> {
+    println!(
+        "移动鼠标到目标控件上，按 {} 拾取，按 Esc 退出",
+        key_name(args.key)
+    );
+
+    let mut last = None;
+    let mut was_down = false;
+    loop {
+        if is_key_down(VK_ESCAPE) {
+            println!();
+            return Ok(());
+        }
+
+        // 光标在无法访问的窗口（如 UAC 提示）上时跳过
+        let Ok(window) = WindowInfo::from_cursor() else {
+            sleep(POLL_INTERVAL);
+            continue;
+        };
+
+        if last != Some(window.hwnd) {
+            last = Some(window.hwnd);
+            print!("\r\x1b[K{}", describe(&window));
+            std::io::stdout().flush()?;
+        }
+
+        // 只在按下的瞬间拾取，按住不放不会重复输出
+        let down = is_key_down(args.key);
+        if down && !was_down {
+            println!("\r\x1b[K");
+            print_pick(&window)?;
+            last = None;
+        }
+        was_down = down;
+
+        sleep(POLL_INTERVAL);
+    }
+}
+
+fn key_name(vk: u16) -> String {
+    winpoke::keys::name_from_vk(vk as u32).unwrap_or_else(|| format!("0x{vk:02X}"))
+}
+
+fn describe(window: &WindowInfo) -> String {
+    format!(
+        "{} {:?} (0x{:X})",
+        window.class_name, window.caption, window.hwnd.0 as usize
+    )
+}
+
+/// 输出各级窗口和选择器路径
+fn print_pick(window: &WindowInfo) -> Result<()> {
+    for (depth, window) in window.hierarchy()?.iter().enumerate() {
+        println!("{}{}", "  ".repeat(depth), describe(window));
+    }
+    println!("选择器: {}", window.selector_path()?);
+    println!();
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+use std::thread::sleep;
+use std::time::Duration;
+
+use clap::Args;
+use winpoke::input::is_key_down;
+use winpoke::keys::{name_from_vk, vk_from_name};
+use winpoke::recorder::{InputKind, Recorder, coalesce, to_script};
+
+use crate::error::Result;
+
+/// 检查停止键的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Args)]
+pub struct RecordArgs {
+    /// 保存脚本的文件，默认输出到标准输出
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// 输出 JSON 而不是 .poke 脚本
+    #[cfg(feature = "rpc")]
+    #[arg(long)]
+    json: bool,
+
+    /// 停止录制的按键
+    #[arg(long, default_value = "Pause", value_parser = parse_key)]
+    stop_key: u32,
+
+    /// 不短于此时长（毫秒）的停顿记录为 sleep，为 0 时不记录停顿
+    #[arg(long, default_value_t = 1000)]
+    min_pause: u64,
+}
+
+fn parse_key(name: &str) -> std::result::Result<u32, String> {
+    vk_from_name(name).ok_or_else(|| format!("未知的按键 {name:?}"))
+}
+
+pub fn run(args: RecordArgs) -> Result<()> {
+    let recorder = Recorder::start()?;
+    eprintln!(
+        "正在录制，按 {} 停止",
+        name_from_vk(args.stop_key).unwrap_or_else(|| format!("0x{:02X}", args.stop_key))
+    );
+
+    while !is_key_down(args.stop_key as u16) {
+        sleep(POLL_INTERVAL);
+    }
+    let mut events = recorder.stop();
+
+    // 去掉停止键本身及之后的输入
+    if let Some(index) = events.iter().position(
+        |event| matches!(event.kind, InputKind::KeyDown { vk, .. } if vk == args.stop_key),
+    ) {
+        events.truncate(index);
+    }
+
+    let min_pause = (args.min_pause > 0).then(|| Duration::from_millis(args.min_pause));
+    let actions = coalesce(&events, min_pause);
+
+    #[cfg(feature = "rpc")]
+    let output = match args.json {
+        true => format!("{:#}\n", winpoke::recorder::to_json(&actions)),
+        false => to_script(&actions).to_string(),
+    };
+    #[cfg(not(feature = "rpc"))]
+    let output = to_script(&actions).to_string();
+
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, output)?;
+            eprintln!("已录制 {} 个操作，保存到 {}", actions.len(), path.display());
+        }
+        None => print!("{output}"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use clap::Args;
+use winpoke::apps::regedit::Regedit;
+use winpoke::registry::RegistryPath;
+
+use crate::error::Result;
+
+#[derive(Args)]
+pub struct RegjumpArgs {
+    /// 注册表键路径，如 `HKCU\Software\Microsoft`、`HKLM:\SOFTWARE` 或从地址栏复制的路径
+    key: RegistryPath,
+
+    /// 定位后选中的值名称，空字符串表示默认值
+    #[arg(short, long)]
+    value: Option<String>,
+}
+
+pub fn run(args: RegjumpArgs) -> Result<()> {
+    let regedit = Regedit::open()?;
+    regedit.jump_to_value(&args.key, args.value.as_deref())?;
+
+    match args.value {
+        Some(value) => println!("已定位到 {} 中的值 {value:?}", args.key),
+        None => println!("已定位到 {}", args.key),
+    }
+
+    Ok(())
+}
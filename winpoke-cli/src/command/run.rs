@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use winpoke::script::{Backend, Desktop, DryRun, Interpreter, Script};
+
+use crate::error::Result;
+
+#[derive(Args)]
+pub struct RunArgs {
+    /// 脚本文件（.poke）
+    script: PathBuf,
+
+    /// 只查找窗口并输出将要执行的操作，不实际操作窗口
+    #[arg(long)]
+    dry_run: bool,
+
+    /// 预先设置脚本变量，可重复，如 `--set name=value`
+    #[arg(long = "set", value_name = "NAME=VALUE", value_parser = parse_variable)]
+    variables: Vec<(String, String)>,
+}
+
+pub(crate) fn parse_variable(s: &str) -> std::result::Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(name, value)| (name.trim().to_string(), value.to_string()))
+        .ok_or_else(|| format!("{s:?} 缺少 ="))
+}
+
+pub fn run(args: RunArgs) -> Result<()> {
+    let script = Script::parse(&std::fs::read_to_string(&args.script)?)?;
+
+    if !args.dry_run {
+        let mut interpreter = with_variables(Interpreter::new(Desktop::new()), &args.variables);
+        return Ok(interpreter.run(&script)?);
+    }
+
+    let mut interpreter = with_variables(
+        Interpreter::new(DryRun::new(Desktop::new())),
+        &args.variables,
+    );
+    let result = interpreter.run(&script);
+
+    // 出错时也输出已记录的操作，便于定位
+    for action in interpreter.backend().actions() {
+        println!("{action}");
+    }
+
+    Ok(result?)
+}
+
+fn with_variables<B: Backend>(
+    mut interpreter: Interpreter<B>,
+    variables: &[(String, String)],
+) -> Interpreter<B> {
+    for (name, value) in variables {
+        interpreter = interpreter.with_variable(name, value);
+    }
+
+    interpreter
+}
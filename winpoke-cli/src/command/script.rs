@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use winpoke::script::rhai::RhaiEngine;
+use winpoke::script::{Backend, Desktop, DryRun};
+
+use crate::command::run::parse_variable;
+use crate::error::Result;
+
+#[derive(Args)]
+pub struct ScriptArgs {
+    /// 脚本文件（.rhai）
+    script: PathBuf,
+
+    /// 只查找窗口并输出将要执行的操作，不实际操作窗口
+    #[arg(long)]
+    dry_run: bool,
+
+    /// 预先设置脚本变量，可重复，如 `--set name=value`
+    #[arg(long = "set", value_name = "NAME=VALUE", value_parser = parse_variable)]
+    variables: Vec<(String, String)>,
+}
+
+pub fn run(args: ScriptArgs) -> Result<()> {
+    let source = std::fs::read_to_string(&args.script)?;
+
+    if !args.dry_run {
+        let mut engine = with_variables(RhaiEngine::new(Desktop::new()), &args.variables);
+        return Ok(engine.run(&source)?);
+    }
+
+    let mut engine = with_variables(
+        RhaiEngine::new(DryRun::new(Desktop::new())),
+        &args.variables,
+    );
+    let result = engine.run(&source);
+
+    // 出错时也输出已记录的操作，便于定位
+    for action in engine.backend().actions() {
+        println!("{action}");
+    }
+
+    Ok(result?)
+}
+
+fn with_variables<B: Backend + 'static>(
+    mut engine: RhaiEngine<B>,
+    variables: &[(String, String)],
+) -> RhaiEngine<B> {
+    for (name, value) in variables {
+        engine = engine.with_variable(name, value);
+    }
+
+    engine
+}
@@ -0,0 +1,47 @@
+use std::net::TcpListener;
+use std::time::Duration;
+
+use clap::Args;
+use winpoke::rpc::Server;
+use winpoke::script::Desktop;
+
+use crate::error::Result;
+
+#[derive(Args)]
+pub struct ServeArgs {
+    /// 监听的 TCP 地址
+    #[arg(long, default_value = "127.0.0.1:7878")]
+    tcp: String,
+
+    /// 改为在命名管道上监听，如 `winpoke` 或 `\\.\pipe\winpoke`
+    #[arg(long, conflicts_with = "tcp")]
+    pipe: Option<String>,
+
+    /// 客户端需要先用此令牌调用 `auth`
+    #[arg(long, env = "WINPOKE_TOKEN", hide_env_values = true)]
+    token: String,
+
+    /// 请求未指定 `timeout_ms` 时的超时（毫秒）
+    #[arg(long, value_name = "MS", default_value_t = 30_000)]
+    timeout: u64,
+}
+
+pub fn run(args: ServeArgs) -> Result<()> {
+    let server = Server::new(Desktop::new())
+        .with_token(args.token)
+        .with_timeout(Duration::from_millis(args.timeout));
+
+    if let Some(pipe) = args.pipe {
+        let name = match pipe.starts_with(r"\\") {
+            true => pipe,
+            false => format!(r"\\.\pipe\{pipe}"),
+        };
+        eprintln!("正在监听 {name}");
+        return Ok(server.serve_pipe(&name)?);
+    }
+
+    let listener = TcpListener::bind(&args.tcp)?;
+    eprintln!("正在监听 {}", listener.local_addr()?);
+
+    Ok(server.serve_tcp(listener)?)
+}
@@ -0,0 +1,319 @@
+mod completion;
+mod parser;
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::Args;
+use rustyline::completion::Completer;
+use rustyline::config::{CompletionType, Config};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use windows::Win32::Foundation::HWND;
+use winpoke::color::Color;
+use winpoke::prelude::*;
+use winpoke::script::{Backend, Desktop, WindowHandle};
+
+use crate::command::shell::completion::{Completion, commands, context, matching};
+use crate::command::shell::parser::{Command, Query, Target, parse};
+use crate::error::Result;
+
+/// 未赋给变量的查询结果保存到 `$_`
+const LAST_RESULT: &str = "_";
+
+/// 高亮窗口的时长
+const HIGHLIGHT_DURATION: Duration = Duration::from_millis(800);
+
+const HELP: &str = "\
+查询窗口，结果保存到变量，未指定变量时保存到 $_:
+  [$w =] find <选择器路径>      如 find RegEdit_RegEdit > SysTreeView32
+  [$w =] children <窗口>        一级子窗口
+  [$w =] parent <窗口>          父窗口
+  [$w =] at <x> <y>             屏幕坐标处的窗口
+  [$w =] cursor                 光标下的窗口
+  [$w =] <窗口>                 输出窗口的详细信息
+
+操作窗口:
+  focus <窗口>                  激活窗口
+  show <窗口>                   显示窗口
+  text <窗口>                   读取窗口文本
+  type <窗口> \"文本\"            输入文本
+  key <窗口> <按键>             按键或组合键，如 Enter、Ctrl+S
+  highlight <窗口>              高亮窗口
+  tree <窗口>                   输出子窗口树
+
+<窗口> 可以是变量 $w（第一个窗口）、$w[1] 或窗口句柄 0x1A2B。
+其它命令: vars 列出变量，help 显示帮助，exit 退出。Tab 补全命令、类名和变量。";
+
+#[derive(Args)]
+pub struct ShellArgs {
+    /// 历史记录文件，默认为用户目录下的 .winpoke_history
+    #[arg(long)]
+    history: Option<PathBuf>,
+}
+
+pub fn run(args: ShellArgs) -> Result<()> {
+    let history = args.history.or_else(|| {
+        std::env::var_os("USERPROFILE").map(|home| PathBuf::from(home).join(".winpoke_history"))
+    });
+
+    let config = Config::builder()
+        .auto_add_history(true)
+        .completion_type(CompletionType::List)
+        .build();
+    let mut editor: Editor<ShellHelper, DefaultHistory> = Editor::with_config(config)?;
+    editor.set_helper(Some(ShellHelper::default()));
+    if let Some(history) = &history {
+        // 第一次运行时文件还不存在
+        let _ = editor.load_history(history);
+    }
+
+    println!("winpoke shell，输入 help 查看帮助，exit 退出");
+    let mut shell = Shell::default();
+    loop {
+        let line = match editor.readline("winpoke> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+
+        let command = match parse(&line) {
+            Ok(Some(command)) => command,
+            Ok(None) => continue,
+            Err(e) => {
+                eprintln!("错误: {e}");
+                continue;
+            }
+        };
+        if command == Command::Exit {
+            break;
+        }
+
+        if let Err(e) = shell.execute(command) {
+            eprintln!("错误: {e}");
+        }
+        if let Some(helper) = editor.helper_mut() {
+            helper.update(&shell.variables);
+        }
+    }
+
+    if let Some(history) = &history {
+        editor.save_history(history)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct Shell {
+    variables: BTreeMap<String, Vec<WindowInfo>>,
+    desktop: Desktop,
+}
+
+impl Shell {
+    fn execute(&mut self, command: Command) -> Result<()> {
+        match command {
+            Command::Query { variable, query } => self.query(variable, query)?,
+            Command::Focus(target) => {
+                let window = handle(self.window(&target)?);
+                self.desktop.focus(&window)?;
+            }
+            Command::Show(target) => self.window(&target)?.show_window()?,
+            Command::Text(target) => println!("{:?}", self.window(&target)?.text()?),
+            Command::Type(target, text) => {
+                let window = handle(self.window(&target)?);
+                self.desktop.type_text(&window, &text)?;
+            }
+            Command::Key(target, vks) => {
+                let window = handle(self.window(&target)?);
+                self.desktop.key(&window, &vks)?;
+            }
+            Command::Highlight(target) => {
+                self.window(&target)?
+                    .highlight(HIGHLIGHT_DURATION, Color::RED)?;
+            }
+            Command::Tree(target) => print_tree(&self.window(&target)?, 0)?,
+            Command::Vars => {
+                for (name, windows) in &self.variables {
+                    match windows.as_slice() {
+                        [window] => println!("${name} = {}", describe(window)),
+                        windows => println!("${name} = {} 个窗口", windows.len()),
+                    }
+                }
+            }
+            Command::Help => println!("{HELP}"),
+            Command::Exit => {}
+        }
+
+        Ok(())
+    }
+
+    fn query(&mut self, variable: Option<String>, query: Query) -> Result<()> {
+        let detailed = matches!(
+            query,
+            Query::Parent(_) | Query::At(..) | Query::Cursor | Query::Target(_)
+        );
+        let windows = match query {
+            Query::Find(path) => WindowInfo::find_path(&path)?,
+            Query::Children(target) => match self.window(&target)?.get_child_windows() {
+                Ok(children) => children,
+                Err(Error::WindowNotFound) => Vec::new(),
+                Err(e) => return Err(e.into()),
+            },
+            Query::Parent(target) => self.window(&target)?.parent()?.into_iter().collect(),
+            Query::At(x, y) => vec![WindowInfo::from_point(x, y)?],
+            Query::Cursor => vec![WindowInfo::from_cursor()?],
+            Query::Target(target) => vec![self.window(&target)?],
+        };
+
+        let name = variable.unwrap_or_else(|| LAST_RESULT.to_string());
+        match windows.as_slice() {
+            [] => println!("没有找到窗口"),
+            [window] if detailed => print_details(window),
+            windows => {
+                for (index, window) in windows.iter().enumerate() {
+                    println!("${name}[{index}] {}", describe(window));
+                }
+            }
+        }
+        self.variables.insert(name, windows);
+
+        Ok(())
+    }
+
+    /// 重新读取窗口，变量中的窗口可能已经关闭
+    fn window(&self, target: &Target) -> Result<WindowInfo> {
+        let hwnd = match target {
+            Target::Handle(hwnd) => HWND(*hwnd as _),
+            Target::Variable { name, index } => {
+                let windows = self
+                    .variables
+                    .get(name)
+                    .ok_or_else(|| Error::Undefined(format!("${name}")))?;
+                windows
+                    .get(*index)
+                    .ok_or_else(|| Error::Undefined(format!("${name}[{index}]")))?
+                    .hwnd
+            }
+        };
+
+        Ok(WindowInfo::from_hwnd(hwnd).map_err(|_| Error::WindowNotFound)?)
+    }
+}
+
+fn handle(window: WindowInfo) -> WindowHandle {
+    WindowHandle {
+        id: window.hwnd.0 as usize,
+        class_name: window.class_name,
+        caption: window.caption,
+    }
+}
+
+fn describe(window: &WindowInfo) -> String {
+    format!(
+        "{} {:?} (0x{:X})",
+        window.class_name, window.caption, window.hwnd.0 as usize
+    )
+}
+
+fn print_details(window: &WindowInfo) {
+    let (top, right, bottom, left) = window.position;
+    let (client_top, client_right, client_bottom, client_left) = window.client_position;
+
+    println!("{}", describe(window));
+    println!("  进程    {}，线程 {}", window.pid, window.tid);
+    println!(
+        "  位置    ({left},{top})-({right},{bottom})，{}x{}",
+        right - left,
+        bottom - top
+    );
+    println!(
+        "  工作区  ({client_left},{client_top})-({client_right},{client_bottom})，{}x{}",
+        client_right - client_left,
+        client_bottom - client_top
+    );
+    println!("  样式    {}", window.style);
+    println!("  活动    {}", if window.is_active { "是" } else { "否" });
+}
+
+fn print_tree(window: &WindowInfo, depth: usize) -> Result<()> {
+    println!("{}{}", "  ".repeat(depth), describe(window));
+
+    let children = match window.get_child_windows() {
+        Ok(children) => children,
+        Err(Error::WindowNotFound) => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    for child in &children {
+        print_tree(child, depth + 1)?;
+    }
+
+    Ok(())
+}
+
+/// 补全命令、类名、变量和窗口句柄
+#[derive(Default)]
+struct ShellHelper {
+    variables: Vec<String>,
+    handles: Vec<String>,
+}
+
+impl ShellHelper {
+    fn update(&mut self, variables: &BTreeMap<String, Vec<WindowInfo>>) {
+        self.variables = variables.keys().map(|name| format!("${name}")).collect();
+        self.handles = variables
+            .values()
+            .flatten()
+            .map(|window| format!("0x{:X}", window.hwnd.0 as usize))
+            .collect();
+    }
+}
+
+impl Completer for ShellHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let (start, completion) = context(line, pos);
+        let word = &line[start..pos];
+        let variables = self.variables.iter().map(String::as_str);
+
+        let candidates = match completion {
+            Completion::Command => matching(word, commands(variables)),
+            Completion::Target => matching(
+                word,
+                variables.chain(self.handles.iter().map(String::as_str)),
+            ),
+            Completion::ClassName => {
+                let windows = WindowInfo::find(&Selector::any()).unwrap_or_default();
+                matching(
+                    word,
+                    windows.iter().map(|window| window.class_name.as_str()),
+                )
+            }
+            Completion::None => Vec::new(),
+        };
+
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
@@ -0,0 +1,114 @@
+use crate::command::shell::parser::COMMANDS;
+
+/// 光标处可以补全的内容
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Completion {
+    /// 命令名或变量（行首）
+    Command,
+
+    /// 窗口类名（`find` 的参数）
+    ClassName,
+
+    /// 变量或窗口句柄
+    Target,
+
+    /// 没有可补全的内容
+    None,
+}
+
+/// 以窗口为第一个参数的命令
+const TARGET_COMMANDS: &[&str] = &[
+    "children",
+    "parent",
+    "focus",
+    "show",
+    "text",
+    "type",
+    "key",
+    "highlight",
+    "tree",
+];
+
+/// 判断光标前的单词应补全什么，返回单词的起始位置
+pub fn context(line: &str, pos: usize) -> (usize, Completion) {
+    let before = &line[..pos];
+    let start = before
+        .char_indices()
+        .rev()
+        .find(|&(_, c)| c.is_whitespace() || c == '=' || c == '>')
+        .map_or(0, |(index, c)| index + c.len_utf8());
+
+    // 赋值的右侧视为新的一行
+    let statement = match before.split_once('=') {
+        Some((variable, rest)) if variable.trim_start().starts_with('$') => rest,
+        _ => before,
+    };
+    let words: Vec<&str> = statement.split_whitespace().collect();
+    let index = match statement.ends_with(char::is_whitespace) || words.is_empty() {
+        true => words.len(),
+        false => words.len() - 1,
+    };
+
+    let completion = match (index, words.first().copied()) {
+        (0, _) => Completion::Command,
+        (_, Some("find")) => Completion::ClassName,
+        (1, Some(command)) if TARGET_COMMANDS.contains(&command) => Completion::Target,
+        _ => Completion::None,
+    };
+
+    (start, completion)
+}
+
+/// 以 `word` 开头的候选项，已排序并去重
+pub fn matching<'a>(word: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut matched: Vec<String> = candidates
+        .into_iter()
+        .filter(|candidate| candidate.starts_with(word))
+        .map(str::to_string)
+        .collect();
+    matched.sort();
+    matched.dedup();
+
+    matched
+}
+
+/// 行首的候选项：命令名和变量
+pub fn commands<'a>(variables: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    COMMANDS.iter().copied().chain(variables).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at_end(line: &str) -> (usize, Completion) {
+        context(line, line.len())
+    }
+
+    #[test]
+    fn contexts() {
+        assert_eq!(at_end(""), (0, Completion::Command));
+        assert_eq!(at_end("fo"), (0, Completion::Command));
+        assert_eq!(at_end("$w = fi"), (5, Completion::Command));
+        assert_eq!(at_end("$w =fi"), (4, Completion::Command));
+        assert_eq!(at_end("find Reg"), (5, Completion::ClassName));
+        assert_eq!(at_end("$w = find Notepad >Ed"), (19, Completion::ClassName));
+        assert_eq!(at_end("focus "), (6, Completion::Target));
+        assert_eq!(at_end("focus $"), (6, Completion::Target));
+        assert_eq!(at_end("key 0x1"), (4, Completion::Target));
+        assert_eq!(at_end("key $w Ctr"), (7, Completion::None));
+        assert_eq!(at_end("type $w "), (8, Completion::None));
+        assert_eq!(at_end("vars "), (5, Completion::None));
+        assert_eq!(context("focus $w", 7), (6, Completion::Target));
+    }
+
+    #[test]
+    fn candidates() {
+        assert_eq!(
+            matching("$e", ["$edit", "$w", "$editor", "$edit"]),
+            ["$edit", "$editor"]
+        );
+        assert_eq!(matching("t", commands(["$t"])), ["text", "tree", "type"]);
+        assert!(commands(["$w"]).contains(&"$w"));
+    }
+}
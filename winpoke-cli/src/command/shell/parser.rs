@@ -0,0 +1,368 @@
+use winpoke::keys::parse_chord;
+use winpoke::selector::SelectorPath;
+
+/// 命令中引用的窗口
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// `$name` 或 `$name[index]`，省略下标时为第一个窗口
+    Variable { name: String, index: usize },
+
+    /// 窗口句柄，如 `0x1A2B` 或 `6699`
+    Handle(usize),
+}
+
+/// 返回窗口的表达式，结果可以赋给变量
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    /// `find 选择器路径`
+    Find(SelectorPath),
+
+    /// `children 窗口`
+    Children(Target),
+
+    /// `parent 窗口`
+    Parent(Target),
+
+    /// `at x y`，屏幕坐标处的窗口
+    At(i32, i32),
+
+    /// `cursor`，光标下的窗口
+    Cursor,
+
+    /// 直接写出的窗口，输出其详细信息
+    Target(Target),
+}
+
+/// 一行命令
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// 查询窗口，带 `$name =` 时保存到变量
+    Query {
+        variable: Option<String>,
+        query: Query,
+    },
+    Focus(Target),
+    Show(Target),
+    Text(Target),
+    Type(Target, String),
+    Key(Target, Vec<u32>),
+    Highlight(Target),
+    Tree(Target),
+    Vars,
+    Help,
+    Exit,
+}
+
+/// 行首可以使用的命令
+pub const COMMANDS: &[&str] = &[
+    "find",
+    "children",
+    "parent",
+    "at",
+    "cursor",
+    "focus",
+    "show",
+    "text",
+    "type",
+    "key",
+    "highlight",
+    "tree",
+    "vars",
+    "help",
+    "exit",
+    "quit",
+];
+
+/// 解析一行命令，空行和 `#` 开头的注释返回 `None`
+pub fn parse(line: &str) -> Result<Option<Command>, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+
+    if let Some((variable, query)) = assignment(line) {
+        let name = variable_name(variable)?;
+        let query = match self::query(query)? {
+            Some(query) => query,
+            None => Query::Target(target(query)?),
+        };
+        return Ok(Some(Command::Query {
+            variable: Some(name),
+            query,
+        }));
+    }
+
+    if let Some(query) = query(line)? {
+        return Ok(Some(Command::Query {
+            variable: None,
+            query,
+        }));
+    }
+
+    let (word, rest) = split_word(line);
+    let command = match word {
+        "focus" => Command::Focus(only_target(rest)?),
+        "show" => Command::Show(only_target(rest)?),
+        "text" => Command::Text(only_target(rest)?),
+        "highlight" => Command::Highlight(only_target(rest)?),
+        "tree" => Command::Tree(only_target(rest)?),
+        "type" => {
+            let (target, text) = split_word(rest);
+            Command::Type(self::target(target)?, unquote(text)?)
+        }
+        "key" => {
+            let (target, chord) = split_word(rest);
+            let vks = parse_chord(chord).ok_or_else(|| format!("无效的按键 {chord:?}"))?;
+            Command::Key(self::target(target)?, vks)
+        }
+        "vars" | "help" | "exit" | "quit" if !rest.is_empty() => {
+            return Err(format!("{word} 不需要参数"));
+        }
+        "vars" => Command::Vars,
+        "help" => Command::Help,
+        "exit" | "quit" => Command::Exit,
+        _ if word.starts_with('$') || word.starts_with(|c: char| c.is_ascii_digit()) => {
+            Command::Query {
+                variable: None,
+                query: Query::Target(only_target(line)?),
+            }
+        }
+        _ => return Err(format!("未知的命令 {word}，输入 help 查看帮助")),
+    };
+
+    Ok(Some(command))
+}
+
+/// 拆分 `$name = ...`
+fn assignment(line: &str) -> Option<(&str, &str)> {
+    let (left, right) = line.split_once('=')?;
+    let left = left.trim();
+
+    (left.starts_with('$') && !left.contains(char::is_whitespace)).then(|| (left, right.trim()))
+}
+
+fn query(line: &str) -> Result<Option<Query>, String> {
+    let (word, rest) = split_word(line);
+    let query = match word {
+        "find" if rest.is_empty() => return Err("find 需要选择器路径".to_string()),
+        "find" => Query::Find(rest.parse().map_err(|e| format!("{e}"))?),
+        "children" => Query::Children(only_target(rest)?),
+        "parent" => Query::Parent(only_target(rest)?),
+        "at" => {
+            let coordinates: Vec<i32> = rest
+                .split_whitespace()
+                .map(|n| n.parse().map_err(|_| format!("无效的坐标 {n}")))
+                .collect::<Result<_, _>>()?;
+            let [x, y] = coordinates[..] else {
+                return Err("at 需要两个坐标，如 at 100 200".to_string());
+            };
+            Query::At(x, y)
+        }
+        "cursor" if rest.is_empty() => Query::Cursor,
+        "cursor" => return Err("cursor 不需要参数".to_string()),
+        _ => return Ok(None),
+    };
+
+    Ok(Some(query))
+}
+
+fn only_target(s: &str) -> Result<Target, String> {
+    let (target, rest) = split_word(s);
+    if !rest.is_empty() {
+        return Err(format!("多余的参数 {rest}"));
+    }
+
+    self::target(target)
+}
+
+fn target(s: &str) -> Result<Target, String> {
+    if s.is_empty() {
+        return Err("缺少窗口，如 $w、$w[1] 或 0x1A2B".to_string());
+    }
+
+    if s.starts_with('$') {
+        let (name, index) = match s.split_once('[') {
+            Some((name, index)) => {
+                let index = index
+                    .strip_suffix(']')
+                    .and_then(|index| index.parse().ok())
+                    .ok_or_else(|| format!("无效的下标 {s}"))?;
+                (name, index)
+            }
+            None => (s, 0),
+        };
+        return Ok(Target::Variable {
+            name: variable_name(name)?,
+            index,
+        });
+    }
+
+    let handle = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => s.parse(),
+    };
+    handle
+        .map(Target::Handle)
+        .map_err(|_| format!("无效的窗口 {s}"))
+}
+
+/// 去掉 `$` 的变量名，只能包含字母、数字和下划线
+fn variable_name(s: &str) -> Result<String, String> {
+    let name = s.strip_prefix('$').unwrap_or(s);
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+
+    match valid {
+        true => Ok(name.to_string()),
+        false => Err(format!("无效的变量名 {s}")),
+    }
+}
+
+/// 第一个单词和其余部分
+fn split_word(s: &str) -> (&str, &str) {
+    let s = s.trim_start();
+    match s.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim()),
+        None => (s, ""),
+    }
+}
+
+/// 去掉两端的双引号并处理 `\"`、`\\`、`\n`、`\t`，没有引号时原样返回
+fn unquote(s: &str) -> Result<String, String> {
+    let Some(inner) = s.strip_prefix('"') else {
+        return Ok(s.to_string());
+    };
+    let inner = inner
+        .strip_suffix('"')
+        .ok_or_else(|| format!("缺少结尾的引号: {s}"))?;
+
+    let mut text = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        text.push(match c {
+            '\\' => match chars.next() {
+                Some('n') => '\n',
+                Some('t') => '\t',
+                Some(c @ ('"' | '\\')) => c,
+                Some(c) => return Err(format!("未知的转义 \\{c}")),
+                None => return Err(format!("缺少结尾的引号: {s}")),
+            },
+            '"' => return Err(format!("引号中的 \" 需要写成 \\\": {s}")),
+            c => c,
+        });
+    }
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variable(name: &str, index: usize) -> Target {
+        Target::Variable {
+            name: name.to_string(),
+            index,
+        }
+    }
+
+    #[test]
+    fn queries() {
+        assert_eq!(
+            parse("$w = find RegEdit_RegEdit > SysTreeView32").unwrap(),
+            Some(Command::Query {
+                variable: Some("w".to_string()),
+                query: Query::Find("RegEdit_RegEdit > SysTreeView32".parse().unwrap()),
+            })
+        );
+        assert_eq!(
+            parse("find #32770[caption=\"a = b\"]").unwrap(),
+            Some(Command::Query {
+                variable: None,
+                query: Query::Find("#32770[caption=\"a = b\"]".parse().unwrap()),
+            })
+        );
+        assert_eq!(
+            parse("$c=children $w[2]").unwrap(),
+            Some(Command::Query {
+                variable: Some("c".to_string()),
+                query: Query::Children(variable("w", 2)),
+            })
+        );
+        assert_eq!(
+            parse("$p = 0x1A2B").unwrap(),
+            Some(Command::Query {
+                variable: Some("p".to_string()),
+                query: Query::Target(Target::Handle(0x1A2B)),
+            })
+        );
+        assert_eq!(
+            parse("at 100 -20").unwrap(),
+            Some(Command::Query {
+                variable: None,
+                query: Query::At(100, -20),
+            })
+        );
+        assert_eq!(
+            parse("$w").unwrap(),
+            Some(Command::Query {
+                variable: None,
+                query: Query::Target(variable("w", 0)),
+            })
+        );
+    }
+
+    #[test]
+    fn actions() {
+        assert_eq!(
+            parse("focus $w").unwrap(),
+            Some(Command::Focus(variable("w", 0)))
+        );
+        assert_eq!(
+            parse(r#"type $edit "say \"hi\"\n""#).unwrap(),
+            Some(Command::Type(
+                variable("edit", 0),
+                "say \"hi\"\n".to_string()
+            ))
+        );
+        assert_eq!(
+            parse("type 6699 hello world").unwrap(),
+            Some(Command::Type(
+                Target::Handle(6699),
+                "hello world".to_string()
+            ))
+        );
+        assert_eq!(
+            parse("key $w Ctrl+S").unwrap(),
+            Some(Command::Key(variable("w", 0), vec![0x11, 0x53]))
+        );
+        assert_eq!(parse("  quit ").unwrap(), Some(Command::Exit));
+        assert_eq!(parse("# 注释").unwrap(), None);
+        assert_eq!(parse("").unwrap(), None);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            parse("open $w").unwrap_err(),
+            "未知的命令 open，输入 help 查看帮助"
+        );
+        assert_eq!(parse("find").unwrap_err(), "find 需要选择器路径");
+        assert_eq!(
+            parse("focus").unwrap_err(),
+            "缺少窗口，如 $w、$w[1] 或 0x1A2B"
+        );
+        assert_eq!(parse("focus $w $v").unwrap_err(), "多余的参数 $v");
+        assert_eq!(parse("focus $w[x]").unwrap_err(), "无效的下标 $w[x]");
+        assert_eq!(parse("$a-b = cursor").unwrap_err(), "无效的变量名 $a-b");
+        assert_eq!(parse("at 1").unwrap_err(), "at 需要两个坐标，如 at 100 200");
+        assert_eq!(
+            parse("key $w Ctrl+Nope").unwrap_err(),
+            "无效的按键 \"Ctrl+Nope\""
+        );
+        assert_eq!(
+            parse(r#"type $w "abc"#).unwrap_err(),
+            "缺少结尾的引号: \"abc"
+        );
+        assert!(parse("vars now").is_err());
+    }
+}
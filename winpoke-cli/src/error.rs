@@ -0,0 +1,15 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Winpoke(#[from] winpoke::error::Error),
+    #[error("读写文件失败: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("配置文件格式错误: {0}")]
+    Config(#[from] toml::de::Error),
+    #[error("读取输入失败: {0}")]
+    Readline(#[from] rustyline::error::ReadlineError),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -1,3 +1,40 @@
-fn main() {
-    println!("Hello winpoke-cli");
+#[cfg(windows)]
+mod command;
+#[cfg(windows)]
+mod error;
+
+use std::process::ExitCode;
+
+#[cfg(windows)]
+use clap::Parser;
+
+#[cfg(windows)]
+use crate::command::Command;
+
+/// winpoke 命令行工具
+#[cfg(windows)]
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[cfg(windows)]
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match cli.command.run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("错误: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn main() -> ExitCode {
+    eprintln!("错误: winpoke 仅支持 Windows");
+    ExitCode::FAILURE
 }
@@ -0,0 +1,252 @@
+/* winpoke 的 C 接口，由 cbindgen 根据 winpoke-ffi 生成，请勿手动修改 */
+
+#ifndef WINPOKE_H
+#define WINPOKE_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * 按键，`param1` 为虚拟键码
+ */
+#define WINPOKE_MESSAGE_KEY_DOWN 0
+
+/**
+ * 鼠标移动，`param1`、`param2` 为工作区坐标
+ */
+#define WINPOKE_MESSAGE_MOUSE_MOVE 1
+
+/**
+ * 字符，`param1` 为 Unicode 码位
+ */
+#define WINPOKE_MESSAGE_CHAR 2
+
+/**
+ * 菜单或按钮命令，`param1` 为命令 ID
+ */
+#define WINPOKE_MESSAGE_COMMAND 3
+
+/**
+ * 函数的返回状态
+ */
+typedef enum WinpokeStatus {
+  WINPOKE_STATUS_OK = 0,
+  /**
+   * 参数为空指针、不是 UTF-8，或选择器等格式错误
+   */
+  WINPOKE_STATUS_INVALID_ARGUMENT = 1,
+  /**
+   * 找不到窗口、菜单项等
+   */
+  WINPOKE_STATUS_NOT_FOUND = 2,
+  /**
+   * 等待窗口或消息响应超时
+   */
+  WINPOKE_STATUS_TIMEOUT = 3,
+  /**
+   * 目标程序以更高权限运行
+   */
+  WINPOKE_STATUS_ACCESS_DENIED = 4,
+  /**
+   * 输出缓冲区太小，所需大小已写入长度参数
+   */
+  WINPOKE_STATUS_BUFFER_TOO_SMALL = 5,
+  /**
+   * 当前平台不支持
+   */
+  WINPOKE_STATUS_UNSUPPORTED = 6,
+  /**
+   * 其它错误
+   */
+  WINPOKE_STATUS_FAILED = 7,
+  /**
+   * 内部错误（Rust panic）
+   */
+  WINPOKE_STATUS_PANIC = 8,
+} WinpokeStatus;
+
+/**
+ * 窗口（不透明类型），由 [`winpoke_window_free`] 释放
+ *
+ * 保存查找时的窗口信息，操作窗口时按句柄重新读取。
+ */
+typedef struct WinpokeWindow WinpokeWindow;
+
+/**
+ * 窗口列表（不透明类型），由 [`winpoke_window_list_free`] 释放
+ */
+typedef struct WinpokeWindowList WinpokeWindowList;
+
+/**
+ * 屏幕坐标
+ */
+typedef struct WinpokeRect {
+  int32_t left;
+  int32_t top;
+  int32_t right;
+  int32_t bottom;
+} WinpokeRect;
+
+/**
+ * 查找窗口时的窗口信息
+ */
+typedef struct WinpokeWindowInfo {
+  /**
+   * 窗口句柄（`HWND`）
+   */
+  void *hwnd;
+  uint32_t pid;
+  uint32_t tid;
+  struct WinpokeRect rect;
+  struct WinpokeRect client_rect;
+  /**
+   * `WS_*` 样式
+   */
+  uint32_t style;
+  /**
+   * `WS_EX_*` 扩展样式
+   */
+  uint32_t ex_style;
+  bool is_active;
+  bool is_maximized;
+} WinpokeWindowInfo;
+
+/**
+ * 发送到窗口的消息
+ */
+typedef struct WinpokeMessage {
+  /**
+   * `WINPOKE_MESSAGE_*` 之一
+   */
+  uint32_t kind;
+  int32_t param1;
+  int32_t param2;
+  /**
+   * 重复次数，0 视为 1
+   */
+  uint32_t count;
+} WinpokeMessage;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * 当前线程上一次调用的错误说明，上一次调用成功时返回 `NULL`
+ *
+ * 返回的字符串在当前线程下一次调用 winpoke 函数前有效。
+ */
+const char *winpoke_last_error(void);
+
+/**
+ * 枚举所有顶层窗口
+ */
+enum WinpokeStatus winpoke_enum_windows(struct WinpokeWindowList **out);
+
+/**
+ * 查找指定类名的顶层窗口
+ */
+enum WinpokeStatus winpoke_find_by_class_name(const char *class_name,
+                                              struct WinpokeWindowList **out);
+
+/**
+ * 按选择器路径查找窗口，如 `Notepad > Edit`
+ */
+enum WinpokeStatus winpoke_find(const char *path, struct WinpokeWindowList **out);
+
+/**
+ * 一级子窗口，没有子窗口时输出空列表
+ */
+enum WinpokeStatus winpoke_window_children(const struct WinpokeWindow *window,
+                                           struct WinpokeWindowList **out);
+
+/**
+ * 指定类名的一级子窗口，没有时输出空列表
+ */
+enum WinpokeStatus winpoke_window_children_with_class_name(const struct WinpokeWindow *window,
+                                                           const char *class_name,
+                                                           struct WinpokeWindowList **out);
+
+/**
+ * 读取窗口句柄对应的窗口
+ */
+enum WinpokeStatus winpoke_window_from_hwnd(void *hwnd, struct WinpokeWindow **out);
+
+/**
+ * 列表中的窗口数，`list` 为空时返回 0
+ */
+size_t winpoke_window_list_len(const struct WinpokeWindowList *list);
+
+/**
+ * 复制列表中的第 `index` 个窗口，输出的窗口需要单独释放
+ */
+enum WinpokeStatus winpoke_window_list_get(const struct WinpokeWindowList *list,
+                                           size_t index,
+                                           struct WinpokeWindow **out);
+
+/**
+ * 释放窗口列表，`list` 可以为空
+ */
+void winpoke_window_list_free(struct WinpokeWindowList *list);
+
+/**
+ * 释放窗口，`window` 可以为空
+ */
+void winpoke_window_free(struct WinpokeWindow *window);
+
+/**
+ * 查找时的窗口信息
+ */
+enum WinpokeStatus winpoke_window_info(const struct WinpokeWindow *window,
+                                       struct WinpokeWindowInfo *out);
+
+/**
+ * 查找时的窗口标题
+ *
+ * 所需的字节数（含结尾的 NUL）写入 `out_len`，缓冲区不够时返回
+ * `WINPOKE_STATUS_BUFFER_TOO_SMALL`，可以先传入空缓冲区查询大小。
+ */
+enum WinpokeStatus winpoke_window_caption(const struct WinpokeWindow *window,
+                                          char *buf,
+                                          size_t buf_len,
+                                          size_t *out_len);
+
+/**
+ * 窗口类名，缓冲区的用法同 [`winpoke_window_caption`]
+ */
+enum WinpokeStatus winpoke_window_class_name(const struct WinpokeWindow *window,
+                                             char *buf,
+                                             size_t buf_len,
+                                             size_t *out_len);
+
+/**
+ * 通过 `WM_GETTEXT` 读取窗口当前的文本，缓冲区的用法同 [`winpoke_window_caption`]
+ */
+enum WinpokeStatus winpoke_window_text(const struct WinpokeWindow *window,
+                                       char *buf,
+                                       size_t buf_len,
+                                       size_t *out_len);
+
+/**
+ * 显示窗口
+ */
+enum WinpokeStatus winpoke_window_show(const struct WinpokeWindow *window);
+
+/**
+ * 设置为前台窗口并获取焦点
+ */
+enum WinpokeStatus winpoke_window_focus(const struct WinpokeWindow *window);
+
+/**
+ * 按顺序发送 `len` 条消息，有无效消息时一条也不发送
+ */
+enum WinpokeStatus winpoke_window_send(const struct WinpokeWindow *window,
+                                       const struct WinpokeMessage *messages,
+                                       size_t len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* WINPOKE_H */
@@ -0,0 +1,172 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::{CString, c_char};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use thiserror::Error;
+use winpoke::error::Error as WinpokeError;
+
+/// 函数的返回状态
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WinpokeStatus {
+    Ok = 0,
+    /// 参数为空指针、不是 UTF-8，或选择器等格式错误
+    InvalidArgument = 1,
+    /// 找不到窗口、菜单项等
+    NotFound = 2,
+    /// 等待窗口或消息响应超时
+    Timeout = 3,
+    /// 目标程序以更高权限运行
+    AccessDenied = 4,
+    /// 输出缓冲区太小，所需大小已写入长度参数
+    BufferTooSmall = 5,
+    /// 当前平台不支持
+    Unsupported = 6,
+    /// 其它错误
+    Failed = 7,
+    /// 内部错误（Rust panic）
+    Panic = 8,
+}
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Winpoke(#[from] WinpokeError),
+    #[error("参数 {0} 为空指针")]
+    NullPointer(&'static str),
+    #[error("参数 {0} 不是有效的 UTF-8 字符串")]
+    InvalidUtf8(&'static str),
+    #[error("索引 {index} 超出范围，共 {len} 项")]
+    IndexOutOfRange { index: usize, len: usize },
+    #[error("无效的消息: {0}")]
+    InvalidMessage(String),
+    #[error("缓冲区太小，需要 {0} 字节")]
+    BufferTooSmall(usize),
+    #[error("winpoke 仅支持 Windows")]
+    Unsupported,
+    #[error("内部错误: {0}")]
+    Panic(String),
+}
+
+impl Error {
+    pub fn status(&self) -> WinpokeStatus {
+        match self {
+            Error::Winpoke(e) => match e {
+                WinpokeError::WindowNotFound
+                | WinpokeError::NoMoreChildWindow
+                | WinpokeError::TreeItemNotFound(_)
+                | WinpokeError::ListItemNotFound(_)
+                | WinpokeError::MenuNotFound
+                | WinpokeError::MenuItemNotFound(_)
+                | WinpokeError::ButtonNotFound(_) => WinpokeStatus::NotFound,
+                WinpokeError::InvalidSelector(_)
+                | WinpokeError::InvalidHotkey(_)
+                | WinpokeError::InvalidRegistryPath(_)
+                | WinpokeError::InvalidPattern(_)
+                | WinpokeError::InvalidColor(_)
+                | WinpokeError::ClassMismatch { .. } => WinpokeStatus::InvalidArgument,
+                WinpokeError::MessageTimeout | WinpokeError::WaitTimeout(_) => {
+                    WinpokeStatus::Timeout
+                }
+                WinpokeError::ElevationRequired | WinpokeError::SendInputFailed => {
+                    WinpokeStatus::AccessDenied
+                }
+                _ => WinpokeStatus::Failed,
+            },
+            Error::NullPointer(_)
+            | Error::InvalidUtf8(_)
+            | Error::IndexOutOfRange { .. }
+            | Error::InvalidMessage(_) => WinpokeStatus::InvalidArgument,
+            Error::BufferTooSmall(_) => WinpokeStatus::BufferTooSmall,
+            Error::Unsupported => WinpokeStatus::Unsupported,
+            Error::Panic(_) => WinpokeStatus::Panic,
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// 执行函数体并转换为返回状态，错误说明保存到当前线程，panic 不会越过 C 边界
+pub(crate) fn call(f: impl FnOnce() -> Result<()>) -> WinpokeStatus {
+    let result = catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or_else(|payload| Err(Error::Panic(panic_message(payload))));
+
+    let (status, message) = match result {
+        Ok(()) => (WinpokeStatus::Ok, None),
+        Err(e) => (e.status(), Some(e.to_string())),
+    };
+    // C 字符串不能包含 NUL，错误说明中出现时截断
+    let message = message.map(|m| {
+        CString::new(m).unwrap_or_else(|e| {
+            let end = e.nul_position();
+            CString::new(&e.into_vec()[..end]).unwrap()
+        })
+    });
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+
+    status
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .unwrap_or_default(),
+    }
+}
+
+/// 当前线程上一次调用的错误说明，上一次调用成功时返回 `NULL`
+///
+/// 返回的字符串在当前线程下一次调用 winpoke 函数前有效。
+#[unsafe(no_mangle)]
+pub extern "C" fn winpoke_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+
+    use super::*;
+
+    fn last_error() -> Option<String> {
+        let message = winpoke_last_error();
+        (!message.is_null()).then(|| {
+            unsafe { CStr::from_ptr(message) }
+                .to_string_lossy()
+                .into_owned()
+        })
+    }
+
+    #[test]
+    fn status_and_last_error() {
+        let status = call(|| Err(WinpokeError::MenuItemNotFound("文件 > 打开".into()).into()));
+        assert_eq!(status, WinpokeStatus::NotFound);
+        assert_eq!(last_error().unwrap(), "找不到菜单项: 文件 > 打开");
+
+        assert_eq!(call(|| Ok(())), WinpokeStatus::Ok);
+        assert_eq!(last_error(), None);
+
+        let status = call(|| Err(Error::InvalidMessage("a\0b".into())));
+        assert_eq!(status, WinpokeStatus::InvalidArgument);
+        assert_eq!(last_error().unwrap(), "无效的消息: a");
+    }
+
+    #[test]
+    fn panics_are_caught() {
+        let status = call(|| panic!("boom {}", 1));
+        assert_eq!(status, WinpokeStatus::Panic);
+        assert_eq!(last_error().unwrap(), "内部错误: boom 1");
+    }
+}
@@ -0,0 +1,20 @@
+//! winpoke 的 C 接口
+//!
+//! 头文件 `include/winpoke.h` 由 cbindgen 生成并提交到仓库，测试会检查它是否过期，修改接口后用
+//! `WINPOKE_UPDATE_HEADER=1 cargo test -p winpoke-ffi --test header` 更新。约定：
+//!
+//! - 函数返回 [`WinpokeStatus`]，结果通过最后的指针参数输出，失败时不会写入输出参数；
+//! - 失败后可以用 [`winpoke_last_error`] 读取当前线程上一次调用的错误说明；
+//! - [`WinpokeWindow`] 和 [`WinpokeWindowList`] 是不透明类型，由对应的 `_free` 函数释放；
+//! - 字符串参数和输出均为 UTF-8，以 NUL 结尾；
+//! - 窗口操作仅在 Windows 上可用，其它平台返回 `WINPOKE_STATUS_UNSUPPORTED`。
+#![allow(clippy::missing_safety_doc)]
+
+mod error;
+mod message;
+mod ptr;
+mod window;
+
+pub use error::{WinpokeStatus, winpoke_last_error};
+pub use message::*;
+pub use window::*;
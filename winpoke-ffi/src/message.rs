@@ -0,0 +1,80 @@
+use winpoke::message::{Message, WindowMessage};
+
+use crate::error::{Error, Result};
+
+/// 按键，`param1` 为虚拟键码
+pub const WINPOKE_MESSAGE_KEY_DOWN: u32 = 0;
+
+/// 鼠标移动，`param1`、`param2` 为工作区坐标
+pub const WINPOKE_MESSAGE_MOUSE_MOVE: u32 = 1;
+
+/// 字符，`param1` 为 Unicode 码位
+pub const WINPOKE_MESSAGE_CHAR: u32 = 2;
+
+/// 菜单或按钮命令，`param1` 为命令 ID
+pub const WINPOKE_MESSAGE_COMMAND: u32 = 3;
+
+/// 发送到窗口的消息
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WinpokeMessage {
+    /// `WINPOKE_MESSAGE_*` 之一
+    pub kind: u32,
+    pub param1: i32,
+    pub param2: i32,
+    /// 重复次数，0 视为 1
+    pub count: u32,
+}
+
+impl TryFrom<&WinpokeMessage> for Message {
+    type Error = Error;
+
+    fn try_from(message: &WinpokeMessage) -> Result<Self> {
+        let msg = match message.kind {
+            WINPOKE_MESSAGE_KEY_DOWN => WindowMessage::KeyDown(message.param1 as u32),
+            WINPOKE_MESSAGE_MOUSE_MOVE => WindowMessage::MouseMove(message.param1, message.param2),
+            WINPOKE_MESSAGE_CHAR => {
+                WindowMessage::Char(char::from_u32(message.param1 as u32).ok_or_else(|| {
+                    Error::InvalidMessage(format!("无效的字符 {:#x}", message.param1))
+                })?)
+            }
+            WINPOKE_MESSAGE_COMMAND => WindowMessage::Command(message.param1 as u32),
+            kind => return Err(Error::InvalidMessage(format!("未知的消息类型 {kind}"))),
+        };
+
+        Ok(Message {
+            msg,
+            count: message.count.max(1),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(kind: u32, param1: i32, param2: i32) -> WinpokeMessage {
+        WinpokeMessage {
+            kind,
+            param1,
+            param2,
+            count: 0,
+        }
+    }
+
+    #[test]
+    fn convert() {
+        let converted = Message::try_from(&message(WINPOKE_MESSAGE_CHAR, '好' as i32, 0)).unwrap();
+        assert_eq!(converted.msg, WindowMessage::Char('好'));
+        assert_eq!(converted.count, 1);
+
+        let converted = Message::try_from(&message(WINPOKE_MESSAGE_MOUSE_MOVE, 3, -4)).unwrap();
+        assert_eq!(converted.msg, WindowMessage::MouseMove(3, -4));
+
+        let error = Message::try_from(&message(WINPOKE_MESSAGE_CHAR, 0xD800, 0)).unwrap_err();
+        assert_eq!(error.to_string(), "无效的消息: 无效的字符 0xd800");
+
+        let error = Message::try_from(&message(9, 0, 0)).unwrap_err();
+        assert_eq!(error.to_string(), "无效的消息: 未知的消息类型 9");
+    }
+}
@@ -0,0 +1,73 @@
+//! 检查和转换 C 传入的指针
+
+use std::ffi::{CStr, c_char};
+
+use crate::error::{Error, Result};
+
+/// 借用 C 字符串参数
+pub(crate) unsafe fn str_arg<'a>(ptr: *const c_char, name: &'static str) -> Result<&'a str> {
+    if ptr.is_null() {
+        return Err(Error::NullPointer(name));
+    }
+
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map_err(|_| Error::InvalidUtf8(name))
+}
+
+/// 借用对象参数
+pub(crate) unsafe fn ref_arg<'a, T>(ptr: *const T, name: &'static str) -> Result<&'a T> {
+    unsafe { ptr.as_ref() }.ok_or(Error::NullPointer(name))
+}
+
+/// 借用数组参数，长度为 0 时允许空指针
+pub(crate) unsafe fn slice_arg<'a, T>(
+    ptr: *const T,
+    len: usize,
+    name: &'static str,
+) -> Result<&'a [T]> {
+    match (ptr.is_null(), len) {
+        (_, 0) => Ok(&[]),
+        (true, _) => Err(Error::NullPointer(name)),
+        (false, _) => Ok(unsafe { std::slice::from_raw_parts(ptr, len) }),
+    }
+}
+
+/// 检查输出参数，在产生结果前调用，避免结果被丢弃
+pub(crate) fn out_arg<T>(ptr: *mut T, name: &'static str) -> Result<*mut T> {
+    match ptr.is_null() {
+        true => Err(Error::NullPointer(name)),
+        false => Ok(ptr),
+    }
+}
+
+/// 把结果移到堆上，通过输出参数交给调用方
+pub(crate) unsafe fn write_boxed<T>(out: *mut *mut T, value: T) {
+    unsafe { out.write(Box::into_raw(Box::new(value))) };
+}
+
+/// 把字符串写入调用方的缓冲区（含结尾的 NUL）
+///
+/// 所需的字节数总是写入 `out_len`（可以为空），缓冲区不够时返回 [`Error::BufferTooSmall`]。
+/// 可以先传入空缓冲区查询大小。
+pub(crate) unsafe fn write_str(
+    s: &str,
+    buf: *mut c_char,
+    buf_len: usize,
+    out_len: *mut usize,
+) -> Result<()> {
+    let needed = s.len() + 1;
+    if !out_len.is_null() {
+        unsafe { out_len.write(needed) };
+    }
+    if buf.is_null() || buf_len < needed {
+        return Err(Error::BufferTooSmall(needed));
+    }
+
+    unsafe {
+        std::ptr::copy_nonoverlapping(s.as_ptr(), buf.cast(), s.len());
+        buf.add(s.len()).write(0);
+    }
+
+    Ok(())
+}
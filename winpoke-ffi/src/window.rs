@@ -0,0 +1,450 @@
+#[cfg(windows)]
+mod desktop;
+#[cfg(not(windows))]
+mod unsupported;
+
+use std::ffi::{c_char, c_void};
+
+use winpoke::message::Message;
+use winpoke::selector::SelectorPath;
+
+use crate::error::{Error, Result, WinpokeStatus, call};
+use crate::message::WinpokeMessage;
+use crate::ptr::{out_arg, ref_arg, slice_arg, str_arg, write_boxed, write_str};
+#[cfg(windows)]
+use desktop as sys;
+#[cfg(not(windows))]
+use unsupported as sys;
+
+/// 屏幕坐标
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WinpokeRect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+/// 查找窗口时的窗口信息
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WinpokeWindowInfo {
+    /// 窗口句柄（`HWND`）
+    pub hwnd: *mut c_void,
+    pub pid: u32,
+    pub tid: u32,
+    pub rect: WinpokeRect,
+    pub client_rect: WinpokeRect,
+    /// `WS_*` 样式
+    pub style: u32,
+    /// `WS_EX_*` 扩展样式
+    pub ex_style: u32,
+    pub is_active: bool,
+    pub is_maximized: bool,
+}
+
+/// 窗口（不透明类型），由 [`winpoke_window_free`] 释放
+///
+/// 保存查找时的窗口信息，操作窗口时按句柄重新读取。
+#[derive(Debug, Clone)]
+pub struct WinpokeWindow {
+    pub(crate) info: WinpokeWindowInfo,
+    pub(crate) caption: String,
+    pub(crate) class_name: String,
+}
+
+/// 窗口列表（不透明类型），由 [`winpoke_window_list_free`] 释放
+#[derive(Debug, Default)]
+pub struct WinpokeWindowList(pub(crate) Vec<WinpokeWindow>);
+
+/// 执行查找并输出窗口列表
+fn find(
+    out: *mut *mut WinpokeWindowList,
+    find: impl FnOnce() -> Result<Vec<WinpokeWindow>>,
+) -> WinpokeStatus {
+    call(|| {
+        let out = out_arg(out, "out")?;
+        let windows = find()?;
+        unsafe { write_boxed(out, WinpokeWindowList(windows)) };
+        Ok(())
+    })
+}
+
+/// 枚举所有顶层窗口
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn winpoke_enum_windows(out: *mut *mut WinpokeWindowList) -> WinpokeStatus {
+    find(out, sys::top_level)
+}
+
+/// 查找指定类名的顶层窗口
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn winpoke_find_by_class_name(
+    class_name: *const c_char,
+    out: *mut *mut WinpokeWindowList,
+) -> WinpokeStatus {
+    find(out, || {
+        sys::find_by_class_name(unsafe { str_arg(class_name, "class_name") }?)
+    })
+}
+
+/// 按选择器路径查找窗口，如 `Notepad > Edit`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn winpoke_find(
+    path: *const c_char,
+    out: *mut *mut WinpokeWindowList,
+) -> WinpokeStatus {
+    find(out, || {
+        let path: SelectorPath = unsafe { str_arg(path, "path") }?.parse()?;
+        sys::find_path(&path)
+    })
+}
+
+/// 一级子窗口，没有子窗口时输出空列表
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn winpoke_window_children(
+    window: *const WinpokeWindow,
+    out: *mut *mut WinpokeWindowList,
+) -> WinpokeStatus {
+    find(out, || {
+        sys::children(unsafe { ref_arg(window, "window") }?, None)
+    })
+}
+
+/// 指定类名的一级子窗口，没有时输出空列表
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn winpoke_window_children_with_class_name(
+    window: *const WinpokeWindow,
+    class_name: *const c_char,
+    out: *mut *mut WinpokeWindowList,
+) -> WinpokeStatus {
+    find(out, || {
+        let window = unsafe { ref_arg(window, "window") }?;
+        let class_name = unsafe { str_arg(class_name, "class_name") }?;
+        sys::children(window, Some(class_name))
+    })
+}
+
+/// 读取窗口句柄对应的窗口
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn winpoke_window_from_hwnd(
+    hwnd: *mut c_void,
+    out: *mut *mut WinpokeWindow,
+) -> WinpokeStatus {
+    call(|| {
+        let out = out_arg(out, "out")?;
+        let window = sys::from_hwnd(hwnd)?;
+        unsafe { write_boxed(out, window) };
+        Ok(())
+    })
+}
+
+/// 列表中的窗口数，`list` 为空时返回 0
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn winpoke_window_list_len(list: *const WinpokeWindowList) -> usize {
+    unsafe { list.as_ref() }.map_or(0, |list| list.0.len())
+}
+
+/// 复制列表中的第 `index` 个窗口，输出的窗口需要单独释放
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn winpoke_window_list_get(
+    list: *const WinpokeWindowList,
+    index: usize,
+    out: *mut *mut WinpokeWindow,
+) -> WinpokeStatus {
+    call(|| {
+        let list = unsafe { ref_arg(list, "list") }?;
+        let out = out_arg(out, "out")?;
+        let window = list.0.get(index).ok_or(Error::IndexOutOfRange {
+            index,
+            len: list.0.len(),
+        })?;
+        unsafe { write_boxed(out, window.clone()) };
+        Ok(())
+    })
+}
+
+/// 释放窗口列表，`list` 可以为空
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn winpoke_window_list_free(list: *mut WinpokeWindowList) {
+    if !list.is_null() {
+        drop(unsafe { Box::from_raw(list) });
+    }
+}
+
+/// 释放窗口，`window` 可以为空
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn winpoke_window_free(window: *mut WinpokeWindow) {
+    if !window.is_null() {
+        drop(unsafe { Box::from_raw(window) });
+    }
+}
+
+/// 查找时的窗口信息
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn winpoke_window_info(
+    window: *const WinpokeWindow,
+    out: *mut WinpokeWindowInfo,
+) -> WinpokeStatus {
+    call(|| {
+        let window = unsafe { ref_arg(window, "window") }?;
+        let out = out_arg(out, "out")?;
+        unsafe { out.write(window.info) };
+        Ok(())
+    })
+}
+
+/// 查找时的窗口标题
+///
+/// 所需的字节数（含结尾的 NUL）写入 `out_len`，缓冲区不够时返回
+/// `WINPOKE_STATUS_BUFFER_TOO_SMALL`，可以先传入空缓冲区查询大小。
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn winpoke_window_caption(
+    window: *const WinpokeWindow,
+    buf: *mut c_char,
+    buf_len: usize,
+    out_len: *mut usize,
+) -> WinpokeStatus {
+    call(|| {
+        let window = unsafe { ref_arg(window, "window") }?;
+        unsafe { write_str(&window.caption, buf, buf_len, out_len) }
+    })
+}
+
+/// 窗口类名，缓冲区的用法同 [`winpoke_window_caption`]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn winpoke_window_class_name(
+    window: *const WinpokeWindow,
+    buf: *mut c_char,
+    buf_len: usize,
+    out_len: *mut usize,
+) -> WinpokeStatus {
+    call(|| {
+        let window = unsafe { ref_arg(window, "window") }?;
+        unsafe { write_str(&window.class_name, buf, buf_len, out_len) }
+    })
+}
+
+/// 通过 `WM_GETTEXT` 读取窗口当前的文本，缓冲区的用法同 [`winpoke_window_caption`]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn winpoke_window_text(
+    window: *const WinpokeWindow,
+    buf: *mut c_char,
+    buf_len: usize,
+    out_len: *mut usize,
+) -> WinpokeStatus {
+    call(|| {
+        let text = sys::text(unsafe { ref_arg(window, "window") }?)?;
+        unsafe { write_str(&text, buf, buf_len, out_len) }
+    })
+}
+
+/// 显示窗口
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn winpoke_window_show(window: *const WinpokeWindow) -> WinpokeStatus {
+    call(|| sys::show(unsafe { ref_arg(window, "window") }?))
+}
+
+/// 设置为前台窗口并获取焦点
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn winpoke_window_focus(window: *const WinpokeWindow) -> WinpokeStatus {
+    call(|| sys::focus(unsafe { ref_arg(window, "window") }?))
+}
+
+/// 按顺序发送 `len` 条消息，有无效消息时一条也不发送
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn winpoke_window_send(
+    window: *const WinpokeWindow,
+    messages: *const WinpokeMessage,
+    len: usize,
+) -> WinpokeStatus {
+    call(|| {
+        let window = unsafe { ref_arg(window, "window") }?;
+        let messages = unsafe { slice_arg(messages, len, "messages") }?
+            .iter()
+            .map(Message::try_from)
+            .collect::<Result<Vec<_>>>()?;
+
+        sys::send(window, messages)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+    use std::mem::{align_of, offset_of, size_of};
+    use std::ptr::{null, null_mut};
+
+    use super::*;
+    use crate::error::winpoke_last_error;
+    use crate::message::WINPOKE_MESSAGE_CHAR;
+
+    fn window(caption: &str) -> WinpokeWindow {
+        WinpokeWindow {
+            info: WinpokeWindowInfo {
+                hwnd: 0x1234 as _,
+                pid: 7,
+                tid: 8,
+                rect: WinpokeRect {
+                    left: 4,
+                    top: 1,
+                    right: 2,
+                    bottom: 3,
+                },
+                client_rect: WinpokeRect::default(),
+                style: 0x10000000,
+                ex_style: 0,
+                is_active: true,
+                is_maximized: false,
+            },
+            caption: caption.to_string(),
+            class_name: "Notepad".to_string(),
+        }
+    }
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(winpoke_last_error()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn abi_layout() {
+        assert_eq!(size_of::<WinpokeStatus>(), 4);
+        assert_eq!(WinpokeStatus::BufferTooSmall as i32, 5);
+        assert_eq!(WinpokeStatus::Panic as i32, 8);
+
+        assert_eq!(size_of::<WinpokeRect>(), 16);
+        assert_eq!(offset_of!(WinpokeRect, bottom), 12);
+
+        let pointer = size_of::<*mut c_void>();
+        assert_eq!(offset_of!(WinpokeWindowInfo, pid), pointer);
+        assert_eq!(offset_of!(WinpokeWindowInfo, rect), pointer + 8);
+        assert_eq!(offset_of!(WinpokeWindowInfo, style), pointer + 40);
+        assert_eq!(offset_of!(WinpokeWindowInfo, is_active), pointer + 48);
+        assert_eq!(offset_of!(WinpokeWindowInfo, is_maximized), pointer + 49);
+        assert_eq!(align_of::<WinpokeWindowInfo>(), align_of::<*mut c_void>());
+
+        assert_eq!(size_of::<WinpokeMessage>(), 16);
+        assert_eq!(offset_of!(WinpokeMessage, count), 12);
+    }
+
+    #[test]
+    fn list_and_strings() {
+        let list = Box::into_raw(Box::new(WinpokeWindowList(vec![
+            window("无标题 - 记事本"),
+            window(""),
+        ])));
+
+        unsafe {
+            assert_eq!(winpoke_window_list_len(list), 2);
+            assert_eq!(winpoke_window_list_len(null()), 0);
+
+            let mut first = null_mut();
+            assert_eq!(
+                winpoke_window_list_get(list, 0, &mut first),
+                WinpokeStatus::Ok
+            );
+            winpoke_window_list_free(list);
+
+            let mut info = std::mem::zeroed();
+            assert_eq!(winpoke_window_info(first, &mut info), WinpokeStatus::Ok);
+            assert_eq!(info.hwnd as usize, 0x1234);
+            assert_eq!(info.rect.left, 4);
+
+            // 先查询大小，再读取
+            let mut len = 0;
+            let status = winpoke_window_caption(first, null_mut(), 0, &mut len);
+            assert_eq!(status, WinpokeStatus::BufferTooSmall);
+            assert_eq!(len, "无标题 - 记事本".len() + 1);
+
+            let mut buf = vec![0 as c_char; len];
+            let status = winpoke_window_caption(first, buf.as_mut_ptr(), buf.len(), null_mut());
+            assert_eq!(status, WinpokeStatus::Ok);
+            assert_eq!(
+                CStr::from_ptr(buf.as_ptr()).to_str().unwrap(),
+                "无标题 - 记事本"
+            );
+
+            let mut buf = [0 as c_char; 4];
+            let status = winpoke_window_class_name(first, buf.as_mut_ptr(), buf.len(), &mut len);
+            assert_eq!(status, WinpokeStatus::BufferTooSmall);
+            assert_eq!(len, 8);
+            assert_eq!(last_error(), "缓冲区太小，需要 8 字节");
+
+            winpoke_window_free(first);
+            winpoke_window_free(null_mut());
+            winpoke_window_list_free(null_mut());
+        }
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        let list = Box::into_raw(Box::new(WinpokeWindowList(vec![window("")])));
+        let mut out = null_mut();
+
+        unsafe {
+            assert_eq!(
+                winpoke_window_list_get(list, 1, &mut out),
+                WinpokeStatus::InvalidArgument
+            );
+            assert_eq!(last_error(), "索引 1 超出范围，共 1 项");
+            assert!(out.is_null());
+
+            assert_eq!(
+                winpoke_window_list_get(list, 0, null_mut()),
+                WinpokeStatus::InvalidArgument
+            );
+            assert_eq!(last_error(), "参数 out 为空指针");
+            winpoke_window_list_free(list);
+
+            let mut list = null_mut();
+            assert_eq!(
+                winpoke_find(c"Edit[".as_ptr(), &mut list),
+                WinpokeStatus::InvalidArgument
+            );
+            assert!(last_error().starts_with("无效的选择器"));
+            assert_eq!(
+                winpoke_find_by_class_name(null(), &mut list),
+                WinpokeStatus::InvalidArgument
+            );
+            assert_eq!(last_error(), "参数 class_name 为空指针");
+            assert!(list.is_null());
+
+            let notepad = window("");
+            let invalid = WinpokeMessage {
+                kind: WINPOKE_MESSAGE_CHAR,
+                param1: -1,
+                param2: 0,
+                count: 1,
+            };
+            assert_eq!(
+                winpoke_window_send(&notepad, &invalid, 1),
+                WinpokeStatus::InvalidArgument
+            );
+            assert_eq!(
+                winpoke_window_send(&notepad, null(), 3),
+                WinpokeStatus::InvalidArgument
+            );
+            assert_eq!(last_error(), "参数 messages 为空指针");
+        }
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn unsupported_platform() {
+        let mut list = null_mut();
+        let notepad = window("");
+
+        unsafe {
+            assert_eq!(winpoke_enum_windows(&mut list), WinpokeStatus::Unsupported);
+            assert_eq!(last_error(), "winpoke 仅支持 Windows");
+            assert!(list.is_null());
+            assert_eq!(winpoke_window_focus(&notepad), WinpokeStatus::Unsupported);
+            assert_eq!(
+                winpoke_window_send(&notepad, null(), 0),
+                WinpokeStatus::Unsupported
+            );
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::ffi::c_void;
+
+use windows::Win32::Foundation::HWND;
+use winpoke::error::Error as WinpokeError;
+use winpoke::message::Message;
+use winpoke::selector::{Selector, SelectorPath};
+use winpoke::window::WindowInfo;
+
+use crate::error::Result;
+use crate::window::{WinpokeRect, WinpokeWindow, WinpokeWindowInfo};
+
+/// 由 winpoke 的 (上,右,下,左) 转换
+fn rect((top, right, bottom, left): (i32, i32, i32, i32)) -> WinpokeRect {
+    WinpokeRect {
+        left,
+        top,
+        right,
+        bottom,
+    }
+}
+
+impl From<WindowInfo> for WinpokeWindow {
+    fn from(info: WindowInfo) -> Self {
+        Self {
+            info: WinpokeWindowInfo {
+                hwnd: info.hwnd.0,
+                pid: info.pid,
+                tid: info.tid,
+                rect: rect(info.position),
+                client_rect: rect(info.client_position),
+                style: info.style.style.0,
+                ex_style: info.style.extend_style.0,
+                is_active: info.is_active,
+                is_maximized: info.style.is_maximized(),
+            },
+            caption: info.caption,
+            class_name: info.class_name,
+        }
+    }
+}
+
+fn convert(windows: Vec<WindowInfo>) -> Vec<WinpokeWindow> {
+    windows.into_iter().map(Into::into).collect()
+}
+
+/// 重新读取窗口，窗口已关闭时返回错误
+fn current(window: &WinpokeWindow) -> Result<WindowInfo> {
+    Ok(WindowInfo::from_hwnd(HWND(window.info.hwnd))?)
+}
+
+pub fn top_level() -> Result<Vec<WinpokeWindow>> {
+    Ok(convert(WindowInfo::find(&Selector::any())?))
+}
+
+pub fn find_by_class_name(class_name: &str) -> Result<Vec<WinpokeWindow>> {
+    Ok(convert(WindowInfo::find_by_class_name(class_name)?))
+}
+
+pub fn find_path(path: &SelectorPath) -> Result<Vec<WinpokeWindow>> {
+    Ok(convert(WindowInfo::find_path(path)?))
+}
+
+pub fn from_hwnd(hwnd: *mut c_void) -> Result<WinpokeWindow> {
+    Ok(WindowInfo::from_hwnd(HWND(hwnd))?.into())
+}
+
+pub fn children(window: &WinpokeWindow, class_name: Option<&str>) -> Result<Vec<WinpokeWindow>> {
+    let window = current(window)?;
+    let children = match class_name {
+        Some(class_name) => window.get_child_windows_with_class_name(class_name),
+        None => window.get_child_windows(),
+    };
+
+    match children {
+        Ok(children) => Ok(convert(children)),
+        Err(WinpokeError::WindowNotFound) => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn text(window: &WinpokeWindow) -> Result<String> {
+    Ok(current(window)?.text()?)
+}
+
+pub fn show(window: &WinpokeWindow) -> Result<()> {
+    Ok(current(window)?.show_window()?)
+}
+
+pub fn focus(window: &WinpokeWindow) -> Result<()> {
+    Ok(current(window)?.set_focus()?)
+}
+
+pub fn send(window: &WinpokeWindow, messages: Vec<Message>) -> Result<()> {
+    Ok(current(window)?.send_message_seq(messages)?)
+}
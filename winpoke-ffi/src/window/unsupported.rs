@@ -0,0 +1,45 @@
+//! 非 Windows 平台上窗口操作都返回 [`Error::Unsupported`]
+
+use std::ffi::c_void;
+
+use winpoke::message::Message;
+use winpoke::selector::SelectorPath;
+
+use crate::error::{Error, Result};
+use crate::window::WinpokeWindow;
+
+pub fn top_level() -> Result<Vec<WinpokeWindow>> {
+    Err(Error::Unsupported)
+}
+
+pub fn find_by_class_name(_class_name: &str) -> Result<Vec<WinpokeWindow>> {
+    Err(Error::Unsupported)
+}
+
+pub fn find_path(_path: &SelectorPath) -> Result<Vec<WinpokeWindow>> {
+    Err(Error::Unsupported)
+}
+
+pub fn from_hwnd(_hwnd: *mut c_void) -> Result<WinpokeWindow> {
+    Err(Error::Unsupported)
+}
+
+pub fn children(_window: &WinpokeWindow, _class_name: Option<&str>) -> Result<Vec<WinpokeWindow>> {
+    Err(Error::Unsupported)
+}
+
+pub fn text(_window: &WinpokeWindow) -> Result<String> {
+    Err(Error::Unsupported)
+}
+
+pub fn show(_window: &WinpokeWindow) -> Result<()> {
+    Err(Error::Unsupported)
+}
+
+pub fn focus(_window: &WinpokeWindow) -> Result<()> {
+    Err(Error::Unsupported)
+}
+
+pub fn send(_window: &WinpokeWindow, _messages: Vec<Message>) -> Result<()> {
+    Err(Error::Unsupported)
+}
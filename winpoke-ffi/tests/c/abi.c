@@ -0,0 +1,62 @@
+/* 按头文件检查结构体布局，并调用不依赖窗口的函数 */
+
+#include <stddef.h>
+#include <stdio.h>
+
+#include "winpoke.h"
+
+_Static_assert(sizeof(WinpokeStatus) == 4, "WinpokeStatus");
+_Static_assert(WINPOKE_STATUS_PANIC == 8, "WinpokeStatus");
+
+_Static_assert(sizeof(WinpokeRect) == 16, "WinpokeRect");
+_Static_assert(offsetof(WinpokeRect, bottom) == 12, "WinpokeRect.bottom");
+
+_Static_assert(offsetof(WinpokeWindowInfo, pid) == sizeof(void *), "WinpokeWindowInfo.pid");
+_Static_assert(offsetof(WinpokeWindowInfo, rect) == sizeof(void *) + 8, "WinpokeWindowInfo.rect");
+_Static_assert(offsetof(WinpokeWindowInfo, style) == sizeof(void *) + 40, "WinpokeWindowInfo.style");
+_Static_assert(offsetof(WinpokeWindowInfo, is_active) == sizeof(void *) + 48,
+               "WinpokeWindowInfo.is_active");
+_Static_assert(offsetof(WinpokeWindowInfo, is_maximized) == sizeof(void *) + 49,
+               "WinpokeWindowInfo.is_maximized");
+
+_Static_assert(sizeof(WinpokeMessage) == 16, "WinpokeMessage");
+_Static_assert(offsetof(WinpokeMessage, count) == 12, "WinpokeMessage.count");
+
+static int failures = 0;
+
+#define CHECK(cond)                                              \
+    do {                                                         \
+        if (!(cond)) {                                           \
+            fprintf(stderr, "%s:%d: %s\n", __FILE__, __LINE__, #cond); \
+            failures++;                                          \
+        }                                                        \
+    } while (0)
+
+int main(void) {
+    WinpokeWindowList *list = NULL;
+    size_t len = 0;
+    WinpokeMessage message = {WINPOKE_MESSAGE_CHAR, 'a', 0, 1};
+
+    CHECK(winpoke_last_error() == NULL);
+
+    CHECK(winpoke_find(NULL, &list) == WINPOKE_STATUS_INVALID_ARGUMENT);
+    CHECK(list == NULL);
+    CHECK(winpoke_last_error() != NULL);
+
+    CHECK(winpoke_find("Notepad[", &list) == WINPOKE_STATUS_INVALID_ARGUMENT);
+    CHECK(list == NULL);
+
+    CHECK(winpoke_window_list_len(NULL) == 0);
+    winpoke_window_list_free(NULL);
+    winpoke_window_free(NULL);
+
+    CHECK(winpoke_window_caption(NULL, NULL, 0, &len) == WINPOKE_STATUS_INVALID_ARGUMENT);
+    CHECK(winpoke_window_send(NULL, &message, 1) == WINPOKE_STATUS_INVALID_ARGUMENT);
+
+#ifndef _WIN32
+    CHECK(winpoke_enum_windows(&list) == WINPOKE_STATUS_UNSUPPORTED);
+    CHECK(list == NULL);
+#endif
+
+    return failures == 0 ? 0 : 1;
+}
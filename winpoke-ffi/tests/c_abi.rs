@@ -0,0 +1,46 @@
+//! 用 C 编译器编译 `tests/c/abi.c` 并链接静态库，检查头文件与实际的 ABI 一致
+//!
+//! 编译器取自 `CC` 环境变量，默认为 `cc`，找不到编译器时跳过。
+#![cfg(unix)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// 与测试一同构建的 `libwinpoke_ffi.a`
+fn static_lib() -> PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    // 测试位于 target/<profile>/deps，静态库在 deps 或其上一级
+    exe.ancestors()
+        .skip(1)
+        .take(2)
+        .map(|dir| dir.join("libwinpoke_ffi.a"))
+        .find(|lib| lib.exists())
+        .expect("找不到 libwinpoke_ffi.a")
+}
+
+#[test]
+fn compile_and_link() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let output = Path::new(env!("CARGO_TARGET_TMPDIR")).join("winpoke_abi");
+    let compiler = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+
+    let status = Command::new(&compiler)
+        .args(["-std=c11", "-Wall", "-Werror", "-I"])
+        .arg(crate_dir.join("include"))
+        .arg(crate_dir.join("tests/c/abi.c"))
+        .arg(static_lib())
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&output)
+        .status();
+    let status = match status {
+        Ok(status) => status,
+        Err(e) => {
+            eprintln!("跳过：无法运行 C 编译器 {compiler}: {e}");
+            return;
+        }
+    };
+    assert!(status.success(), "编译 abi.c 失败");
+
+    let status = Command::new(&output).status().unwrap();
+    assert!(status.success(), "abi.c 中的检查失败");
+}
@@ -0,0 +1,29 @@
+//! 检查提交的 `include/winpoke.h` 与 cbindgen 的输出一致
+//!
+//! 修改接口后运行 `WINPOKE_UPDATE_HEADER=1 cargo test -p winpoke-ffi --test header` 更新头文件。
+
+use std::path::Path;
+
+#[test]
+fn header_up_to_date() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let header = crate_dir.join("include/winpoke.h");
+
+    let mut generated = Vec::new();
+    cbindgen::generate(crate_dir)
+        .expect("生成 C 头文件失败")
+        .write(&mut generated);
+    let generated = String::from_utf8(generated).unwrap();
+
+    if std::env::var_os("WINPOKE_UPDATE_HEADER").is_some() {
+        std::fs::write(&header, generated).unwrap();
+        return;
+    }
+
+    let committed = std::fs::read_to_string(&header).unwrap();
+    assert!(
+        committed == generated,
+        "{} 已过期，用 WINPOKE_UPDATE_HEADER=1 重新运行此测试以更新",
+        header.display()
+    );
+}
@@ -0,0 +1,109 @@
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use winpoke::error::Error as WinpokeError;
+
+create_exception!(
+    winpoke,
+    Error,
+    PyException,
+    "winpoke 操作失败，其它异常的基类"
+);
+create_exception!(winpoke, NotFoundError, Error, "找不到窗口、菜单项、按钮等");
+create_exception!(
+    winpoke,
+    InvalidArgumentError,
+    Error,
+    "选择器、热键、颜色等参数格式错误"
+);
+create_exception!(winpoke, WaitTimeoutError, Error, "等待窗口或消息响应超时");
+create_exception!(
+    winpoke,
+    AccessDeniedError,
+    Error,
+    "目标程序以更高权限运行，需要以管理员身份运行"
+);
+
+/// 转换为 Python 异常的 [`winpoke::error::Error`]
+#[derive(Debug)]
+pub struct PyWinpokeError(pub WinpokeError);
+
+impl From<WinpokeError> for PyWinpokeError {
+    fn from(e: WinpokeError) -> Self {
+        Self(e)
+    }
+}
+
+impl From<PyWinpokeError> for PyErr {
+    fn from(PyWinpokeError(e): PyWinpokeError) -> Self {
+        let message = e.to_string();
+        match e {
+            WinpokeError::WindowNotFound
+            | WinpokeError::NoMoreChildWindow
+            | WinpokeError::TreeItemNotFound(_)
+            | WinpokeError::ListItemNotFound(_)
+            | WinpokeError::MenuNotFound
+            | WinpokeError::MenuItemNotFound(_)
+            | WinpokeError::ButtonNotFound(_) => NotFoundError::new_err(message),
+            WinpokeError::InvalidSelector(_)
+            | WinpokeError::InvalidHotkey(_)
+            | WinpokeError::InvalidRegistryPath(_)
+            | WinpokeError::InvalidPattern(_)
+            | WinpokeError::InvalidColor(_)
+            | WinpokeError::ClassMismatch { .. } => InvalidArgumentError::new_err(message),
+            WinpokeError::MessageTimeout | WinpokeError::WaitTimeout(_) => {
+                WaitTimeoutError::new_err(message)
+            }
+            WinpokeError::ElevationRequired | WinpokeError::SendInputFailed => {
+                AccessDeniedError::new_err(message)
+            }
+            _ => Error::new_err(message),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, PyWinpokeError>;
+
+pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("Error", py.get_type::<Error>())?;
+    m.add("NotFoundError", py.get_type::<NotFoundError>())?;
+    m.add(
+        "InvalidArgumentError",
+        py.get_type::<InvalidArgumentError>(),
+    )?;
+    m.add("WaitTimeoutError", py.get_type::<WaitTimeoutError>())?;
+    m.add("AccessDeniedError", py.get_type::<AccessDeniedError>())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(e: WinpokeError) -> PyErr {
+        PyWinpokeError(e).into()
+    }
+
+    #[test]
+    fn exception_types() {
+        Python::attach(|py| {
+            let not_found = convert(WinpokeError::MenuItemNotFound("文件 > 打开".into()));
+            assert!(not_found.is_instance_of::<NotFoundError>(py));
+            assert!(not_found.is_instance_of::<Error>(py));
+            assert_eq!(not_found.value(py).to_string(), "找不到菜单项: 文件 > 打开");
+
+            let invalid = convert("Edit[".parse::<winpoke::selector::Selector>().unwrap_err());
+            assert!(invalid.is_instance_of::<InvalidArgumentError>(py));
+
+            let timeout = convert(WinpokeError::WaitTimeout("Notepad 仍未关闭".into()));
+            assert!(timeout.is_instance_of::<WaitTimeoutError>(py));
+            assert!(!timeout.is_instance_of::<NotFoundError>(py));
+
+            let other = convert(WinpokeError::SetFocusFailed);
+            assert!(other.is_instance_of::<Error>(py));
+            assert!(!other.is_instance_of::<AccessDeniedError>(py));
+        });
+    }
+}
@@ -0,0 +1,29 @@
+//! winpoke 的 Python 绑定
+//!
+//! 用 `maturin build --release` 构建 wheel，Python 中以 `import winpoke` 使用。
+//! 窗口相关的函数仅在 Windows 上可用，选择器和消息可以在任何平台上构造。
+
+mod error;
+mod message;
+mod selector;
+#[cfg(windows)]
+mod window;
+
+use pyo3::prelude::*;
+
+#[pymodule(name = "winpoke")]
+fn winpoke_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    register(m)
+}
+
+pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    error::register(m)?;
+    m.add_class::<selector::PySelector>()?;
+    m.add_class::<selector::PySelectorPath>()?;
+    m.add_class::<message::PyWindowMessage>()?;
+    m.add_class::<message::PyMessage>()?;
+    #[cfg(windows)]
+    window::register(m)?;
+
+    Ok(())
+}
@@ -0,0 +1,186 @@
+use pyo3::prelude::*;
+use winpoke::error::Error;
+use winpoke::keys::vk_from_name;
+use winpoke::message::{Message, WindowMessage};
+
+use crate::error::Result;
+
+/// 窗口消息的种类
+#[pyclass(name = "WindowMessage", module = "winpoke", frozen, eq, from_py_object)]
+#[derive(Clone, PartialEq)]
+pub enum PyWindowMessage {
+    KeyDown(u32),
+    MouseMove(i32, i32),
+    Char(char),
+    Command(u32),
+}
+
+impl From<&PyWindowMessage> for WindowMessage {
+    fn from(msg: &PyWindowMessage) -> Self {
+        match *msg {
+            PyWindowMessage::KeyDown(vk) => WindowMessage::KeyDown(vk),
+            PyWindowMessage::MouseMove(x, y) => WindowMessage::MouseMove(x, y),
+            PyWindowMessage::Char(c) => WindowMessage::Char(c),
+            PyWindowMessage::Command(id) => WindowMessage::Command(id),
+        }
+    }
+}
+
+impl From<&WindowMessage> for PyWindowMessage {
+    fn from(msg: &WindowMessage) -> Self {
+        match *msg {
+            WindowMessage::KeyDown(vk) => PyWindowMessage::KeyDown(vk),
+            WindowMessage::MouseMove(x, y) => PyWindowMessage::MouseMove(x, y),
+            WindowMessage::Char(c) => PyWindowMessage::Char(c),
+            WindowMessage::Command(id) => PyWindowMessage::Command(id),
+        }
+    }
+}
+
+/// 键名或虚拟键码
+#[derive(FromPyObject)]
+pub enum KeyArg {
+    Code(u32),
+    Name(String),
+}
+
+/// 发送到窗口的消息，`count` 为重复次数
+#[pyclass(name = "Message", module = "winpoke", frozen, eq, from_py_object)]
+#[derive(Clone, PartialEq)]
+pub struct PyMessage(pub Message);
+
+#[pymethods]
+impl PyMessage {
+    #[new]
+    #[pyo3(signature = (msg, count = 1))]
+    fn new(msg: PyWindowMessage, count: u32) -> Self {
+        Self(Message {
+            msg: (&msg).into(),
+            count,
+        })
+    }
+
+    /// 按键消息，`key` 为键名（如 `"Enter"`）或虚拟键码
+    #[staticmethod]
+    #[pyo3(signature = (key, count = 1))]
+    fn key(key: KeyArg, count: u32) -> Result<Self> {
+        let vk = match key {
+            KeyArg::Code(vk) => vk,
+            KeyArg::Name(name) => vk_from_name(&name).ok_or(Error::InvalidHotkey(name))?,
+        };
+
+        Ok(Self(Message {
+            msg: WindowMessage::KeyDown(vk),
+            count,
+        }))
+    }
+
+    /// 字符消息
+    #[staticmethod]
+    #[pyo3(signature = (c, count = 1))]
+    fn char(c: char, count: u32) -> Self {
+        Self(Message {
+            msg: WindowMessage::Char(c),
+            count,
+        })
+    }
+
+    /// 菜单或按钮命令消息
+    #[staticmethod]
+    fn command(id: u32) -> Self {
+        Self(Message {
+            msg: WindowMessage::Command(id),
+            count: 1,
+        })
+    }
+
+    /// 鼠标移动消息，坐标相对于工作区
+    #[staticmethod]
+    fn mouse_move(x: i32, y: i32) -> Self {
+        Self(Message {
+            msg: WindowMessage::MouseMove(x, y),
+            count: 1,
+        })
+    }
+
+    /// 逐字符输入文本的消息列表
+    #[staticmethod]
+    fn text(text: &str) -> Vec<Self> {
+        text.chars().map(|c| Self::char(c, 1)).collect()
+    }
+
+    #[getter]
+    fn msg(&self) -> PyWindowMessage {
+        (&self.0.msg).into()
+    }
+
+    #[getter]
+    fn count(&self) -> u32 {
+        self.0.count
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Message({:?}, count={})", self.0.msg, self.0.count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pyo3::types::PyDict;
+
+    use super::*;
+
+    #[test]
+    fn build_messages_in_python() {
+        Python::attach(|py| {
+            let module = PyModule::new(py, "winpoke").unwrap();
+            crate::register(&module).unwrap();
+            let locals = PyDict::new(py);
+            locals.set_item("winpoke", module).unwrap();
+
+            let messages: Vec<PyMessage> = py
+                .eval(
+                    c"[winpoke.Message.key('Enter', count=2), winpoke.Message.key(0x41), \
+                       winpoke.Message(winpoke.WindowMessage.MouseMove(3, 4))] \
+                      + winpoke.Message.text('hi')",
+                    None,
+                    Some(&locals),
+                )
+                .unwrap()
+                .extract()
+                .unwrap();
+            let messages: Vec<Message> = messages.into_iter().map(|m| m.0).collect();
+            assert_eq!(
+                messages,
+                [
+                    Message {
+                        msg: WindowMessage::KeyDown(0x0D),
+                        count: 2
+                    },
+                    Message {
+                        msg: WindowMessage::KeyDown(0x41),
+                        count: 1
+                    },
+                    Message {
+                        msg: WindowMessage::MouseMove(3, 4),
+                        count: 1
+                    },
+                    Message {
+                        msg: WindowMessage::Char('h'),
+                        count: 1
+                    },
+                    Message {
+                        msg: WindowMessage::Char('i'),
+                        count: 1
+                    },
+                ]
+            );
+
+            let error = py
+                .eval(c"winpoke.Message.key('Nope')", None, Some(&locals))
+                .unwrap_err();
+            assert!(error.is_instance_of::<crate::error::InvalidArgumentError>(py));
+            assert_eq!(error.value(py).to_string(), "无效的热键: Nope");
+        });
+    }
+}
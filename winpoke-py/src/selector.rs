@@ -0,0 +1,89 @@
+use pyo3::prelude::*;
+use winpoke::selector::{Selector, SelectorPath};
+
+use crate::error::Result;
+
+/// 窗口选择器，如 `Notepad[caption="无标题"]`
+#[pyclass(name = "Selector", module = "winpoke", frozen, eq, from_py_object)]
+#[derive(Clone, PartialEq)]
+pub struct PySelector(pub Selector);
+
+#[pymethods]
+impl PySelector {
+    #[new]
+    #[pyo3(signature = (class_name = None, caption = None, pid = None))]
+    fn new(class_name: Option<String>, caption: Option<String>, pid: Option<u32>) -> Self {
+        Self(Selector {
+            class_name,
+            caption,
+            pid,
+        })
+    }
+
+    /// 解析选择器字符串
+    #[staticmethod]
+    fn parse(s: &str) -> Result<Self> {
+        Ok(Self(s.parse()?))
+    }
+
+    #[getter]
+    fn class_name(&self) -> Option<&str> {
+        self.0.class_name.as_deref()
+    }
+
+    #[getter]
+    fn caption(&self) -> Option<&str> {
+        self.0.caption.as_deref()
+    }
+
+    #[getter]
+    fn pid(&self) -> Option<u32> {
+        self.0.pid
+    }
+
+    fn with_caption(&self, caption: String) -> Self {
+        Self(self.0.clone().with_caption(caption))
+    }
+
+    fn with_pid(&self, pid: u32) -> Self {
+        Self(self.0.clone().with_pid(pid))
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Selector({:?})", self.0.to_string())
+    }
+}
+
+/// 选择器路径，如 `Notepad > Edit`，第一级匹配顶层窗口
+#[pyclass(name = "SelectorPath", module = "winpoke", frozen, eq, from_py_object)]
+#[derive(Clone, PartialEq)]
+pub struct PySelectorPath(pub SelectorPath);
+
+#[pymethods]
+impl PySelectorPath {
+    #[new]
+    fn new(path: &str) -> Result<Self> {
+        Ok(Self(path.parse()?))
+    }
+
+    #[getter]
+    fn selectors(&self) -> Vec<PySelector> {
+        self.0.0.iter().cloned().map(PySelector).collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.0.len()
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("SelectorPath({:?})", self.0.to_string())
+    }
+}
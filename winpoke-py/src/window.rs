@@ -0,0 +1,310 @@
+use std::time::Duration;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use windows::Win32::Foundation::HWND;
+use winpoke::color::Color;
+use winpoke::error::Error;
+use winpoke::script::{Backend, Desktop};
+use winpoke::selector::SelectorPath;
+use winpoke::window::WindowInfo;
+
+use crate::error::{PyWinpokeError, Result};
+use crate::message::PyMessage;
+use crate::selector::{PySelector, PySelectorPath};
+
+/// 等待窗口时默认的超时时间（秒）
+const DEFAULT_WAIT_TIMEOUT: f64 = 10.0;
+
+/// 接受选择器路径的参数：字符串、`Selector` 或 `SelectorPath`
+#[derive(FromPyObject)]
+pub enum PathArg {
+    Path(PySelectorPath),
+    Selector(PySelector),
+    Text(String),
+}
+
+impl PathArg {
+    fn into_path(self) -> Result<SelectorPath> {
+        Ok(match self {
+            PathArg::Path(path) => path.0,
+            PathArg::Selector(selector) => selector.0.into(),
+            PathArg::Text(text) => text.parse()?,
+        })
+    }
+}
+
+/// 单条消息或消息列表
+#[derive(FromPyObject)]
+pub enum MessagesArg {
+    One(PyMessage),
+    Many(Vec<PyMessage>),
+}
+
+/// 秒数转为时长，负数视为 0，NaN 和过大的值抛出 `ValueError`
+fn seconds(secs: f64) -> PyResult<Duration> {
+    if secs.is_nan() {
+        return Err(PyValueError::new_err("时长不能是 NaN"));
+    }
+
+    Duration::try_from_secs_f64(secs.max(0.0))
+        .map_err(|_| PyValueError::new_err(format!("时长 {secs} 秒超出范围")))
+}
+
+/// 查找或读取窗口时的窗口信息快照
+///
+/// 属性不会随窗口变化自动更新，需要时调用 `refresh()`；方法总是作用于窗口的当前状态。
+#[pyclass(name = "WindowInfo", module = "winpoke", frozen, eq, hash)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct PyWindowInfo {
+    /// 窗口句柄
+    #[pyo3(get)]
+    hwnd: isize,
+
+    #[pyo3(get)]
+    caption: String,
+
+    #[pyo3(get)]
+    class_name: String,
+
+    #[pyo3(get)]
+    pid: u32,
+
+    #[pyo3(get)]
+    tid: u32,
+
+    /// 窗口坐标(上,右,下,左)
+    #[pyo3(get)]
+    position: (i32, i32, i32, i32),
+
+    /// 工作区坐标(上,右,下,左)
+    #[pyo3(get)]
+    client_position: (i32, i32, i32, i32),
+
+    #[pyo3(get)]
+    border: (u32, u32),
+
+    #[pyo3(get)]
+    is_active: bool,
+
+    /// `WS_*` 样式
+    #[pyo3(get)]
+    style: u32,
+
+    /// `WS_EX_*` 扩展样式
+    #[pyo3(get)]
+    ex_style: u32,
+
+    /// 样式名称，如 `WS_VISIBLE`
+    #[pyo3(get)]
+    style_names: Vec<String>,
+
+    #[pyo3(get)]
+    is_maximized: bool,
+}
+
+impl From<WindowInfo> for PyWindowInfo {
+    fn from(info: WindowInfo) -> Self {
+        let style_names = info.style.to_string();
+
+        Self {
+            hwnd: info.hwnd.0 as isize,
+            is_maximized: info.style.is_maximized(),
+            style: info.style.style.0,
+            ex_style: info.style.extend_style.0,
+            style_names: style_names
+                .split(" | ")
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect(),
+            caption: info.caption,
+            class_name: info.class_name,
+            pid: info.pid,
+            tid: info.tid,
+            position: info.position,
+            client_position: info.client_position,
+            border: info.border,
+            is_active: info.is_active,
+        }
+    }
+}
+
+impl PyWindowInfo {
+    /// 重新读取窗口，窗口已关闭时返回错误
+    fn current(&self) -> Result<WindowInfo> {
+        Ok(WindowInfo::from_hwnd(HWND(self.hwnd as _))?)
+    }
+}
+
+fn convert(windows: Vec<WindowInfo>) -> Vec<PyWindowInfo> {
+    windows.into_iter().map(Into::into).collect()
+}
+
+#[pymethods]
+impl PyWindowInfo {
+    /// 按选择器路径查找窗口
+    #[staticmethod]
+    fn find(py: Python<'_>, path: PathArg) -> Result<Vec<Self>> {
+        let path = path.into_path()?;
+
+        Ok(py.detach(|| WindowInfo::find_path(&path).map(convert))?)
+    }
+
+    /// 第一个匹配的窗口，找不到时抛出 `NotFoundError`
+    #[staticmethod]
+    fn find_one(py: Python<'_>, path: PathArg) -> Result<Self> {
+        Self::find(py, path)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::WindowNotFound.into())
+    }
+
+    #[staticmethod]
+    fn from_hwnd(hwnd: isize) -> Result<Self> {
+        Ok(WindowInfo::from_hwnd(HWND(hwnd as _))?.into())
+    }
+
+    /// 屏幕坐标处最深层的可见窗口
+    #[staticmethod]
+    fn from_point(x: i32, y: i32) -> Result<Self> {
+        Ok(WindowInfo::from_point(x, y)?.into())
+    }
+
+    /// 鼠标光标下最深层的可见窗口
+    #[staticmethod]
+    fn from_cursor() -> Result<Self> {
+        Ok(WindowInfo::from_cursor()?.into())
+    }
+
+    /// 重新读取窗口信息
+    fn refresh(&self) -> Result<Self> {
+        Ok(self.current()?.into())
+    }
+
+    fn parent(&self) -> Result<Option<Self>> {
+        Ok(self.current()?.parent()?.map(Into::into))
+    }
+
+    /// 一级子窗口
+    fn children(&self) -> Result<Vec<Self>> {
+        match self.current()?.get_child_windows() {
+            Ok(children) => Ok(convert(children)),
+            Err(Error::WindowNotFound) => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 从顶层窗口到当前窗口（包含）的各级窗口
+    fn hierarchy(&self) -> Result<Vec<Self>> {
+        Ok(convert(self.current()?.hierarchy()?))
+    }
+
+    fn selector_path(&self) -> Result<PySelectorPath> {
+        Ok(PySelectorPath(self.current()?.selector_path()?))
+    }
+
+    /// 通过 `WM_GETTEXT` 读取窗口文本
+    #[pyo3(signature = (timeout = 1.0))]
+    fn text(&self, py: Python<'_>, timeout: f64) -> PyResult<String> {
+        let timeout = seconds(timeout)?.as_millis().min(u32::MAX as u128) as u32;
+        let text =
+            py.detach(|| -> Result<String> { Ok(self.current()?.text_with_timeout(timeout)?) })?;
+
+        Ok(text)
+    }
+
+    fn show(&self) -> Result<()> {
+        Ok(self.current()?.show_window()?)
+    }
+
+    /// 设置为前台窗口并获取焦点
+    fn focus(&self, py: Python<'_>) -> Result<()> {
+        py.detach(|| Ok(self.current()?.set_focus()?))
+    }
+
+    /// 按顺序发送消息
+    fn send(&self, py: Python<'_>, messages: MessagesArg) -> Result<()> {
+        let messages = match messages {
+            MessagesArg::One(message) => vec![message.0],
+            MessagesArg::Many(messages) => messages.into_iter().map(|m| m.0).collect(),
+        };
+
+        py.detach(|| Ok(self.current()?.send_message_seq(messages)?))
+    }
+
+    /// 是否带有指定样式，如 `WS_VISIBLE`、`WS_EX_TOPMOST`
+    fn has_style(&self, name: &str) -> bool {
+        self.style_names.iter().any(|style| style == name)
+    }
+
+    /// 在窗口外侧绘制高亮框，阻塞 `duration` 秒
+    #[pyo3(signature = (duration = 0.8, color = "red"))]
+    fn highlight(&self, py: Python<'_>, duration: f64, color: &str) -> PyResult<()> {
+        let color: Color = color.parse().map_err(PyWinpokeError)?;
+        let duration = seconds(duration)?;
+        let hwnd = self.hwnd;
+
+        py.detach(|| WindowInfo::from_hwnd(HWND(hwnd as _))?.highlight(duration, color))
+            .map_err(PyWinpokeError)?;
+
+        Ok(())
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "WindowInfo(hwnd={:#x}, class_name={:?}, caption={:?}, pid={})",
+            self.hwnd, self.class_name, self.caption, self.pid
+        )
+    }
+}
+
+/// 按选择器路径查找窗口
+#[pyfunction]
+fn find(py: Python<'_>, path: PathArg) -> Result<Vec<PyWindowInfo>> {
+    PyWindowInfo::find(py, path)
+}
+
+/// 第一个匹配的窗口，找不到时抛出 `NotFoundError`
+#[pyfunction]
+fn find_one(py: Python<'_>, path: PathArg) -> Result<PyWindowInfo> {
+    PyWindowInfo::find_one(py, path)
+}
+
+/// 等待匹配的窗口出现并返回第一个，超时抛出 `WaitTimeoutError`
+#[pyfunction]
+#[pyo3(signature = (path, timeout = DEFAULT_WAIT_TIMEOUT))]
+fn wait(py: Python<'_>, path: PathArg, timeout: f64) -> PyResult<PyWindowInfo> {
+    let path = path.into_path()?;
+    let timeout = seconds(timeout)?;
+    // wait_for 超时时返回 WindowNotFound，与 wait_gone 一样转换为超时错误
+    let handle = py
+        .detach(|| match Desktop::new().wait_for(&path, timeout) {
+            Err(Error::WindowNotFound) => Err(Error::WaitTimeout(format!("{path} 仍未出现"))),
+            result => result,
+        })
+        .map_err(PyWinpokeError)?;
+
+    Ok(PyWindowInfo::from_hwnd(handle.id as isize)?)
+}
+
+/// 等待匹配的窗口全部关闭，超时抛出 `WaitTimeoutError`
+#[pyfunction]
+#[pyo3(signature = (path, timeout = DEFAULT_WAIT_TIMEOUT))]
+fn wait_gone(py: Python<'_>, path: PathArg, timeout: f64) -> PyResult<()> {
+    let path = path.into_path()?;
+    let timeout = seconds(timeout)?;
+    py.detach(|| Desktop::new().wait_gone(&path, timeout))
+        .map_err(PyWinpokeError)?;
+
+    Ok(())
+}
+
+pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyWindowInfo>()?;
+    m.add_function(wrap_pyfunction!(find, m)?)?;
+    m.add_function(wrap_pyfunction!(find_one, m)?)?;
+    m.add_function(wrap_pyfunction!(wait, m)?)?;
+    m.add_function(wrap_pyfunction!(wait_gone, m)?)?;
+
+    Ok(())
+}
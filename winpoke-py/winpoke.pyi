@@ -0,0 +1,187 @@
+"""winpoke 的 Python 绑定
+
+窗口相关的函数和 `WindowInfo` 仅在 Windows 上可用。
+查找、读取文本、发送消息和等待时释放 GIL，其它线程可以继续运行。
+时长参数以秒计，负数视为 0，NaN 或过大时抛出 `ValueError`。
+"""
+
+from typing import Optional, Sequence, Union, final
+
+class Error(Exception):
+    """winpoke 操作失败，其它异常的基类"""
+
+class NotFoundError(Error):
+    """找不到窗口、菜单项、按钮等"""
+
+class InvalidArgumentError(Error):
+    """选择器、热键、颜色等参数格式错误"""
+
+class WaitTimeoutError(Error):
+    """等待窗口或消息响应超时"""
+
+class AccessDeniedError(Error):
+    """目标程序以更高权限运行，需要以管理员身份运行"""
+
+@final
+class Selector:
+    """窗口选择器，如 `Notepad[caption="无标题"]`"""
+
+    def __init__(
+        self,
+        class_name: Optional[str] = None,
+        caption: Optional[str] = None,
+        pid: Optional[int] = None,
+    ) -> None: ...
+    @staticmethod
+    def parse(s: str) -> Selector:
+        """解析选择器字符串，格式错误时抛出 `InvalidArgumentError`"""
+    @property
+    def class_name(self) -> Optional[str]: ...
+    @property
+    def caption(self) -> Optional[str]: ...
+    @property
+    def pid(self) -> Optional[int]: ...
+    def with_caption(self, caption: str) -> Selector: ...
+    def with_pid(self, pid: int) -> Selector: ...
+    def __eq__(self, other: object) -> bool: ...
+
+@final
+class SelectorPath:
+    """选择器路径，如 `Notepad > Edit`，第一级匹配顶层窗口"""
+
+    def __init__(self, path: str) -> None: ...
+    @property
+    def selectors(self) -> list[Selector]: ...
+    def __len__(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+
+Path = Union[str, Selector, SelectorPath]
+
+class WindowMessage:
+    """窗口消息的种类"""
+
+    @final
+    class KeyDown(WindowMessage):
+        def __init__(self, _0: int) -> None: ...
+        _0: int
+
+    @final
+    class MouseMove(WindowMessage):
+        def __init__(self, _0: int, _1: int) -> None: ...
+        _0: int
+        _1: int
+
+    @final
+    class Char(WindowMessage):
+        def __init__(self, _0: str) -> None: ...
+        _0: str
+
+    @final
+    class Command(WindowMessage):
+        def __init__(self, _0: int) -> None: ...
+        _0: int
+
+@final
+class Message:
+    """发送到窗口的消息，`count` 为重复次数"""
+
+    def __init__(self, msg: WindowMessage, count: int = 1) -> None: ...
+    @staticmethod
+    def key(key: Union[str, int], count: int = 1) -> Message:
+        """按键消息，`key` 为键名（如 `"Enter"`）或虚拟键码"""
+    @staticmethod
+    def char(c: str, count: int = 1) -> Message: ...
+    @staticmethod
+    def command(id: int) -> Message:
+        """菜单或按钮命令消息"""
+    @staticmethod
+    def mouse_move(x: int, y: int) -> Message:
+        """鼠标移动消息，坐标相对于工作区"""
+    @staticmethod
+    def text(text: str) -> list[Message]:
+        """逐字符输入文本的消息列表"""
+    @property
+    def msg(self) -> WindowMessage: ...
+    @property
+    def count(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+
+@final
+class WindowInfo:
+    """查找或读取窗口时的窗口信息快照
+
+    属性不会随窗口变化自动更新，需要时调用 `refresh()`；方法总是作用于窗口的当前状态。
+    """
+
+    @property
+    def hwnd(self) -> int: ...
+    @property
+    def caption(self) -> str: ...
+    @property
+    def class_name(self) -> str: ...
+    @property
+    def pid(self) -> int: ...
+    @property
+    def tid(self) -> int: ...
+    @property
+    def position(self) -> tuple[int, int, int, int]:
+        """窗口坐标(上,右,下,左)"""
+    @property
+    def client_position(self) -> tuple[int, int, int, int]:
+        """工作区坐标(上,右,下,左)"""
+    @property
+    def border(self) -> tuple[int, int]: ...
+    @property
+    def is_active(self) -> bool: ...
+    @property
+    def style(self) -> int:
+        """`WS_*` 样式"""
+    @property
+    def ex_style(self) -> int:
+        """`WS_EX_*` 扩展样式"""
+    @property
+    def style_names(self) -> list[str]:
+        """样式名称，如 `WS_VISIBLE`"""
+    @property
+    def is_maximized(self) -> bool: ...
+    @staticmethod
+    def find(path: Path) -> list[WindowInfo]: ...
+    @staticmethod
+    def find_one(path: Path) -> WindowInfo: ...
+    @staticmethod
+    def from_hwnd(hwnd: int) -> WindowInfo: ...
+    @staticmethod
+    def from_point(x: int, y: int) -> WindowInfo: ...
+    @staticmethod
+    def from_cursor() -> WindowInfo: ...
+    def refresh(self) -> WindowInfo: ...
+    def parent(self) -> Optional[WindowInfo]: ...
+    def children(self) -> list[WindowInfo]: ...
+    def hierarchy(self) -> list[WindowInfo]:
+        """从顶层窗口到当前窗口（包含）的各级窗口"""
+    def selector_path(self) -> SelectorPath: ...
+    def text(self, timeout: float = 1.0) -> str:
+        """通过 `WM_GETTEXT` 读取窗口文本"""
+    def show(self) -> None: ...
+    def focus(self) -> None:
+        """设置为前台窗口并获取焦点"""
+    def send(self, messages: Union[Message, Sequence[Message]]) -> None:
+        """按顺序发送消息"""
+    def has_style(self, name: str) -> bool:
+        """是否带有指定样式，如 `WS_VISIBLE`、`WS_EX_TOPMOST`"""
+    def highlight(self, duration: float = 0.8, color: str = "red") -> None:
+        """在窗口外侧绘制高亮框，阻塞 `duration` 秒"""
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
+def find(path: Path) -> list[WindowInfo]:
+    """按选择器路径查找窗口"""
+
+def find_one(path: Path) -> WindowInfo:
+    """第一个匹配的窗口，找不到时抛出 `NotFoundError`"""
+
+def wait(path: Path, timeout: float = 10.0) -> WindowInfo:
+    """等待匹配的窗口出现并返回第一个，超时抛出 `WaitTimeoutError`"""
+
+def wait_gone(path: Path, timeout: float = 10.0) -> None:
+    """等待匹配的窗口全部关闭，超时抛出 `WaitTimeoutError`"""
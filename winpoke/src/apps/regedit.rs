@@ -0,0 +1,184 @@
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use windows::Win32::Foundation::{CloseHandle, ERROR_ELEVATION_REQUIRED};
+
+use crate::control::{ListView, TreeView};
+use crate::error::Error;
+use crate::prelude::Result;
+use crate::registry::RegistryPath;
+use crate::window::WindowInfo;
+use crate::window::active::{create_process, wait_for_input_idle};
+
+/// 注册表编辑器主窗口的类名
+pub const REGEDIT_CLASS: &str = "RegEdit_RegEdit";
+
+/// 启动注册表编辑器后等待主窗口出现的最长时间
+const LAUNCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 选中键后等待值列表刷新的最长时间
+const VALUES_TIMEOUT: Duration = Duration::from_secs(2);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 注册表编辑器（regedit.exe）
+///
+/// 通过左侧树形控件定位键，通过右侧列表控件选中值，不依赖键盘输入和窗口焦点。
+/// 注册表编辑器以管理员权限运行，调用方也需要以管理员身份运行才能向其发送消息。
+///
+/// ```no_run
+/// use winpoke::apps::regedit::Regedit;
+///
+/// let regedit = Regedit::open()?;
+/// regedit.jump(&r"HKCU\Software\Microsoft".parse()?)?;
+/// # Ok::<(), winpoke::error::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Regedit {
+    window: WindowInfo,
+}
+
+impl Regedit {
+    /// 查找已运行的注册表编辑器
+    pub fn find() -> Result<Option<Self>> {
+        let window = WindowInfo::find_by_class_name(REGEDIT_CLASS)?
+            .into_iter()
+            .next();
+
+        Ok(window.map(|window| Self { window }))
+    }
+
+    /// 使用已运行的注册表编辑器，没有时启动一个并等待主窗口出现
+    pub fn open() -> Result<Self> {
+        if let Some(regedit) = Self::find()? {
+            return Ok(regedit);
+        }
+
+        launch()?;
+
+        let started = Instant::now();
+        loop {
+            if let Some(regedit) = Self::find()? {
+                return Ok(regedit);
+            }
+            if started.elapsed() > LAUNCH_TIMEOUT {
+                return Err(Error::WindowNotFound);
+            }
+            sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// 主窗口
+    pub fn window(&self) -> &WindowInfo {
+        &self.window
+    }
+
+    /// 左侧的键树
+    pub fn keys(&self) -> Result<TreeView> {
+        TreeView::find_in(&self.window)
+    }
+
+    /// 右侧的值列表
+    pub fn values(&self) -> Result<ListView> {
+        ListView::find_in(&self.window)
+    }
+
+    /// 定位并选中键
+    ///
+    /// 接受 [`RegistryPath`] 支持的各种写法，如 `HKLM\SOFTWARE`、`HKLM:\SOFTWARE`、
+    /// `计算机\HKEY_LOCAL_MACHINE\SOFTWARE`。
+    pub fn jump(&self, key: &RegistryPath) -> Result<()> {
+        // 显示和激活窗口只为方便查看，失败（如被前台锁定拦截）不影响定位
+        let _ = self.window.show_window();
+        let _ = self.window.set_focus();
+
+        self.keys()?.select_path(key.to_string())?;
+
+        Ok(())
+    }
+
+    /// 在当前键的值列表中选中值，名称不区分大小写，空字符串表示默认值
+    ///
+    /// 选中键后值列表可能稍后才刷新，找不到时会在短时间内重试。
+    pub fn select_value(&self, name: impl AsRef<str>) -> Result<()> {
+        let name = name.as_ref();
+        let lowercase = name.to_lowercase();
+        let values = self.values()?;
+
+        let started = Instant::now();
+        let row = loop {
+            let names = values.column(0)?;
+
+            // 默认值总是第一行，显示的名称随系统语言变化
+            let row = if name.is_empty() && !names.is_empty() {
+                Some(0)
+            } else {
+                names
+                    .iter()
+                    .position(|value| value.to_lowercase() == lowercase)
+            };
+
+            match row {
+                Some(row) => break row,
+                None if started.elapsed() > VALUES_TIMEOUT => {
+                    return Err(Error::ListItemNotFound(name.to_string()));
+                }
+                None => sleep(POLL_INTERVAL),
+            }
+        };
+
+        values.select(row)
+    }
+
+    /// 定位键，并在 `value` 不为 `None` 时选中其中的值
+    pub fn jump_to_value(&self, key: &RegistryPath, value: Option<&str>) -> Result<()> {
+        self.jump(key)?;
+
+        match value {
+            Some(value) => self.select_value(value),
+            None => Ok(()),
+        }
+    }
+}
+
+/// 启动注册表编辑器
+fn launch() -> Result<()> {
+    let windir = std::env::var("WINDIR").unwrap_or_else(|_| r"C:\Windows".to_string());
+
+    let process = create_process(format!(r"{windir}\regedit.exe")).map_err(|e| match e {
+        // 注册表编辑器的清单要求管理员权限，普通进程无法直接创建
+        Error::WindowsError(ref error) if error.code() == ERROR_ELEVATION_REQUIRED.to_hresult() => {
+            Error::ElevationRequired
+        }
+        e => e,
+    })?;
+
+    let waited = wait_for_input_idle(process, LAUNCH_TIMEOUT.as_millis() as u32);
+    unsafe { CloseHandle(process) }?;
+    waited?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jump() {
+        let regedit = Regedit::open().unwrap();
+        regedit
+            .jump_to_value(&r"HKCU:\Environment".parse().unwrap(), Some("Path"))
+            .unwrap();
+
+        let values = regedit.values().unwrap();
+        let selected = values.selected_rows().unwrap();
+        assert_eq!(selected.len(), 1);
+        assert!(
+            values
+                .cell_text(selected[0], 0)
+                .unwrap()
+                .eq_ignore_ascii_case("Path")
+        );
+    }
+}
@@ -0,0 +1,38 @@
+//! 异步接口（`async` 特性），基于 tokio
+//!
+//! 窗口操作会阻塞线程：跨进程发送消息要等目标程序处理，等待窗口时要反复查找。
+//! [`UiThread`] 在一个专用线程上持有 [`Backend`](crate::script::Backend)
+//! 并依次执行操作，异步任务只等待结果而不占用 tokio 的工作线程。
+//!
+//! 所有操作都可以取消：future 在操作开始执行前被丢弃时操作不会执行，
+//! [`UiThread::send`] 逐条发送消息，取消后不再发送剩余的消息，
+//! [`UiThread::wait_for`] 在两次查找之间的等待中取消。等待使用 tokio 的计时器，
+//! 因此可以在测试中用暂停的时间（`start_paused`）配合
+//! [`FakeDesktop`](crate::script::FakeDesktop) 验证超时。
+//!
+//! ```no_run
+//! # #[cfg(windows)]
+//! # async fn example() -> winpoke::prelude::Result<()> {
+//! use std::time::Duration;
+//! use winpoke::asynchronous::UiThread;
+//! use winpoke::script::Desktop;
+//!
+//! let ui = UiThread::spawn_with(Desktop::new);
+//! ui.launch("notepad.exe").await?;
+//!
+//! let path = "Notepad > Edit".parse()?;
+//! let editor = ui.wait_for(&path, Duration::from_secs(5)).await?;
+//! ui.type_text(&editor, "hello").await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Windows 上还可以用 [`AsyncEventSubscription`] 异步接收窗口事件。
+
+#[cfg(windows)]
+mod events;
+mod ui_thread;
+
+#[cfg(windows)]
+pub use events::AsyncEventSubscription;
+pub use ui_thread::UiThread;
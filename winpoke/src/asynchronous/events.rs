@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+use crate::prelude::{Result, Selector};
+use crate::window::event::{EventSubscription, WindowEvent};
+
+/// 转发线程检查接收端是否已丢弃的间隔
+const CLOSE_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 异步的窗口事件订阅
+///
+/// 由一个转发线程把 [`EventSubscription`] 的事件送入 tokio 通道。
+/// 订阅被丢弃后，转发线程最迟在 100 毫秒内注销钩子并退出。
+///
+/// ```no_run
+/// # async fn example() -> winpoke::prelude::Result<()> {
+/// use winpoke::asynchronous::AsyncEventSubscription;
+/// use winpoke::selector::Selector;
+/// use winpoke::window::event::WindowEventKind;
+///
+/// let mut events = AsyncEventSubscription::new(Selector::class_name("Notepad"))?;
+/// while let Some(event) = events.recv().await {
+///     if event.kind == WindowEventKind::Created {
+///         break;
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct AsyncEventSubscription {
+    receiver: UnboundedReceiver<WindowEvent>,
+}
+
+impl AsyncEventSubscription {
+    /// 订阅匹配选择器的窗口的事件
+    pub fn new(selector: Selector) -> Result<Self> {
+        let subscription = EventSubscription::new(selector)?;
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        std::thread::spawn(move || {
+            while !sender.is_closed() {
+                if let Some(event) = subscription.recv_timeout(CLOSE_CHECK_INTERVAL)
+                    && sender.send(event).is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self { receiver })
+    }
+
+    /// 订阅指定进程的所有窗口事件
+    pub fn for_pid(pid: u32) -> Result<Self> {
+        Self::new(Selector::pid(pid))
+    }
+
+    /// 等待下一个事件，可以安全地在 `select!` 中取消
+    pub async fn recv(&mut self) -> Option<WindowEvent> {
+        self.receiver.recv().await
+    }
+
+    /// 获取已到达的事件，不等待
+    pub fn try_recv(&mut self) -> Option<WindowEvent> {
+        self.receiver.try_recv().ok()
+    }
+}
@@ -0,0 +1,284 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use tokio::sync::oneshot;
+use tokio::time::{Instant, sleep};
+
+use crate::error::Error;
+use crate::message::Message;
+use crate::prelude::Result;
+use crate::script::{Backend, WAIT_INTERVAL, WindowHandle};
+use crate::selector::SelectorPath;
+
+type Job<B> = Box<dyn FnOnce(&mut B) + Send>;
+
+/// 在专用线程上执行窗口操作的执行器
+///
+/// 操作按提交的顺序在同一线程上执行，该线程持有后端。克隆得到的是同一线程的句柄，
+/// 所有句柄都被丢弃后线程退出。操作中 panic 会结束线程，之后的操作返回
+/// [`Error::MessageLoopExited`]。
+///
+/// 异步方法需要在 tokio 运行时中调用。
+pub struct UiThread<B> {
+    sender: mpsc::Sender<Job<B>>,
+}
+
+impl<B> Clone for UiThread<B> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+/// future 被丢弃时标记操作已取消
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Release);
+    }
+}
+
+impl<B: Backend + Send + 'static> UiThread<B> {
+    /// 启动线程，把后端移到线程上
+    pub fn spawn(backend: B) -> Self {
+        Self::spawn_with(move || backend)
+    }
+}
+
+impl<B: Backend + 'static> UiThread<B> {
+    /// 启动线程并在线程上创建后端，后端不需要实现 `Send`
+    pub fn spawn_with(create: impl FnOnce() -> B + Send + 'static) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job<B>>();
+
+        std::thread::spawn(move || {
+            let mut backend = create();
+            for job in receiver {
+                job(&mut backend);
+            }
+        });
+
+        Self { sender }
+    }
+
+    /// 在线程上执行任意操作
+    ///
+    /// 返回的 future 在操作开始前被丢弃时操作不会执行；已经开始的操作会执行完，但结果被丢弃。
+    pub async fn call<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut B) -> Result<T> + Send + 'static,
+    {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let (reply, result) = oneshot::channel();
+
+        let flag = cancelled.clone();
+        let job: Job<B> = Box::new(move |backend| {
+            if !flag.load(Ordering::Acquire) {
+                let _ = reply.send(f(backend));
+            }
+        });
+        self.sender
+            .send(job)
+            .map_err(|_| Error::MessageLoopExited)?;
+
+        let _cancel = CancelOnDrop(cancelled);
+        result.await.unwrap_or(Err(Error::MessageLoopExited))
+    }
+
+    /// 按选择器路径查找窗口
+    pub async fn find(&self, path: &SelectorPath) -> Result<Vec<WindowHandle>> {
+        let path = path.clone();
+        self.call(move |backend| backend.find(&path)).await
+    }
+
+    /// 窗口当前的文本
+    pub async fn text(&self, window: &WindowHandle) -> Result<String> {
+        let window = window.clone();
+        self.call(move |backend| backend.text(&window)).await
+    }
+
+    /// 激活窗口
+    pub async fn focus(&self, window: &WindowHandle) -> Result<()> {
+        let window = window.clone();
+        self.call(move |backend| backend.focus(&window)).await
+    }
+
+    /// 输入文本
+    pub async fn type_text(&self, window: &WindowHandle, text: &str) -> Result<()> {
+        let (window, text) = (window.clone(), text.to_string());
+        self.call(move |backend| backend.type_text(&window, &text))
+            .await
+    }
+
+    /// 按下组合键
+    pub async fn key(&self, window: &WindowHandle, vks: &[u32]) -> Result<()> {
+        let (window, vks) = (window.clone(), vks.to_vec());
+        self.call(move |backend| backend.key(&window, &vks)).await
+    }
+
+    /// 按顺序发送消息
+    ///
+    /// 每条消息单独提交，其间可以执行其它操作；取消后不再发送剩余的消息。
+    pub async fn send(&self, window: &WindowHandle, messages: &[Message]) -> Result<()> {
+        for message in messages {
+            let (window, message) = (window.clone(), message.clone());
+            self.call(move |backend| backend.send(&window, &[message]))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// 启动程序，返回进程 ID
+    pub async fn launch(&self, command: &str) -> Result<u32> {
+        let command = command.to_string();
+        self.call(move |backend| backend.launch(&command)).await
+    }
+
+    /// 等待匹配的窗口出现并返回第一个，超时返回 [`Error::WindowNotFound`]
+    pub async fn wait_for(&self, path: &SelectorPath, timeout: Duration) -> Result<WindowHandle> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(window) = self.find(path).await?.into_iter().next() {
+                return Ok(window);
+            }
+            if Instant::now() >= deadline {
+                return Err(Error::WindowNotFound);
+            }
+
+            sleep(WAIT_INTERVAL).await;
+        }
+    }
+
+    /// 等待匹配的窗口全部消失，超时返回 [`Error::WaitTimeout`]
+    pub async fn wait_gone(&self, path: &SelectorPath, timeout: Duration) -> Result<()> {
+        let deadline = Instant::now() + timeout;
+        while !self.find(path).await?.is_empty() {
+            if Instant::now() >= deadline {
+                return Err(Error::WaitTimeout(format!("{path} 仍未关闭")));
+            }
+
+            sleep(WAIT_INTERVAL).await;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::WindowMessage;
+    use crate::script::{FakeDesktop, FakeWindow};
+
+    fn path(s: &str) -> SelectorPath {
+        s.parse().unwrap()
+    }
+
+    fn history(ui: &UiThread<FakeDesktop>) -> impl Future<Output = Result<Vec<String>>> {
+        ui.call(|desktop| Ok(desktop.history().to_vec()))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn wait_for_window_shown_later() {
+        let ui = UiThread::spawn(FakeDesktop::new());
+
+        let shower = ui.clone();
+        tokio::spawn(async move {
+            sleep(Duration::from_millis(1250)).await;
+            shower
+                .call(|desktop| Ok(desktop.add(FakeWindow::new("Notepad", "无标题"))))
+                .await
+        });
+
+        let start = Instant::now();
+        let window = ui
+            .wait_for(&path("Notepad"), Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(window.caption, "无标题");
+        // 等待 UI 线程时运行时空闲，时间可能直接推进到窗口出现，此后的第一次查找必定找到
+        let elapsed = start.elapsed();
+        assert!(
+            elapsed >= Duration::from_millis(1250)
+                && elapsed <= Duration::from_millis(1250) + WAIT_INTERVAL,
+            "{elapsed:?}"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waits_time_out() {
+        let ui = UiThread::spawn(FakeDesktop::new().with_window(FakeWindow::new("Notepad", "")));
+
+        let start = Instant::now();
+        let result = ui.wait_for(&path("Dialog"), Duration::from_secs(2)).await;
+        assert!(matches!(result, Err(Error::WindowNotFound)), "{result:?}");
+        assert_eq!(start.elapsed(), Duration::from_secs(2));
+
+        let start = Instant::now();
+        let result = ui
+            .wait_gone(&path("Notepad"), Duration::from_millis(500))
+            .await;
+        assert!(matches!(result, Err(Error::WaitTimeout(_))), "{result:?}");
+        assert_eq!(start.elapsed(), Duration::from_millis(500));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn launch_and_send() {
+        let ui = UiThread::spawn(
+            FakeDesktop::new().with_program("notepad.exe", FakeWindow::new("Notepad", "")),
+        );
+
+        assert_eq!(ui.launch("notepad.exe").await.unwrap(), 1000);
+        let window = ui
+            .wait_for(&path("Notepad"), Duration::from_secs(1))
+            .await
+            .unwrap();
+        let messages = [
+            Message {
+                msg: WindowMessage::Char('h'),
+                count: 1,
+            },
+            Message {
+                msg: WindowMessage::Char('i'),
+                count: 2,
+            },
+        ];
+        ui.send(&window, &messages).await.unwrap();
+
+        assert_eq!(ui.text(&window).await.unwrap(), "hii");
+        assert_eq!(history(&ui).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cancelled_calls_do_not_run() {
+        // 线程在创建后端时等待，提交的操作只能排队
+        let (release, blocked) = mpsc::channel::<()>();
+        let ui = UiThread::spawn_with(move || {
+            let _ = blocked.recv();
+            FakeDesktop::new().with_window(FakeWindow::new("Notepad", ""))
+        });
+        let window = WindowHandle {
+            id: 0,
+            class_name: "Notepad".to_string(),
+            caption: String::new(),
+        };
+
+        // 轮询一次后丢弃，操作还在排队
+        tokio::select! {
+            biased;
+            _ = ui.focus(&window) => panic!("线程被占用时操作不应完成"),
+            _ = std::future::ready(()) => {}
+        }
+
+        release.send(()).unwrap();
+        assert!(history(&ui).await.unwrap().is_empty());
+
+        ui.focus(&window).await.unwrap();
+        assert_eq!(history(&ui).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,128 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::error::Error;
+
+/// RGB 颜色
+///
+/// 可以从 `#RRGGBB`、`#RGB` 或颜色名（如 `red`）解析。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const RED: Color = Color::rgb(0xFF, 0x00, 0x00);
+    pub const GREEN: Color = Color::rgb(0x00, 0xFF, 0x00);
+    pub const BLUE: Color = Color::rgb(0x00, 0x00, 0xFF);
+    pub const YELLOW: Color = Color::rgb(0xFF, 0xFF, 0x00);
+    pub const CYAN: Color = Color::rgb(0x00, 0xFF, 0xFF);
+    pub const MAGENTA: Color = Color::rgb(0xFF, 0x00, 0xFF);
+    pub const ORANGE: Color = Color::rgb(0xFF, 0xA5, 0x00);
+    pub const WHITE: Color = Color::rgb(0xFF, 0xFF, 0xFF);
+    pub const BLACK: Color = Color::rgb(0x00, 0x00, 0x00);
+
+    /// 可以按名称解析的颜色
+    const NAMED: &[(&str, Color)] = &[
+        ("red", Color::RED),
+        ("green", Color::GREEN),
+        ("blue", Color::BLUE),
+        ("yellow", Color::YELLOW),
+        ("cyan", Color::CYAN),
+        ("magenta", Color::MAGENTA),
+        ("orange", Color::ORANGE),
+        ("white", Color::WHITE),
+        ("black", Color::BLACK),
+    ];
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// GDI 使用的 `COLORREF` 值（`0x00BBGGRR`）
+    pub fn to_colorref(self) -> u32 {
+        (self.b as u32) << 16 | (self.g as u32) << 8 | self.r as u32
+    }
+}
+
+impl Default for Color {
+    fn default() -> Self {
+        Color::RED
+    }
+}
+
+impl Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+    }
+}
+
+impl FromStr for Color {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidColor(s.to_string());
+        let s = s.trim();
+
+        let Some(hex) = s.strip_prefix('#') else {
+            return Self::NAMED
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(s))
+                .map(|&(_, color)| color)
+                .ok_or_else(invalid);
+        };
+
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        let channel = |digits: &str| u8::from_str_radix(digits, 16).map_err(|_| invalid());
+        match hex.len() {
+            // #RGB 相当于 #RRGGBB
+            3 => {
+                let digit = |i: usize| channel(&hex[i..=i]).map(|v| v * 0x11);
+                Ok(Self::rgb(digit(0)?, digit(1)?, digit(2)?))
+            }
+            6 => Ok(Self::rgb(
+                channel(&hex[0..2])?,
+                channel(&hex[2..4])?,
+                channel(&hex[4..6])?,
+            )),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            "#FF8000".parse::<Color>().unwrap(),
+            Color::rgb(0xFF, 0x80, 0x00)
+        );
+        assert_eq!(
+            "#0af".parse::<Color>().unwrap(),
+            Color::rgb(0x00, 0xAA, 0xFF)
+        );
+        assert_eq!(" Red ".parse::<Color>().unwrap(), Color::RED);
+
+        for s in [
+            "", "#", "#12", "#12345", "#GGGGGG", "#+1+2+3", "purple", "FF0000",
+        ] {
+            assert!(s.parse::<Color>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn display_and_colorref() {
+        let color = Color::rgb(0x12, 0x34, 0x56);
+        assert_eq!(color.to_string(), "#123456");
+        assert_eq!(color.to_string().parse::<Color>().unwrap(), color);
+        assert_eq!(color.to_colorref(), 0x563412);
+    }
+}
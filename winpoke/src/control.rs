@@ -0,0 +1,172 @@
+pub mod button;
+pub mod combo_box;
+pub mod edit;
+mod items;
+pub mod list_box;
+pub mod list_view;
+pub mod remote;
+pub mod tree_view;
+
+use windows::Win32::Foundation::{LPARAM, WPARAM};
+use windows::Win32::UI::WindowsAndMessaging::{GetDlgCtrlID, GetParent, WM_COMMAND};
+
+pub use button::{Button, CheckState};
+pub use combo_box::ComboBox;
+pub use edit::Edit;
+pub use list_box::ListBox;
+pub use list_view::ListView;
+pub use tree_view::TreeView;
+
+use crate::error::Error;
+use crate::prelude::Result;
+use crate::window::WindowInfo;
+use crate::window::msg::post_message;
+
+/// 按窗口类名识别的标准控件
+///
+/// ```no_run
+/// use winpoke::control::Control;
+/// use winpoke::prelude::*;
+///
+/// let dialog = WindowInfo::find(&"#32770".parse()?)?.remove(0);
+/// for child in dialog.get_child_windows()? {
+///     if let Control::Edit(edit) = Control::from(child) {
+///         edit.set_text("hello")?;
+///     }
+/// }
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug)]
+pub enum Control {
+    Edit(Edit),
+    Button(Button),
+    ComboBox(ComboBox),
+    ListBox(ListBox),
+    ListView(ListView),
+    TreeView(TreeView),
+
+    /// 无法识别的窗口
+    Other(WindowInfo),
+}
+
+impl From<WindowInfo> for Control {
+    fn from(window: WindowInfo) -> Self {
+        let class = window.class_name.as_str();
+
+        if class.eq_ignore_ascii_case(edit::EDIT_CLASS) {
+            Control::Edit(Edit { window })
+        } else if class.eq_ignore_ascii_case(button::BUTTON_CLASS) {
+            Control::Button(Button { window })
+        } else if class.eq_ignore_ascii_case(combo_box::COMBO_BOX_CLASS) {
+            Control::ComboBox(ComboBox { window })
+        } else if class.eq_ignore_ascii_case(list_box::LIST_BOX_CLASS) {
+            Control::ListBox(ListBox { window })
+        } else if class.eq_ignore_ascii_case(list_view::LIST_VIEW_CLASS) {
+            Control::ListView(ListView { window })
+        } else if class.eq_ignore_ascii_case(tree_view::TREE_VIEW_CLASS) {
+            Control::TreeView(TreeView { window })
+        } else {
+            Control::Other(window)
+        }
+    }
+}
+
+impl Control {
+    /// 控件窗口
+    pub fn window(&self) -> &WindowInfo {
+        match self {
+            Control::Edit(control) => control.window(),
+            Control::Button(control) => control.window(),
+            Control::ComboBox(control) => control.window(),
+            Control::ListBox(control) => control.window(),
+            Control::ListView(control) => control.window(),
+            Control::TreeView(control) => control.window(),
+            Control::Other(window) => window,
+        }
+    }
+}
+
+/// 检查窗口类名（不区分大小写），不匹配时返回 [`Error::ClassMismatch`]
+pub(crate) fn expect_class(window: WindowInfo, expected: &'static str) -> Result<WindowInfo> {
+    if !window.class_name.eq_ignore_ascii_case(expected) {
+        return Err(Error::ClassMismatch {
+            expected,
+            found: window.class_name,
+        });
+    }
+
+    Ok(window)
+}
+
+/// 查找窗口中第一个指定类名的直接子窗口
+pub(crate) fn find_child(parent: &WindowInfo, class_name: &'static str) -> Result<WindowInfo> {
+    parent
+        .get_child_windows_with_class_name(class_name)?
+        .into_iter()
+        .find(|w| w.class_name.eq_ignore_ascii_case(class_name))
+        .ok_or(Error::WindowNotFound)
+}
+
+/// 以 `WM_COMMAND` 向父窗口发送控件通知
+///
+/// 通过消息修改控件状态（如 `CB_SETCURSEL`）时控件不会通知父窗口，
+/// 程序依赖通知更新界面时需要手动补发。
+pub(crate) fn notify_parent(window: &WindowInfo, code: u32) -> Result<()> {
+    let parent = unsafe { GetParent(window.hwnd) }.map_err(|_| Error::WindowNotFound)?;
+    let id = unsafe { GetDlgCtrlID(window.hwnd) } as u16 as usize;
+
+    post_message(
+        parent,
+        WM_COMMAND,
+        WPARAM(id | (code as usize) << 16),
+        LPARAM(window.hwnd.0 as isize),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(class_name: &str) -> WindowInfo {
+        WindowInfo {
+            class_name: class_name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn resolve_by_class_name() {
+        assert!(matches!(Control::from(window("Edit")), Control::Edit(_)));
+        assert!(matches!(
+            Control::from(window("BUTTON")),
+            Control::Button(_)
+        ));
+        assert!(matches!(
+            Control::from(window("ComboBox")),
+            Control::ComboBox(_)
+        ));
+        assert!(matches!(
+            Control::from(window("ListBox")),
+            Control::ListBox(_)
+        ));
+        assert!(matches!(
+            Control::from(window("SysListView32")),
+            Control::ListView(_)
+        ));
+        assert!(matches!(
+            Control::from(window("SysTreeView32")),
+            Control::TreeView(_)
+        ));
+        assert!(matches!(Control::from(window("Static")), Control::Other(_)));
+    }
+
+    #[test]
+    fn class_mismatch() {
+        let err = Edit::new(window("Button")).unwrap_err();
+        assert!(
+            matches!(&err, Error::ClassMismatch { expected: "Edit", found } if found == "Button"),
+            "{err:?}"
+        );
+        assert!(Edit::new(window("EDIT")).is_ok());
+    }
+}
@@ -0,0 +1,133 @@
+use windows::Win32::UI::Controls::{BST_CHECKED, BST_INDETERMINATE, BST_UNCHECKED};
+use windows::Win32::UI::WindowsAndMessaging::{BM_CLICK, BM_GETCHECK, BM_SETCHECK};
+
+use crate::control::{expect_class, find_child};
+use crate::error::Error;
+use crate::prelude::Result;
+use crate::window::WindowInfo;
+
+/// 按钮的窗口类名，复选框和单选按钮也使用该类
+pub const BUTTON_CLASS: &str = "Button";
+
+/// 复选框和单选按钮的选中状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckState {
+    Unchecked,
+    Checked,
+
+    /// 三态复选框的不确定状态
+    Indeterminate,
+}
+
+impl CheckState {
+    fn from_raw(value: u32) -> Self {
+        match value {
+            v if v == BST_CHECKED.0 => CheckState::Checked,
+            v if v == BST_INDETERMINATE.0 => CheckState::Indeterminate,
+            _ => CheckState::Unchecked,
+        }
+    }
+
+    fn to_raw(self) -> u32 {
+        match self {
+            CheckState::Unchecked => BST_UNCHECKED.0,
+            CheckState::Checked => BST_CHECKED.0,
+            CheckState::Indeterminate => BST_INDETERMINATE.0,
+        }
+    }
+}
+
+/// 按钮（`Button`），包括普通按钮、复选框和单选按钮
+#[derive(Debug)]
+pub struct Button {
+    pub(crate) window: WindowInfo,
+}
+
+impl TryFrom<WindowInfo> for Button {
+    type Error = Error;
+
+    fn try_from(window: WindowInfo) -> Result<Self> {
+        Self::new(window)
+    }
+}
+
+impl Button {
+    /// 包装按钮窗口，类名不是 `Button` 时返回错误
+    pub fn new(window: WindowInfo) -> Result<Self> {
+        let window = expect_class(window, BUTTON_CLASS)?;
+
+        Ok(Self { window })
+    }
+
+    /// 查找窗口中的第一个按钮
+    pub fn find_in(parent: &WindowInfo) -> Result<Self> {
+        find_child(parent, BUTTON_CLASS).and_then(Self::new)
+    }
+
+    /// 查找窗口中文本为 `caption` 的按钮，忽略 `&` 助记符
+    pub fn find_by_caption(parent: &WindowInfo, caption: impl AsRef<str>) -> Result<Self> {
+        let caption = caption.as_ref().replace('&', "");
+
+        parent
+            .get_child_windows()?
+            .into_iter()
+            .find(|w| {
+                w.class_name.eq_ignore_ascii_case(BUTTON_CLASS)
+                    && w.caption.replace('&', "") == caption
+            })
+            .ok_or(Error::WindowNotFound)
+            .and_then(Self::new)
+    }
+
+    /// 控件窗口
+    pub fn window(&self) -> &WindowInfo {
+        &self.window
+    }
+
+    /// 点击按钮
+    ///
+    /// 通过 `BM_CLICK` 模拟按下和松开，父窗口会收到 `BN_CLICKED` 通知。
+    /// 消息是投递的，点击后弹出模态对话框时不会阻塞；所在窗口不是活动窗口时部分程序会忽略点击。
+    pub fn click(&self) -> Result<()> {
+        self.window.post_raw(BM_CLICK, 0, 0)
+    }
+
+    /// 复选框或单选按钮的选中状态，普通按钮总是 [`CheckState::Unchecked`]
+    pub fn check_state(&self) -> Result<CheckState> {
+        let state = self.window.send_raw(BM_GETCHECK, 0, 0)?;
+
+        Ok(CheckState::from_raw(state as u32))
+    }
+
+    /// 是否选中
+    pub fn is_checked(&self) -> Result<bool> {
+        Ok(self.check_state()? == CheckState::Checked)
+    }
+
+    /// 设置选中状态
+    ///
+    /// `BM_SETCHECK` 不会通知父窗口，程序需要响应状态变化时改用 [`click`](Self::click)。
+    pub fn set_check_state(&self, state: CheckState) -> Result<()> {
+        self.window
+            .send_raw(BM_SETCHECK, state.to_raw() as usize, 0)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_state_round_trip() {
+        for state in [
+            CheckState::Unchecked,
+            CheckState::Checked,
+            CheckState::Indeterminate,
+        ] {
+            assert_eq!(CheckState::from_raw(state.to_raw()), state);
+        }
+        assert_eq!(CheckState::from_raw(0xFF), CheckState::Unchecked);
+    }
+}
@@ -0,0 +1,106 @@
+use windows::Win32::UI::WindowsAndMessaging::{
+    CB_FINDSTRINGEXACT, CB_GETCOUNT, CB_GETCURSEL, CB_GETLBTEXT, CB_GETLBTEXTLEN, CB_SETCURSEL,
+    CBN_SELCHANGE,
+};
+
+use crate::control::items::{ItemMessages, Items};
+use crate::control::{expect_class, find_child};
+use crate::error::Error;
+use crate::prelude::Result;
+use crate::window::WindowInfo;
+
+/// 组合框的窗口类名
+pub const COMBO_BOX_CLASS: &str = "ComboBox";
+
+const MESSAGES: ItemMessages = ItemMessages {
+    count: CB_GETCOUNT,
+    text_len: CB_GETLBTEXTLEN,
+    text: CB_GETLBTEXT,
+    get_cur_sel: CB_GETCURSEL,
+    set_cur_sel: CB_SETCURSEL,
+    find_exact: CB_FINDSTRINGEXACT,
+    sel_change: CBN_SELCHANGE,
+};
+
+/// 组合框（`ComboBox`）
+///
+/// 自绘且没有 `CBS_HASSTRINGS` 样式的组合框无法读取条目文本；可编辑组合框中的文本
+/// 通过 [`window`](Self::window) 的 [`text`](WindowInfo::text) 读取。
+#[derive(Debug)]
+pub struct ComboBox {
+    pub(crate) window: WindowInfo,
+}
+
+impl TryFrom<WindowInfo> for ComboBox {
+    type Error = Error;
+
+    fn try_from(window: WindowInfo) -> Result<Self> {
+        Self::new(window)
+    }
+}
+
+impl ComboBox {
+    /// 包装组合框窗口，类名不是 `ComboBox` 时返回错误
+    pub fn new(window: WindowInfo) -> Result<Self> {
+        let window = expect_class(window, COMBO_BOX_CLASS)?;
+
+        Ok(Self { window })
+    }
+
+    /// 查找窗口中的第一个组合框
+    pub fn find_in(parent: &WindowInfo) -> Result<Self> {
+        find_child(parent, COMBO_BOX_CLASS).and_then(Self::new)
+    }
+
+    /// 控件窗口
+    pub fn window(&self) -> &WindowInfo {
+        &self.window
+    }
+
+    fn items(&self) -> Items<'_> {
+        Items {
+            window: &self.window,
+            messages: &MESSAGES,
+        }
+    }
+
+    /// 条目数量
+    pub fn count(&self) -> Result<usize> {
+        self.items().count()
+    }
+
+    /// 所有条目的文本
+    pub fn item_texts(&self) -> Result<Vec<String>> {
+        self.items().all()
+    }
+
+    /// 条目文本
+    pub fn item_text(&self, index: usize) -> Result<String> {
+        self.items().text(index)
+    }
+
+    /// 当前选中的条目，可编辑组合框中输入的文本不对应任何条目
+    pub fn selected_index(&self) -> Result<Option<usize>> {
+        self.items().selected()
+    }
+
+    /// 当前选中条目的文本
+    pub fn selected_text(&self) -> Result<Option<String>> {
+        self.selected_index()?
+            .map(|index| self.item_text(index))
+            .transpose()
+    }
+
+    /// 按序号选中条目，并以 `CBN_SELCHANGE` 通知父窗口
+    pub fn select_index(&self, index: usize) -> Result<()> {
+        self.items().select(index)
+    }
+
+    /// 选中文本相同（不区分大小写）的条目，返回其序号
+    pub fn select_text(&self, text: impl AsRef<str>) -> Result<usize> {
+        let index = self.items().find(text.as_ref())?;
+        self.select_index(index)?;
+
+        Ok(index)
+    }
+}
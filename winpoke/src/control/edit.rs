@@ -0,0 +1,118 @@
+use windows::Win32::UI::Controls::{EM_GETSEL, EM_REPLACESEL, EM_SETSEL};
+use windows::Win32::UI::WindowsAndMessaging::{ES_READONLY, GWL_STYLE, GetWindowLongW, WM_SETTEXT};
+
+use crate::control::{expect_class, find_child};
+use crate::error::Error;
+use crate::prelude::Result;
+use crate::window::WindowInfo;
+
+/// 编辑框的窗口类名
+pub const EDIT_CLASS: &str = "Edit";
+
+/// 编辑框（`Edit`）
+///
+/// 位置均以 UTF-16 单元计。
+///
+/// ```no_run
+/// use winpoke::control::Edit;
+/// use winpoke::prelude::*;
+///
+/// let notepad = WindowInfo::find_by_class_name("Notepad")?.remove(0);
+/// let edit = Edit::find_in(&notepad)?;
+/// edit.set_text("hello")?;
+/// edit.select(0, 0)?;
+/// edit.replace_selection("// ")?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Edit {
+    pub(crate) window: WindowInfo,
+}
+
+impl TryFrom<WindowInfo> for Edit {
+    type Error = Error;
+
+    fn try_from(window: WindowInfo) -> Result<Self> {
+        Self::new(window)
+    }
+}
+
+impl Edit {
+    /// 包装编辑框窗口，类名不是 `Edit` 时返回错误
+    pub fn new(window: WindowInfo) -> Result<Self> {
+        let window = expect_class(window, EDIT_CLASS)?;
+
+        Ok(Self { window })
+    }
+
+    /// 查找窗口中的第一个编辑框
+    pub fn find_in(parent: &WindowInfo) -> Result<Self> {
+        find_child(parent, EDIT_CLASS).and_then(Self::new)
+    }
+
+    /// 控件窗口
+    pub fn window(&self) -> &WindowInfo {
+        &self.window
+    }
+
+    /// 编辑框的内容
+    pub fn text(&self) -> Result<String> {
+        self.window.text()
+    }
+
+    /// 替换编辑框的全部内容，不能撤销
+    pub fn set_text(&self, text: impl AsRef<str>) -> Result<()> {
+        let wide: Vec<u16> = text.as_ref().encode_utf16().chain(Some(0)).collect();
+
+        // WM_SETTEXT 由系统跨进程封送，可以直接传本进程的缓冲区
+        self.window
+            .send_raw(WM_SETTEXT, 0, wide.as_ptr() as isize)?;
+
+        Ok(())
+    }
+
+    /// 当前选中的范围(起,止)，没有选中时起止相同，即光标位置
+    pub fn selection(&self) -> Result<(usize, usize)> {
+        let (mut start, mut end) = (0u32, 0u32);
+
+        // EM_GETSEL 由系统跨进程封送，结果写回本进程的变量
+        self.window.send_raw(
+            EM_GETSEL,
+            &mut start as *mut u32 as usize,
+            &mut end as *mut u32 as isize,
+        )?;
+
+        Ok((start as usize, end as usize))
+    }
+
+    /// 选中 `start` 到 `end` 之间的文本，`end` 超出文本长度时选到末尾
+    pub fn select(&self, start: usize, end: usize) -> Result<()> {
+        self.window.send_raw(EM_SETSEL, start, end as isize)?;
+
+        Ok(())
+    }
+
+    /// 选中全部文本
+    pub fn select_all(&self) -> Result<()> {
+        self.window.send_raw(EM_SETSEL, 0, -1)?;
+
+        Ok(())
+    }
+
+    /// 用 `text` 替换选中的文本，没有选中时在光标处插入，可以撤销
+    pub fn replace_selection(&self, text: impl AsRef<str>) -> Result<()> {
+        let wide: Vec<u16> = text.as_ref().encode_utf16().chain(Some(0)).collect();
+
+        self.window
+            .send_raw(EM_REPLACESEL, 1, wide.as_ptr() as isize)?;
+
+        Ok(())
+    }
+
+    /// 是否为只读
+    pub fn is_read_only(&self) -> bool {
+        let style = unsafe { GetWindowLongW(self.window.hwnd, GWL_STYLE) };
+
+        style & ES_READONLY != 0
+    }
+}
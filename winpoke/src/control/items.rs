@@ -0,0 +1,76 @@
+use crate::control::notify_parent;
+use crate::error::Error;
+use crate::prelude::Result;
+use crate::window::WindowInfo;
+
+/// 列表框和组合框中功能相同、编号不同的消息
+pub(crate) struct ItemMessages {
+    pub count: u32,
+    pub text_len: u32,
+    pub text: u32,
+    pub get_cur_sel: u32,
+    pub set_cur_sel: u32,
+    pub find_exact: u32,
+    pub sel_change: u32,
+}
+
+/// 以消息读写带字符串条目的控件
+///
+/// 这些消息由系统跨进程封送，文本可以直接读入本进程的缓冲区。
+pub(crate) struct Items<'a> {
+    pub window: &'a WindowInfo,
+    pub messages: &'static ItemMessages,
+}
+
+impl Items<'_> {
+    /// 消息返回 -1（`LB_ERR`/`CB_ERR`）时为 `None`
+    fn send(&self, msg: u32, wparam: usize, lparam: isize) -> Result<Option<usize>> {
+        let ret = self.window.send_raw(msg, wparam, lparam)? as isize;
+
+        Ok((ret >= 0).then_some(ret as usize))
+    }
+
+    pub fn count(&self) -> Result<usize> {
+        Ok(self.send(self.messages.count, 0, 0)?.unwrap_or(0))
+    }
+
+    pub fn text(&self, index: usize) -> Result<String> {
+        let not_found = || Error::ListItemNotFound(format!("第 {index} 项"));
+
+        let len = self
+            .send(self.messages.text_len, index, 0)?
+            .ok_or_else(not_found)?;
+        let mut buffer = vec![0u16; len + 1];
+        let len = self
+            .send(self.messages.text, index, buffer.as_mut_ptr() as isize)?
+            .ok_or_else(not_found)?;
+
+        Ok(String::from_utf16_lossy(&buffer[..len.min(buffer.len())]))
+    }
+
+    pub fn all(&self) -> Result<Vec<String>> {
+        (0..self.count()?).map(|index| self.text(index)).collect()
+    }
+
+    pub fn selected(&self) -> Result<Option<usize>> {
+        self.send(self.messages.get_cur_sel, 0, 0)
+    }
+
+    pub fn select(&self, index: usize) -> Result<()> {
+        if index >= self.count()? {
+            return Err(Error::ListItemNotFound(format!("第 {index} 项")));
+        }
+
+        self.send(self.messages.set_cur_sel, index, 0)?;
+        notify_parent(self.window, self.messages.sel_change)
+    }
+
+    /// 查找文本完全相同（不区分大小写）的条目
+    pub fn find(&self, text: &str) -> Result<usize> {
+        let wide: Vec<u16> = text.encode_utf16().chain(Some(0)).collect();
+
+        // wParam 为 -1 表示从头查找
+        self.send(self.messages.find_exact, usize::MAX, wide.as_ptr() as isize)?
+            .ok_or_else(|| Error::ListItemNotFound(text.to_string()))
+    }
+}
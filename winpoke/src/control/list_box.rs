@@ -0,0 +1,105 @@
+use windows::Win32::UI::WindowsAndMessaging::{
+    LB_FINDSTRINGEXACT, LB_GETCOUNT, LB_GETCURSEL, LB_GETTEXT, LB_GETTEXTLEN, LB_SETCURSEL,
+    LBN_SELCHANGE,
+};
+
+use crate::control::items::{ItemMessages, Items};
+use crate::control::{expect_class, find_child};
+use crate::error::Error;
+use crate::prelude::Result;
+use crate::window::WindowInfo;
+
+/// 列表框的窗口类名
+pub const LIST_BOX_CLASS: &str = "ListBox";
+
+const MESSAGES: ItemMessages = ItemMessages {
+    count: LB_GETCOUNT,
+    text_len: LB_GETTEXTLEN,
+    text: LB_GETTEXT,
+    get_cur_sel: LB_GETCURSEL,
+    set_cur_sel: LB_SETCURSEL,
+    find_exact: LB_FINDSTRINGEXACT,
+    sel_change: LBN_SELCHANGE,
+};
+
+/// 列表框（`ListBox`）
+///
+/// 只支持单选列表框；自绘且没有 `LBS_HASSTRINGS` 样式的列表框无法读取文本。
+#[derive(Debug)]
+pub struct ListBox {
+    pub(crate) window: WindowInfo,
+}
+
+impl TryFrom<WindowInfo> for ListBox {
+    type Error = Error;
+
+    fn try_from(window: WindowInfo) -> Result<Self> {
+        Self::new(window)
+    }
+}
+
+impl ListBox {
+    /// 包装列表框窗口，类名不是 `ListBox` 时返回错误
+    pub fn new(window: WindowInfo) -> Result<Self> {
+        let window = expect_class(window, LIST_BOX_CLASS)?;
+
+        Ok(Self { window })
+    }
+
+    /// 查找窗口中的第一个列表框
+    pub fn find_in(parent: &WindowInfo) -> Result<Self> {
+        find_child(parent, LIST_BOX_CLASS).and_then(Self::new)
+    }
+
+    /// 控件窗口
+    pub fn window(&self) -> &WindowInfo {
+        &self.window
+    }
+
+    fn items(&self) -> Items<'_> {
+        Items {
+            window: &self.window,
+            messages: &MESSAGES,
+        }
+    }
+
+    /// 条目数量
+    pub fn count(&self) -> Result<usize> {
+        self.items().count()
+    }
+
+    /// 所有条目的文本
+    pub fn item_texts(&self) -> Result<Vec<String>> {
+        self.items().all()
+    }
+
+    /// 条目文本
+    pub fn item_text(&self, index: usize) -> Result<String> {
+        self.items().text(index)
+    }
+
+    /// 当前选中的条目
+    pub fn selected_index(&self) -> Result<Option<usize>> {
+        self.items().selected()
+    }
+
+    /// 当前选中条目的文本
+    pub fn selected_text(&self) -> Result<Option<String>> {
+        self.selected_index()?
+            .map(|index| self.item_text(index))
+            .transpose()
+    }
+
+    /// 按序号选中条目，并以 `LBN_SELCHANGE` 通知父窗口
+    pub fn select_index(&self, index: usize) -> Result<()> {
+        self.items().select(index)
+    }
+
+    /// 选中文本相同（不区分大小写）的条目，返回其序号
+    pub fn select_text(&self, text: impl AsRef<str>) -> Result<usize> {
+        let index = self.items().find(text.as_ref())?;
+        self.select_index(index)?;
+
+        Ok(index)
+    }
+}
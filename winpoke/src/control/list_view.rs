@@ -0,0 +1,600 @@
+use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+use windows::Win32::UI::Controls::{
+    HDI_TEXT, HDM_GETITEMCOUNT, HDM_GETITEMW, LVIF_STATE, LVIR_LABEL, LVIS_FOCUSED, LVIS_SELECTED,
+    LVM_ENSUREVISIBLE, LVM_GETHEADER, LVM_GETITEMCOUNT, LVM_GETITEMRECT, LVM_GETITEMSTATE,
+    LVM_GETITEMTEXTW, LVM_GETNEXTITEM, LVM_SETITEMSTATE, LVNI_SELECTED,
+};
+
+use crate::control::remote::{
+    LayoutReader, LayoutWriter, PointerWidth, RemoteBuffer, RemoteLayout, RemoteProcess, RemoteText,
+};
+use crate::control::{expect_class, find_child};
+use crate::error::Error;
+use crate::input::MouseButton;
+use crate::prelude::Result;
+use crate::window::msg::{Delivery, send_message_timeout};
+use crate::window::{DEFAULT_MESSAGE_TIMEOUT, WindowInfo};
+
+/// 列表控件的窗口类名
+pub const LIST_VIEW_CLASS: &str = "SysListView32";
+
+/// 读取单元格和列标题时的缓冲区大小（UTF-16 单元）
+const TEXT_CAPACITY: usize = 1024;
+
+/// 与目标进程指针宽度无关的 `LVITEMW`
+#[derive(Debug, Default)]
+struct LvItem {
+    mask: u32,
+    item: i32,
+    sub_item: i32,
+    state: u32,
+    state_mask: u32,
+    text: usize,
+    text_max: i32,
+    image: i32,
+    lparam: usize,
+    indent: i32,
+    group_id: i32,
+    columns: u32,
+    pu_columns: usize,
+    col_fmt: usize,
+    group: i32,
+}
+
+impl RemoteLayout for LvItem {
+    fn size(width: PointerWidth) -> usize {
+        match width {
+            PointerWidth::Bits32 => 60,
+            PointerWidth::Bits64 => 88,
+        }
+    }
+
+    fn encode(&self, writer: &mut LayoutWriter) {
+        writer
+            .u32(self.mask)
+            .i32(self.item)
+            .i32(self.sub_item)
+            .u32(self.state)
+            .u32(self.state_mask)
+            .ptr(self.text)
+            .i32(self.text_max)
+            .i32(self.image)
+            .ptr(self.lparam)
+            .i32(self.indent)
+            .i32(self.group_id)
+            .u32(self.columns)
+            .ptr(self.pu_columns)
+            .ptr(self.col_fmt)
+            .i32(self.group);
+    }
+
+    fn decode(reader: &mut LayoutReader) -> Self {
+        Self {
+            mask: reader.u32(),
+            item: reader.i32(),
+            sub_item: reader.i32(),
+            state: reader.u32(),
+            state_mask: reader.u32(),
+            text: reader.ptr(),
+            text_max: reader.i32(),
+            image: reader.i32(),
+            lparam: reader.ptr(),
+            indent: reader.i32(),
+            group_id: reader.i32(),
+            columns: reader.u32(),
+            pu_columns: reader.ptr(),
+            col_fmt: reader.ptr(),
+            group: reader.i32(),
+        }
+    }
+}
+
+/// 与目标进程指针宽度无关的 `HDITEMW`
+#[derive(Debug, Default)]
+struct HdItem {
+    mask: u32,
+    cxy: i32,
+    text: usize,
+    bitmap: usize,
+    text_max: i32,
+    fmt: i32,
+    lparam: usize,
+    image: i32,
+    order: i32,
+    filter_type: u32,
+    filter: usize,
+    state: u32,
+}
+
+impl RemoteLayout for HdItem {
+    fn size(width: PointerWidth) -> usize {
+        match width {
+            PointerWidth::Bits32 => 48,
+            PointerWidth::Bits64 => 72,
+        }
+    }
+
+    fn encode(&self, writer: &mut LayoutWriter) {
+        writer
+            .u32(self.mask)
+            .i32(self.cxy)
+            .ptr(self.text)
+            .ptr(self.bitmap)
+            .i32(self.text_max)
+            .i32(self.fmt)
+            .ptr(self.lparam)
+            .i32(self.image)
+            .i32(self.order)
+            .u32(self.filter_type)
+            .ptr(self.filter)
+            .u32(self.state);
+    }
+
+    fn decode(reader: &mut LayoutReader) -> Self {
+        Self {
+            mask: reader.u32(),
+            cxy: reader.i32(),
+            text: reader.ptr(),
+            bitmap: reader.ptr(),
+            text_max: reader.i32(),
+            fmt: reader.i32(),
+            lparam: reader.ptr(),
+            image: reader.i32(),
+            order: reader.i32(),
+            filter_type: reader.u32(),
+            filter: reader.ptr(),
+            state: reader.u32(),
+        }
+    }
+}
+
+/// `RECT`，各字段宽度与指针无关
+#[derive(Debug, Default)]
+struct Rect {
+    left: i32,
+    top: i32,
+    right: i32,
+    bottom: i32,
+}
+
+impl RemoteLayout for Rect {
+    fn size(_: PointerWidth) -> usize {
+        16
+    }
+
+    fn encode(&self, writer: &mut LayoutWriter) {
+        writer
+            .i32(self.left)
+            .i32(self.top)
+            .i32(self.right)
+            .i32(self.bottom);
+    }
+
+    fn decode(reader: &mut LayoutReader) -> Self {
+        Self {
+            left: reader.i32(),
+            top: reader.i32(),
+            right: reader.i32(),
+            bottom: reader.i32(),
+        }
+    }
+}
+
+/// 列表控件（`SysListView32`）
+///
+/// 通过 `LVM_*` 消息读取和操作其它进程中的列表控件，行和列均从 0 开始编号，
+/// 第 0 列为项目本身的文本。
+///
+/// ```no_run
+/// use winpoke::control::ListView;
+/// use winpoke::prelude::*;
+///
+/// let regedit = WindowInfo::find_by_class_name("RegEdit_RegEdit")?.remove(0);
+/// let list = ListView::find_in(&regedit)?;
+/// println!("{:?}", list.headers()?);
+///
+/// let row = list.find_row(0, "(默认)")?;
+/// list.select(row)?;
+/// print!("{}", list.to_csv()?);
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug)]
+pub struct ListView {
+    pub(crate) window: WindowInfo,
+}
+
+impl TryFrom<WindowInfo> for ListView {
+    type Error = Error;
+
+    fn try_from(window: WindowInfo) -> Result<Self> {
+        Self::new(window)
+    }
+}
+
+impl ListView {
+    /// 包装列表控件窗口，类名不是 `SysListView32` 时返回错误
+    pub fn new(window: WindowInfo) -> Result<Self> {
+        let window = expect_class(window, LIST_VIEW_CLASS)?;
+
+        Ok(Self { window })
+    }
+
+    /// 查找窗口中的第一个列表控件
+    pub fn find_in(parent: &WindowInfo) -> Result<Self> {
+        find_child(parent, LIST_VIEW_CLASS).and_then(Self::new)
+    }
+
+    /// 控件窗口
+    pub fn window(&self) -> &WindowInfo {
+        &self.window
+    }
+
+    /// 表头控件，非详细信息视图可能没有
+    fn header(&self) -> Result<Option<HWND>> {
+        let header = self.window.send_raw(LVM_GETHEADER, 0, 0)?;
+
+        Ok((header != 0).then_some(HWND(header as _)))
+    }
+
+    /// 列数，没有表头时视为只有 1 列
+    pub fn column_count(&self) -> Result<usize> {
+        let Some(header) = self.header()? else {
+            return Ok(1);
+        };
+
+        let count = send_message_timeout(
+            header,
+            HDM_GETITEMCOUNT,
+            WPARAM(0),
+            LPARAM(0),
+            DEFAULT_MESSAGE_TIMEOUT,
+        )?;
+
+        // 失败时返回 -1
+        Ok(match count as isize {
+            count if count > 0 => count as usize,
+            _ => 1,
+        })
+    }
+
+    /// 列标题，没有表头时返回空列表
+    pub fn headers(&self) -> Result<Vec<String>> {
+        let Some(header) = self.header()? else {
+            return Ok(Vec::new());
+        };
+
+        let process = RemoteProcess::open(self.window.pid)?;
+        let text = RemoteText::new(&process, TEXT_CAPACITY)?;
+        let hd_item = RemoteBuffer::new(&process, &HdItem::default())?;
+
+        let mut headers = Vec::new();
+        for column in 0..self.column_count()? {
+            text.write("")?;
+            hd_item.write(&HdItem {
+                mask: HDI_TEXT.0,
+                text: text.address(),
+                text_max: TEXT_CAPACITY as i32,
+                ..Default::default()
+            })?;
+
+            send_message_timeout(
+                header,
+                HDM_GETITEMW,
+                WPARAM(column),
+                LPARAM(hd_item.address() as isize),
+                DEFAULT_MESSAGE_TIMEOUT,
+            )?;
+            headers.push(text.read()?);
+        }
+
+        Ok(headers)
+    }
+
+    /// 行数
+    pub fn row_count(&self) -> Result<usize> {
+        self.window.send_raw(LVM_GETITEMCOUNT, 0, 0)
+    }
+
+    /// 在目标进程中批量读取单元格文本
+    fn read_cells(&self, cells: impl IntoIterator<Item = (usize, usize)>) -> Result<Vec<String>> {
+        let process = RemoteProcess::open(self.window.pid)?;
+        let text = RemoteText::new(&process, TEXT_CAPACITY)?;
+        let lv_item = RemoteBuffer::new(&process, &LvItem::default())?;
+
+        let mut values = Vec::new();
+        for (row, column) in cells {
+            lv_item.write(&LvItem {
+                sub_item: column as i32,
+                text: text.address(),
+                text_max: TEXT_CAPACITY as i32,
+                ..Default::default()
+            })?;
+
+            // 返回值为文本长度，文本写入我们提供的缓冲区
+            let len = self
+                .window
+                .send_raw(LVM_GETITEMTEXTW, row, lv_item.address() as isize)?;
+            values.push(if len == 0 {
+                String::new()
+            } else {
+                text.read()?
+            });
+        }
+
+        Ok(values)
+    }
+
+    /// 单元格文本
+    pub fn cell_text(&self, row: usize, column: usize) -> Result<String> {
+        if row >= self.row_count()? {
+            return Err(Error::ListItemNotFound(format!("第 {row} 行")));
+        }
+
+        Ok(self.read_cells([(row, column)])?.remove(0))
+    }
+
+    /// 一行中所有列的文本
+    pub fn row(&self, row: usize) -> Result<Vec<String>> {
+        if row >= self.row_count()? {
+            return Err(Error::ListItemNotFound(format!("第 {row} 行")));
+        }
+
+        let columns = self.column_count()?;
+        self.read_cells((0..columns).map(|column| (row, column)))
+    }
+
+    /// 所有行的文本
+    pub fn rows(&self) -> Result<Vec<Vec<String>>> {
+        let columns = self.column_count()?;
+        let cells = (0..self.row_count()?)
+            .flat_map(|row| (0..columns).map(move |column| (row, column)))
+            .collect::<Vec<_>>();
+
+        Ok(self
+            .read_cells(cells)?
+            .chunks(columns)
+            .map(<[String]>::to_vec)
+            .collect())
+    }
+
+    /// 一列中所有行的文本
+    pub fn column(&self, column: usize) -> Result<Vec<String>> {
+        let rows = self.row_count()?;
+        self.read_cells((0..rows).map(|row| (row, column)))
+    }
+
+    /// 查找指定列文本与 `text` 相同的第一行
+    pub fn find_row(&self, column: usize, text: impl AsRef<str>) -> Result<usize> {
+        let text = text.as_ref();
+
+        self.column(column)?
+            .iter()
+            .position(|value| value == text)
+            .ok_or_else(|| Error::ListItemNotFound(text.to_string()))
+    }
+
+    /// 行是否被选中
+    pub fn is_selected(&self, row: usize) -> Result<bool> {
+        let state = self
+            .window
+            .send_raw(LVM_GETITEMSTATE, row, LVIS_SELECTED.0 as isize)?;
+
+        Ok(state as u32 & LVIS_SELECTED.0 != 0)
+    }
+
+    /// 所有选中的行
+    pub fn selected_rows(&self) -> Result<Vec<usize>> {
+        let mut rows = Vec::new();
+        let mut start = -1isize;
+
+        loop {
+            // 从 start 之后开始查找，-1 表示从头开始；找不到时返回 -1
+            let next =
+                self.window
+                    .send_raw(LVM_GETNEXTITEM, start as usize, LVNI_SELECTED as isize)?
+                    as isize;
+            if next < 0 {
+                break;
+            }
+
+            rows.push(next as usize);
+            start = next;
+        }
+
+        Ok(rows)
+    }
+
+    /// 设置行的状态，`row` 为 `None` 时作用于所有行
+    fn set_state(&self, row: Option<usize>, state: u32, mask: u32) -> Result<()> {
+        let process = RemoteProcess::open(self.window.pid)?;
+        let lv_item = RemoteBuffer::new(
+            &process,
+            &LvItem {
+                mask: LVIF_STATE.0,
+                state,
+                state_mask: mask,
+                ..Default::default()
+            },
+        )?;
+
+        let index = row.map_or(usize::MAX, |row| row);
+        if self
+            .window
+            .send_raw(LVM_SETITEMSTATE, index, lv_item.address() as isize)?
+            == 0
+        {
+            return Err(Error::ListItemNotFound(format!("第 {index} 行")));
+        }
+
+        Ok(())
+    }
+
+    /// 只选中指定的行，并设为焦点行、滚动到可见位置
+    pub fn select(&self, row: usize) -> Result<()> {
+        self.set_state(None, 0, LVIS_SELECTED.0)?;
+        let state = LVIS_SELECTED.0 | LVIS_FOCUSED.0;
+        self.set_state(Some(row), state, state)?;
+        self.ensure_visible(row)
+    }
+
+    /// 将行设为焦点行，不改变选中状态
+    pub fn focus(&self, row: usize) -> Result<()> {
+        self.set_state(Some(row), LVIS_FOCUSED.0, LVIS_FOCUSED.0)
+    }
+
+    /// 滚动到行可见的位置
+    pub fn ensure_visible(&self, row: usize) -> Result<()> {
+        self.window.send_raw(LVM_ENSUREVISIBLE, row, 0)?;
+
+        Ok(())
+    }
+
+    /// 双击行的文本，通常会打开该项目
+    pub fn double_click_row(&self, row: usize) -> Result<()> {
+        self.ensure_visible(row)?;
+
+        let process = RemoteProcess::open(self.window.pid)?;
+        let rect = RemoteBuffer::new(
+            &process,
+            &Rect {
+                left: LVIR_LABEL as i32,
+                ..Default::default()
+            },
+        )?;
+
+        if self
+            .window
+            .send_raw(LVM_GETITEMRECT, row, rect.address() as isize)?
+            == 0
+        {
+            return Err(Error::ListItemNotFound(format!("第 {row} 行")));
+        }
+
+        // 控件返回的矩形已是物理像素
+        let rect = rect.read()?;
+        let center = ((rect.left + rect.right) / 2, (rect.top + rect.bottom) / 2);
+        self.window
+            .mouse(Delivery::Message)
+            .physical()
+            .double_click(center, MouseButton::Left)
+    }
+
+    /// 以 CSV 格式导出表头和所有行
+    pub fn to_csv(&self) -> Result<String> {
+        Ok(to_csv(&self.headers()?, &self.rows()?))
+    }
+}
+
+/// 按 RFC 4180 转义 CSV 字段
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 将表头和各行拼接为 CSV，表头为空时不输出表头行
+pub(crate) fn to_csv(headers: &[String], rows: &[Vec<String>]) -> String {
+    let mut csv = String::new();
+
+    for line in (!headers.is_empty())
+        .then_some(headers)
+        .into_iter()
+        .chain(rows.iter().map(Vec::as_slice))
+    {
+        let fields: Vec<String> = line.iter().map(|value| csv_field(value)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn lv_item_layout_matches_native() {
+        use windows::Win32::UI::Controls::LVITEMW;
+
+        let mut writer = LayoutWriter::new(PointerWidth::native());
+        LvItem::default().encode(&mut writer);
+
+        assert_eq!(writer.finish().len(), size_of::<LVITEMW>());
+        assert_eq!(LvItem::size(PointerWidth::native()), size_of::<LVITEMW>());
+    }
+
+    #[test]
+    fn hd_item_layout_matches_native() {
+        use windows::Win32::UI::Controls::HDITEMW;
+
+        let mut writer = LayoutWriter::new(PointerWidth::native());
+        HdItem::default().encode(&mut writer);
+
+        assert_eq!(writer.finish().len(), size_of::<HDITEMW>());
+        assert_eq!(HdItem::size(PointerWidth::native()), size_of::<HDITEMW>());
+    }
+
+    #[test]
+    fn layouts_32() {
+        let mut writer = LayoutWriter::new(PointerWidth::Bits32);
+        LvItem::default().encode(&mut writer);
+        assert_eq!(writer.finish().len(), LvItem::size(PointerWidth::Bits32));
+
+        let mut writer = LayoutWriter::new(PointerWidth::Bits32);
+        HdItem::default().encode(&mut writer);
+        assert_eq!(writer.finish().len(), HdItem::size(PointerWidth::Bits32));
+    }
+
+    #[test]
+    fn csv_escaping() {
+        let headers = strings(&["名称", "类型", "数据"]);
+        let rows = vec![
+            strings(&["(默认)", "REG_SZ", "(数值未设置)"]),
+            strings(&["Path", "REG_EXPAND_SZ", "C:\\a,b"]),
+            strings(&["Quote", "REG_SZ", "say \"hi\"\nbye"]),
+        ];
+
+        assert_eq!(
+            to_csv(&headers, &rows),
+            "名称,类型,数据\r\n\
+             (默认),REG_SZ,(数值未设置)\r\n\
+             Path,REG_EXPAND_SZ,\"C:\\a,b\"\r\n\
+             Quote,REG_SZ,\"say \"\"hi\"\"\nbye\"\r\n"
+        );
+    }
+
+    #[test]
+    fn csv_without_headers() {
+        assert_eq!(to_csv(&[], &[strings(&["a"]), strings(&[""])]), "a\r\n\r\n");
+        assert_eq!(to_csv(&[], &[]), "");
+    }
+
+    #[test]
+    fn test_read_regedit_values() {
+        let regedit = WindowInfo::find_by_class_name("RegEdit_RegEdit")
+            .unwrap()
+            .remove(0);
+        let list = ListView::find_in(&regedit).expect("找不到列表控件");
+
+        let headers = list.headers().unwrap();
+        assert_eq!(headers.len(), list.column_count().unwrap());
+
+        for row in list.rows().unwrap() {
+            assert_eq!(row.len(), headers.len());
+            println!("{row:?}");
+        }
+
+        if list.row_count().unwrap() > 0 {
+            list.select(0).unwrap();
+            assert_eq!(list.selected_rows().unwrap(), vec![0]);
+            assert!(list.is_selected(0).unwrap());
+        }
+    }
+}
@@ -0,0 +1,624 @@
+use std::ffi::c_void;
+use std::marker::PhantomData;
+
+use windows::Win32::Foundation::{CloseHandle, HANDLE};
+use windows::Win32::System::Diagnostics::Debug::{ReadProcessMemory, WriteProcessMemory};
+use windows::Win32::System::Memory::{
+    MEM_COMMIT, MEM_RELEASE, MEM_RESERVE, PAGE_READWRITE, VirtualAllocEx, VirtualFreeEx,
+};
+use windows::Win32::System::Threading::{GetCurrentProcess, IsWow64Process};
+use windows::core::BOOL;
+
+use crate::error::Error;
+use crate::prelude::Result;
+use crate::window::active::open_process;
+
+/// 内存页大小，分段读取时每段不跨越页边界
+const PAGE_SIZE: usize = 0x1000;
+
+/// 目标进程的指针宽度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointerWidth {
+    Bits32,
+    Bits64,
+}
+
+impl PointerWidth {
+    /// 当前进程的指针宽度
+    pub const fn native() -> Self {
+        if cfg!(target_pointer_width = "64") {
+            PointerWidth::Bits64
+        } else {
+            PointerWidth::Bits32
+        }
+    }
+
+    /// 指针的字节数，同时也是指针的对齐要求
+    pub const fn bytes(self) -> usize {
+        match self {
+            PointerWidth::Bits32 => 4,
+            PointerWidth::Bits64 => 8,
+        }
+    }
+}
+
+/// 进程内存的分配与读写
+///
+/// 真实实现为 [`RemoteProcess`]，测试时可以替换为进程内的假实现。
+pub trait ProcessMemory {
+    /// 目标进程的指针宽度，决定结构体的内存布局
+    fn pointer_width(&self) -> PointerWidth;
+
+    /// 分配 `size` 字节的可读写内存，返回其在目标进程中的地址
+    fn alloc(&self, size: usize) -> Result<usize>;
+
+    /// 释放 [`alloc`](Self::alloc) 分配的内存
+    fn free(&self, address: usize) -> Result<()>;
+
+    fn read(&self, address: usize, buffer: &mut [u8]) -> Result<()>;
+
+    fn write(&self, address: usize, bytes: &[u8]) -> Result<()>;
+}
+
+/// 通过 `VirtualAllocEx`/`ReadProcessMemory`/`WriteProcessMemory` 访问其它进程的内存
+pub struct RemoteProcess {
+    handle: HANDLE,
+    width: PointerWidth,
+}
+
+impl RemoteProcess {
+    /// 打开进程，并根据 WOW64 状态判断其指针宽度
+    pub fn open(pid: u32) -> Result<Self> {
+        let handle = open_process(pid)?;
+
+        let is_wow64 = |process: HANDLE| {
+            let mut wow64 = BOOL(0);
+            unsafe { IsWow64Process(process, &mut wow64) }.map(|_| wow64.as_bool())
+        };
+
+        let width = match (is_wow64(handle), is_wow64(unsafe { GetCurrentProcess() })) {
+            // 目标运行在 WOW64 下一定是 32 位进程
+            (Ok(true), _) => PointerWidth::Bits32,
+            // 本进程是 32 位而目标不在 WOW64 下，说明目标是 64 位进程
+            (Ok(false), Ok(true)) => PointerWidth::Bits64,
+            (Ok(false), Ok(false)) => PointerWidth::native(),
+            (Err(e), _) | (_, Err(e)) => {
+                let _ = unsafe { CloseHandle(handle) };
+                return Err(e.into());
+            }
+        };
+
+        Ok(Self { handle, width })
+    }
+}
+
+impl Drop for RemoteProcess {
+    fn drop(&mut self) {
+        let _ = unsafe { CloseHandle(self.handle) };
+    }
+}
+
+impl ProcessMemory for RemoteProcess {
+    fn pointer_width(&self) -> PointerWidth {
+        self.width
+    }
+
+    fn alloc(&self, size: usize) -> Result<usize> {
+        let address = unsafe {
+            VirtualAllocEx(
+                self.handle,
+                None,
+                size,
+                MEM_COMMIT | MEM_RESERVE,
+                PAGE_READWRITE,
+            )
+        };
+
+        if address.is_null() {
+            return Err(Error::RemoteMemoryFailed);
+        }
+
+        Ok(address as usize)
+    }
+
+    fn free(&self, address: usize) -> Result<()> {
+        unsafe { VirtualFreeEx(self.handle, address as *mut c_void, 0, MEM_RELEASE) }
+            .map_err(|_| Error::RemoteMemoryFailed)
+    }
+
+    fn read(&self, address: usize, buffer: &mut [u8]) -> Result<()> {
+        unsafe {
+            ReadProcessMemory(
+                self.handle,
+                address as *const c_void,
+                buffer.as_mut_ptr().cast(),
+                buffer.len(),
+                None,
+            )
+        }
+        .map_err(|_| Error::RemoteMemoryFailed)
+    }
+
+    fn write(&self, address: usize, bytes: &[u8]) -> Result<()> {
+        unsafe {
+            WriteProcessMemory(
+                self.handle,
+                address as *const c_void,
+                bytes.as_ptr().cast(),
+                bytes.len(),
+                None,
+            )
+        }
+        .map_err(|_| Error::RemoteMemoryFailed)
+    }
+}
+
+/// 按目标进程的指针宽度序列化结构体
+///
+/// 字段按 C 的对齐规则排列：指针按指针宽度对齐，结构体总大小按最大对齐补齐。
+pub struct LayoutWriter {
+    width: PointerWidth,
+    bytes: Vec<u8>,
+    align: usize,
+}
+
+impl LayoutWriter {
+    pub fn new(width: PointerWidth) -> Self {
+        Self {
+            width,
+            bytes: Vec::new(),
+            align: 4,
+        }
+    }
+
+    fn pad_to(&mut self, align: usize) {
+        self.align = self.align.max(align);
+        self.bytes
+            .resize(self.bytes.len().next_multiple_of(align), 0);
+    }
+
+    pub fn u32(&mut self, value: u32) -> &mut Self {
+        self.pad_to(4);
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub fn i32(&mut self, value: i32) -> &mut Self {
+        self.u32(value as u32)
+    }
+
+    /// 指针或指针大小的整数（`HTREEITEM`、`LPARAM` 等）
+    pub fn ptr(&mut self, value: usize) -> &mut Self {
+        let size = self.width.bytes();
+        self.pad_to(size);
+        self.bytes
+            .extend_from_slice(&(value as u64).to_le_bytes()[..size]);
+        self
+    }
+
+    /// 完成序列化，按最大对齐补齐结构体大小
+    pub fn finish(&mut self) -> Vec<u8> {
+        let align = self.align;
+        self.pad_to(align);
+        std::mem::take(&mut self.bytes)
+    }
+}
+
+/// 按目标进程的指针宽度反序列化结构体，字段顺序需与 [`LayoutWriter`] 一致
+pub struct LayoutReader<'a> {
+    width: PointerWidth,
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> LayoutReader<'a> {
+    pub fn new(width: PointerWidth, bytes: &'a [u8]) -> Self {
+        Self {
+            width,
+            bytes,
+            offset: 0,
+        }
+    }
+
+    fn take(&mut self, size: usize) -> &'a [u8] {
+        self.offset = self.offset.next_multiple_of(size);
+        let field = &self.bytes[self.offset..self.offset + size];
+        self.offset += size;
+        field
+    }
+
+    pub fn u32(&mut self) -> u32 {
+        u32::from_le_bytes(self.take(4).try_into().unwrap())
+    }
+
+    pub fn i32(&mut self) -> i32 {
+        self.u32() as i32
+    }
+
+    pub fn ptr(&mut self) -> usize {
+        let mut value = [0u8; 8];
+        let size = self.width.bytes();
+        value[..size].copy_from_slice(self.take(size));
+
+        u64::from_le_bytes(value) as usize
+    }
+}
+
+/// 可以写入其它进程内存的结构体
+///
+/// 指针字段的大小随目标进程而变，因此不能直接复制本进程中的内存布局。
+pub trait RemoteLayout: Sized {
+    /// 在指定指针宽度下序列化后的大小
+    fn size(width: PointerWidth) -> usize;
+
+    fn encode(&self, writer: &mut LayoutWriter);
+
+    fn decode(reader: &mut LayoutReader) -> Self;
+}
+
+/// 在目标进程中分配的内存，`Drop` 时释放
+struct Allocation<'a, M: ProcessMemory> {
+    memory: &'a M,
+    address: usize,
+    size: usize,
+}
+
+impl<'a, M: ProcessMemory> Allocation<'a, M> {
+    fn new(memory: &'a M, size: usize) -> Result<Self> {
+        Ok(Self {
+            memory,
+            address: memory.alloc(size)?,
+            size,
+        })
+    }
+}
+
+impl<M: ProcessMemory> Drop for Allocation<'_, M> {
+    fn drop(&mut self) {
+        let _ = self.memory.free(self.address);
+    }
+}
+
+/// 位于目标进程中的结构体 `T`
+///
+/// 通用控件的消息（如 `TVM_GETITEMW`）以指针传递结构体，系统不会为这些消息跨进程复制数据，
+/// 因此结构体必须位于目标进程的地址空间中。`Drop` 时释放内存。
+pub struct RemoteBuffer<'a, T: RemoteLayout, M: ProcessMemory = RemoteProcess> {
+    allocation: Allocation<'a, M>,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T: RemoteLayout, M: ProcessMemory> RemoteBuffer<'a, T, M> {
+    /// 在目标进程中分配并写入 `value`
+    pub fn new(memory: &'a M, value: &T) -> Result<Self> {
+        let buffer = Self {
+            allocation: Allocation::new(memory, T::size(memory.pointer_width()))?,
+            _marker: PhantomData,
+        };
+        buffer.write(value)?;
+
+        Ok(buffer)
+    }
+
+    /// 结构体在目标进程中的地址，作为消息的 `lParam` 传递
+    pub fn address(&self) -> usize {
+        self.allocation.address
+    }
+
+    pub fn write(&self, value: &T) -> Result<()> {
+        let mut writer = LayoutWriter::new(self.allocation.memory.pointer_width());
+        value.encode(&mut writer);
+
+        self.allocation
+            .memory
+            .write(self.allocation.address, &writer.finish())
+    }
+
+    pub fn read(&self) -> Result<T> {
+        let mut bytes = vec![0u8; self.allocation.size];
+        self.allocation
+            .memory
+            .read(self.allocation.address, &mut bytes)?;
+
+        let width = self.allocation.memory.pointer_width();
+        Ok(T::decode(&mut LayoutReader::new(width, &bytes)))
+    }
+}
+
+/// 位于目标进程中的 UTF-16 文本缓冲区
+pub struct RemoteText<'a, M: ProcessMemory = RemoteProcess> {
+    allocation: Allocation<'a, M>,
+    capacity: usize,
+}
+
+impl<'a, M: ProcessMemory> RemoteText<'a, M> {
+    /// 分配可容纳 `capacity` 个 UTF-16 单元（含结尾的 NUL）的缓冲区
+    pub fn new(memory: &'a M, capacity: usize) -> Result<Self> {
+        Ok(Self {
+            allocation: Allocation::new(memory, capacity * 2)?,
+            capacity,
+        })
+    }
+
+    /// 缓冲区在目标进程中的地址
+    pub fn address(&self) -> usize {
+        self.allocation.address
+    }
+
+    /// 缓冲区可容纳的 UTF-16 单元数
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// 写入文本（以 NUL 结尾），超出容量时截断
+    pub fn write(&self, text: &str) -> Result<()> {
+        let mut units: Vec<u16> = text
+            .encode_utf16()
+            .take(self.capacity.saturating_sub(1))
+            .collect();
+        units.push(0);
+
+        let bytes: Vec<u8> = units.iter().flat_map(|unit| unit.to_le_bytes()).collect();
+        self.allocation
+            .memory
+            .write(self.allocation.address, &bytes)
+    }
+
+    /// 读取以 NUL 结尾的文本
+    pub fn read(&self) -> Result<String> {
+        read_utf16(self.allocation.memory, self.address(), self.capacity)
+    }
+}
+
+/// 读取目标进程中 `address` 处以 NUL 结尾的 UTF-16 字符串，最多 `capacity` 个单元
+///
+/// 用于读取控件返回的、指向其自身缓冲区的文本指针。字符串可能位于已提交内存的末尾，
+/// 因此按页分段读取，读到 NUL 即停止；之后的页无法读取时返回已读到的部分。
+pub fn read_utf16<M: ProcessMemory>(memory: &M, address: usize, capacity: usize) -> Result<String> {
+    let end = address + capacity * 2;
+    let mut units = Vec::new();
+    let mut current = address;
+    while current < end {
+        // 读到下一个页边界为止，并保证每段都是完整的 UTF-16 单元
+        let page_end = (current / PAGE_SIZE + 1) * PAGE_SIZE;
+        let chunk_end = match page_end - current {
+            1 => page_end + 1,
+            len if len.is_multiple_of(2) => page_end,
+            _ => page_end - 1,
+        };
+        let mut bytes = vec![0u8; chunk_end.min(end) - current];
+        match memory.read(current, &mut bytes) {
+            Ok(()) => {}
+            Err(_) if current != address => break,
+            Err(e) => return Err(e),
+        }
+
+        for pair in bytes.chunks_exact(2) {
+            match u16::from_le_bytes([pair[0], pair[1]]) {
+                0 => return Ok(String::from_utf16_lossy(&units)),
+                unit => units.push(unit),
+            }
+        }
+        current += bytes.len();
+    }
+
+    Ok(String::from_utf16_lossy(&units))
+}
+
+#[cfg(test)]
+pub(crate) mod fake {
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    /// 进程内的假内存，地址从一个远离 0 的值开始分配，以便发现未正确换算的指针
+    pub(crate) struct LocalMemory {
+        width: PointerWidth,
+        blocks: RefCell<BTreeMap<usize, Vec<u8>>>,
+        next: RefCell<usize>,
+    }
+
+    impl LocalMemory {
+        pub(crate) fn new(width: PointerWidth) -> Self {
+            Self {
+                width,
+                blocks: RefCell::new(BTreeMap::new()),
+                next: RefCell::new(0x1000_0000),
+            }
+        }
+
+        /// 尚未释放的分配数量
+        pub(crate) fn live_allocations(&self) -> usize {
+            self.blocks.borrow().len()
+        }
+
+        fn locate(&self, address: usize, len: usize) -> Result<(usize, usize)> {
+            self.blocks
+                .borrow()
+                .range(..=address)
+                .next_back()
+                .filter(|(start, block)| address + len <= *start + block.len())
+                .map(|(&start, _)| (start, address - start))
+                .ok_or(Error::RemoteMemoryFailed)
+        }
+    }
+
+    impl ProcessMemory for LocalMemory {
+        fn pointer_width(&self) -> PointerWidth {
+            self.width
+        }
+
+        fn alloc(&self, size: usize) -> Result<usize> {
+            let mut next = self.next.borrow_mut();
+            let address = *next;
+            *next += size.next_multiple_of(0x1000).max(0x1000);
+
+            self.blocks.borrow_mut().insert(address, vec![0; size]);
+            Ok(address)
+        }
+
+        fn free(&self, address: usize) -> Result<()> {
+            self.blocks
+                .borrow_mut()
+                .remove(&address)
+                .map(|_| ())
+                .ok_or(Error::RemoteMemoryFailed)
+        }
+
+        fn read(&self, address: usize, buffer: &mut [u8]) -> Result<()> {
+            let (start, offset) = self.locate(address, buffer.len())?;
+            buffer.copy_from_slice(&self.blocks.borrow()[&start][offset..offset + buffer.len()]);
+            Ok(())
+        }
+
+        fn write(&self, address: usize, bytes: &[u8]) -> Result<()> {
+            let (start, offset) = self.locate(address, bytes.len())?;
+            self.blocks.borrow_mut().get_mut(&start).unwrap()[offset..offset + bytes.len()]
+                .copy_from_slice(bytes);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fake::LocalMemory;
+    use super::*;
+
+    /// 形如 `{ u32; void*; u32; }` 的结构体
+    #[derive(Debug, PartialEq)]
+    struct Sample {
+        a: u32,
+        p: usize,
+        b: i32,
+    }
+
+    impl RemoteLayout for Sample {
+        fn size(width: PointerWidth) -> usize {
+            match width {
+                PointerWidth::Bits32 => 12,
+                PointerWidth::Bits64 => 24,
+            }
+        }
+
+        fn encode(&self, writer: &mut LayoutWriter) {
+            writer.u32(self.a).ptr(self.p).i32(self.b);
+        }
+
+        fn decode(reader: &mut LayoutReader) -> Self {
+            Self {
+                a: reader.u32(),
+                p: reader.ptr(),
+                b: reader.i32(),
+            }
+        }
+    }
+
+    #[test]
+    fn layout_padding() {
+        let sample = Sample {
+            a: 1,
+            p: 0x1122_3344,
+            b: -1,
+        };
+
+        for width in [PointerWidth::Bits32, PointerWidth::Bits64] {
+            let mut writer = LayoutWriter::new(width);
+            sample.encode(&mut writer);
+            let bytes = writer.finish();
+
+            assert_eq!(bytes.len(), Sample::size(width), "{width:?}");
+            assert_eq!(
+                Sample::decode(&mut LayoutReader::new(width, &bytes)),
+                sample
+            );
+        }
+
+        let mut writer = LayoutWriter::new(PointerWidth::Bits64);
+        sample.encode(&mut writer);
+        assert_eq!(
+            writer.finish(),
+            [
+                1, 0, 0, 0, 0, 0, 0, 0, // a + 对齐
+                0x44, 0x33, 0x22, 0x11, 0, 0, 0, 0, // p
+                0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0, // b + 结尾补齐
+            ]
+        );
+    }
+
+    #[test]
+    fn buffer_round_trip() {
+        for width in [PointerWidth::Bits32, PointerWidth::Bits64] {
+            let memory = LocalMemory::new(width);
+            {
+                let buffer = RemoteBuffer::new(
+                    &memory,
+                    &Sample {
+                        a: 7,
+                        p: 0xDEAD,
+                        b: 3,
+                    },
+                )
+                .unwrap();
+
+                assert_ne!(buffer.address(), 0);
+                assert_eq!(buffer.read().unwrap().p, 0xDEAD);
+
+                let updated = Sample { a: 8, p: 1, b: 2 };
+                buffer.write(&updated).unwrap();
+                assert_eq!(buffer.read().unwrap(), updated);
+            }
+
+            assert_eq!(memory.live_allocations(), 0, "{width:?}");
+        }
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let memory = LocalMemory::new(PointerWidth::native());
+        let text = RemoteText::new(&memory, 8).unwrap();
+
+        text.write("注册表").unwrap();
+        assert_eq!(text.read().unwrap(), "注册表");
+
+        // 超出容量时截断并保留结尾的 NUL
+        text.write("0123456789").unwrap();
+        assert_eq!(text.read().unwrap(), "0123456");
+
+        assert_eq!(
+            read_utf16(&memory, text.address() + 2, text.capacity() - 1).unwrap(),
+            "123456"
+        );
+    }
+
+    #[test]
+    fn out_of_range_access_fails() {
+        let memory = LocalMemory::new(PointerWidth::native());
+        let text = RemoteText::new(&memory, 4).unwrap();
+
+        assert!(read_utf16(&memory, text.address(), 5).is_err());
+        assert!(read_utf16(&memory, 0, 1).is_err());
+    }
+
+    #[test]
+    fn text_at_end_of_page() {
+        let memory = LocalMemory::new(PointerWidth::native());
+        let page = memory.alloc(PAGE_SIZE).unwrap();
+
+        // 之后的页无法读取，读到 NUL 时不再继续
+        let text = page + PAGE_SIZE - 8;
+        memory
+            .write(text, &[b'a', 0, b'b', 0, b'c', 0, 0, 0])
+            .unwrap();
+        assert_eq!(read_utf16(&memory, text, 100).unwrap(), "abc");
+
+        // 没有 NUL 时返回读到的部分
+        memory.write(text + 6, &[b'd', 0]).unwrap();
+        assert_eq!(read_utf16(&memory, text, 100).unwrap(), "abcd");
+        assert_eq!(
+            read_utf16(&memory, text + 1, 100).unwrap().chars().count(),
+            3
+        );
+    }
+}
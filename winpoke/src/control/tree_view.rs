@@ -0,0 +1,468 @@
+use windows::Win32::UI::Controls::{
+    TVE_COLLAPSE, TVE_EXPAND, TVGN_CARET, TVGN_CHILD, TVGN_NEXT, TVGN_PARENT, TVGN_ROOT,
+    TVIF_HANDLE, TVIF_TEXT, TVM_ENSUREVISIBLE, TVM_EXPAND, TVM_GETITEMW, TVM_GETNEXTITEM,
+    TVM_SELECTITEM,
+};
+
+use crate::control::remote::{
+    LayoutReader, LayoutWriter, PointerWidth, RemoteBuffer, RemoteLayout, RemoteProcess,
+    RemoteText, read_utf16,
+};
+use crate::control::{expect_class, find_child};
+use crate::error::Error;
+use crate::prelude::Result;
+use crate::window::WindowInfo;
+
+/// 树形控件的窗口类名
+pub const TREE_VIEW_CLASS: &str = "SysTreeView32";
+
+/// 读取节点文本时的缓冲区大小（UTF-16 单元）
+const TEXT_CAPACITY: usize = 1024;
+
+/// 树形控件中的节点（`HTREEITEM`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TreeItem(pub isize);
+
+/// 与目标进程指针宽度无关的 `TVITEMW`
+#[derive(Debug, Default)]
+struct TvItem {
+    mask: u32,
+    item: usize,
+    state: u32,
+    state_mask: u32,
+    text: usize,
+    text_max: i32,
+    image: i32,
+    selected_image: i32,
+    children: i32,
+    lparam: usize,
+}
+
+impl RemoteLayout for TvItem {
+    fn size(width: PointerWidth) -> usize {
+        match width {
+            PointerWidth::Bits32 => 40,
+            PointerWidth::Bits64 => 56,
+        }
+    }
+
+    fn encode(&self, writer: &mut LayoutWriter) {
+        writer
+            .u32(self.mask)
+            .ptr(self.item)
+            .u32(self.state)
+            .u32(self.state_mask)
+            .ptr(self.text)
+            .i32(self.text_max)
+            .i32(self.image)
+            .i32(self.selected_image)
+            .i32(self.children)
+            .ptr(self.lparam);
+    }
+
+    fn decode(reader: &mut LayoutReader) -> Self {
+        Self {
+            mask: reader.u32(),
+            item: reader.ptr(),
+            state: reader.u32(),
+            state_mask: reader.u32(),
+            text: reader.ptr(),
+            text_max: reader.i32(),
+            image: reader.i32(),
+            selected_image: reader.i32(),
+            children: reader.i32(),
+            lparam: reader.ptr(),
+        }
+    }
+}
+
+/// 树形控件（`SysTreeView32`）
+///
+/// 通过 `TVM_*` 消息读取和操作其它进程中的树形控件，节点文本通过在目标进程中
+/// 分配的内存读取。
+///
+/// ```no_run
+/// use winpoke::control::TreeView;
+/// use winpoke::prelude::*;
+///
+/// let regedit = WindowInfo::find_by_class_name("RegEdit_RegEdit")?.remove(0);
+/// let tree = TreeView::find_in(&regedit)?;
+/// let item = tree.navigate(r"HKEY_CURRENT_USER\Software\Microsoft")?;
+/// tree.select(item)?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug)]
+pub struct TreeView {
+    pub(crate) window: WindowInfo,
+}
+
+impl TryFrom<WindowInfo> for TreeView {
+    type Error = Error;
+
+    fn try_from(window: WindowInfo) -> Result<Self> {
+        Self::new(window)
+    }
+}
+
+impl TreeView {
+    /// 包装树形控件窗口，类名不是 `SysTreeView32` 时返回错误
+    pub fn new(window: WindowInfo) -> Result<Self> {
+        let window = expect_class(window, TREE_VIEW_CLASS)?;
+
+        Ok(Self { window })
+    }
+
+    /// 查找窗口中的第一个树形控件
+    pub fn find_in(parent: &WindowInfo) -> Result<Self> {
+        find_child(parent, TREE_VIEW_CLASS).and_then(Self::new)
+    }
+
+    /// 控件窗口
+    pub fn window(&self) -> &WindowInfo {
+        &self.window
+    }
+
+    fn next_item(&self, flag: u32, item: Option<TreeItem>) -> Result<Option<TreeItem>> {
+        let item = self.window.send_raw(
+            TVM_GETNEXTITEM,
+            flag as usize,
+            item.map_or(0, |item| item.0),
+        )?;
+
+        Ok((item != 0).then_some(TreeItem(item as isize)))
+    }
+
+    /// 从 `first` 开始依次获取兄弟节点
+    fn siblings(&self, first: Option<TreeItem>) -> Result<Vec<TreeItem>> {
+        let mut items = Vec::new();
+        let mut next = first;
+
+        while let Some(item) = next {
+            items.push(item);
+            next = self.next_item(TVGN_NEXT, Some(item))?;
+        }
+
+        Ok(items)
+    }
+
+    /// 所有根节点
+    pub fn roots(&self) -> Result<Vec<TreeItem>> {
+        let first = self.next_item(TVGN_ROOT, None)?;
+        self.siblings(first)
+    }
+
+    /// 节点的直接子节点
+    ///
+    /// 许多程序（如注册表编辑器）在节点展开时才创建子节点，必要时先调用 [`expand`](Self::expand)。
+    pub fn children(&self, item: TreeItem) -> Result<Vec<TreeItem>> {
+        let first = self.next_item(TVGN_CHILD, Some(item))?;
+        self.siblings(first)
+    }
+
+    /// 节点的父节点，根节点返回 `None`
+    pub fn parent(&self, item: TreeItem) -> Result<Option<TreeItem>> {
+        self.next_item(TVGN_PARENT, Some(item))
+    }
+
+    /// 当前选中的节点
+    pub fn selected(&self) -> Result<Option<TreeItem>> {
+        self.next_item(TVGN_CARET, None)
+    }
+
+    /// 读取节点文本
+    pub fn text(&self, item: TreeItem) -> Result<String> {
+        let process = RemoteProcess::open(self.window.pid)?;
+        let text = RemoteText::new(&process, TEXT_CAPACITY)?;
+        let tv_item = RemoteBuffer::new(
+            &process,
+            &TvItem {
+                mask: (TVIF_TEXT | TVIF_HANDLE).0,
+                item: item.0 as usize,
+                text: text.address(),
+                text_max: TEXT_CAPACITY as i32,
+                ..Default::default()
+            },
+        )?;
+
+        if self
+            .window
+            .send_raw(TVM_GETITEMW, 0, tv_item.address() as isize)?
+            == 0
+        {
+            return Err(Error::TreeItemNotFound(format!("{item:?}")));
+        }
+
+        // 控件可能把 pszText 指向自己的缓冲区，因此按返回的指针读取
+        read_utf16(&process, tv_item.read()?.text, TEXT_CAPACITY)
+    }
+
+    /// 展开节点
+    pub fn expand(&self, item: TreeItem) -> Result<()> {
+        self.window
+            .send_raw(TVM_EXPAND, TVE_EXPAND.0 as usize, item.0)?;
+
+        Ok(())
+    }
+
+    /// 折叠节点
+    pub fn collapse(&self, item: TreeItem) -> Result<()> {
+        self.window
+            .send_raw(TVM_EXPAND, TVE_COLLAPSE.0 as usize, item.0)?;
+
+        Ok(())
+    }
+
+    /// 选中节点并滚动到可见位置
+    pub fn select(&self, item: TreeItem) -> Result<()> {
+        if self
+            .window
+            .send_raw(TVM_SELECTITEM, TVGN_CARET as usize, item.0)?
+            == 0
+        {
+            return Err(Error::TreeItemNotFound(format!("{item:?}")));
+        }
+
+        self.window.send_raw(TVM_ENSUREVISIBLE, 0, item.0)?;
+
+        Ok(())
+    }
+
+    /// 按路径查找节点，如 `HKEY_CURRENT_USER\Software\Microsoft`
+    ///
+    /// 路径以 `\` 分隔，逐级展开并按文本（不区分大小写）匹配子节点；
+    /// 第一段与根节点都不匹配时，会在根节点的子节点中查找（如注册表编辑器的“计算机”节点）。
+    pub fn navigate(&self, path: impl AsRef<str>) -> Result<TreeItem> {
+        let segments: Vec<&str> = path
+            .as_ref()
+            .split('\\')
+            .filter(|segment| !segment.is_empty())
+            .collect();
+
+        walk_path(self, &segments)
+    }
+
+    /// 按路径查找节点并选中
+    pub fn select_path(&self, path: impl AsRef<str>) -> Result<TreeItem> {
+        let item = self.navigate(path)?;
+        self.select(item)?;
+
+        Ok(item)
+    }
+}
+
+/// 可以按路径遍历的树
+///
+/// 将路径匹配逻辑与 `TVM_*` 消息分开，便于脱离真实控件测试。
+pub(crate) trait TreeSource {
+    type Item: Copy;
+
+    fn roots(&self) -> Result<Vec<Self::Item>>;
+    fn children(&self, item: Self::Item) -> Result<Vec<Self::Item>>;
+    fn text(&self, item: Self::Item) -> Result<String>;
+    fn expand(&self, item: Self::Item) -> Result<()>;
+}
+
+impl TreeSource for TreeView {
+    type Item = TreeItem;
+
+    fn roots(&self) -> Result<Vec<TreeItem>> {
+        TreeView::roots(self)
+    }
+
+    fn children(&self, item: TreeItem) -> Result<Vec<TreeItem>> {
+        TreeView::children(self, item)
+    }
+
+    fn text(&self, item: TreeItem) -> Result<String> {
+        TreeView::text(self, item)
+    }
+
+    fn expand(&self, item: TreeItem) -> Result<()> {
+        TreeView::expand(self, item)
+    }
+}
+
+/// 在 `items` 中查找文本与 `segment` 相同（不区分大小写）的节点
+fn find_item<S: TreeSource>(
+    source: &S,
+    items: &[S::Item],
+    segment: &str,
+) -> Result<Option<S::Item>> {
+    let segment = segment.to_lowercase();
+
+    for &item in items {
+        if source.text(item)?.to_lowercase() == segment {
+            return Ok(Some(item));
+        }
+    }
+
+    Ok(None)
+}
+
+/// 按路径逐级展开并查找节点
+pub(crate) fn walk_path<S: TreeSource>(source: &S, segments: &[&str]) -> Result<S::Item> {
+    let not_found = |depth: usize| Error::TreeItemNotFound(segments[..=depth].join("\\"));
+
+    let (first, rest) = segments
+        .split_first()
+        .ok_or_else(|| Error::TreeItemNotFound(String::new()))?;
+
+    let roots = source.roots()?;
+    let mut current = match find_item(source, &roots, first)? {
+        Some(item) => item,
+        None => {
+            // 路径省略了根节点时，在根节点的子节点中查找第一段
+            let mut found = None;
+            for &root in &roots {
+                source.expand(root)?;
+                if let Some(item) = find_item(source, &source.children(root)?, first)? {
+                    found = Some(item);
+                    break;
+                }
+            }
+            found.ok_or_else(|| not_found(0))?
+        }
+    };
+
+    for (depth, segment) in rest.iter().enumerate() {
+        source.expand(current)?;
+
+        let children = source.children(current)?;
+        current = find_item(source, &children, segment)?.ok_or_else(|| not_found(depth + 1))?;
+    }
+
+    Ok(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    /// 内存中的树，节点在展开后才有子节点，模拟注册表编辑器的延迟加载
+    struct FakeTree {
+        nodes: Vec<(&'static str, Option<usize>)>,
+        expanded: RefCell<Vec<usize>>,
+    }
+
+    impl FakeTree {
+        fn regedit() -> Self {
+            Self {
+                nodes: vec![
+                    ("计算机", None),
+                    ("HKEY_CLASSES_ROOT", Some(0)),
+                    ("HKEY_CURRENT_USER", Some(0)),
+                    ("Software", Some(2)),
+                    ("Microsoft", Some(3)),
+                    ("Classes", Some(3)),
+                    ("Environment", Some(2)),
+                ],
+                expanded: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl TreeSource for FakeTree {
+        type Item = usize;
+
+        fn roots(&self) -> Result<Vec<usize>> {
+            Ok(self.children_of(None))
+        }
+
+        fn children(&self, item: usize) -> Result<Vec<usize>> {
+            if self.expanded.borrow().contains(&item) {
+                Ok(self.children_of(Some(item)))
+            } else {
+                Ok(Vec::new())
+            }
+        }
+
+        fn text(&self, item: usize) -> Result<String> {
+            Ok(self.nodes[item].0.to_string())
+        }
+
+        fn expand(&self, item: usize) -> Result<()> {
+            self.expanded.borrow_mut().push(item);
+            Ok(())
+        }
+    }
+
+    impl FakeTree {
+        fn children_of(&self, parent: Option<usize>) -> Vec<usize> {
+            (0..self.nodes.len())
+                .filter(|&i| self.nodes[i].1 == parent)
+                .collect()
+        }
+    }
+
+    #[test]
+    fn tv_item_layout_matches_native() {
+        use windows::Win32::UI::Controls::TVITEMW;
+
+        let mut writer = LayoutWriter::new(PointerWidth::native());
+        TvItem::default().encode(&mut writer);
+
+        assert_eq!(writer.finish().len(), size_of::<TVITEMW>());
+        assert_eq!(TvItem::size(PointerWidth::native()), size_of::<TVITEMW>());
+    }
+
+    #[test]
+    fn tv_item_layout_32() {
+        let mut writer = LayoutWriter::new(PointerWidth::Bits32);
+        TvItem::default().encode(&mut writer);
+
+        assert_eq!(writer.finish().len(), TvItem::size(PointerWidth::Bits32));
+    }
+
+    #[test]
+    fn walk_from_root() {
+        let tree = FakeTree::regedit();
+        let item = walk_path(&tree, &["计算机", "HKEY_CURRENT_USER", "Software"]).unwrap();
+        assert_eq!(item, 3);
+    }
+
+    #[test]
+    fn walk_skips_implicit_root() {
+        let tree = FakeTree::regedit();
+        let item = walk_path(&tree, &["HKEY_CURRENT_USER", "Software", "Microsoft"]).unwrap();
+        assert_eq!(item, 4);
+    }
+
+    #[test]
+    fn walk_ignores_case() {
+        let tree = FakeTree::regedit();
+        let item = walk_path(&tree, &["hkey_current_user", "ENVIRONMENT"]).unwrap();
+        assert_eq!(item, 6);
+    }
+
+    #[test]
+    fn walk_reports_missing_segment() {
+        let tree = FakeTree::regedit();
+        let err = walk_path(&tree, &["HKEY_CURRENT_USER", "Software", "Nope"]).unwrap_err();
+        assert!(
+            matches!(&err, Error::TreeItemNotFound(path) if path == r"HKEY_CURRENT_USER\Software\Nope"),
+            "{err:?}"
+        );
+
+        assert!(walk_path(&tree, &["HKEY_USERS"]).is_err());
+        assert!(walk_path(&tree, &[]).is_err());
+    }
+
+    #[test]
+    fn test_navigate_regedit() {
+        let regedit = WindowInfo::find_by_class_name("RegEdit_RegEdit")
+            .unwrap()
+            .remove(0);
+        let tree = TreeView::find_in(&regedit).expect("找不到树形控件");
+
+        for root in tree.roots().unwrap() {
+            println!("{}", tree.text(root).unwrap());
+        }
+
+        let item = tree
+            .select_path(r"HKEY_CURRENT_USER\Software\Microsoft")
+            .expect("定位节点失败");
+        assert_eq!(tree.selected().unwrap(), Some(item));
+    }
+}
@@ -0,0 +1,10 @@
+mod rule;
+#[cfg(windows)]
+mod watcher;
+
+pub use rule::{DialogAction, DialogRule, find_button};
+#[cfg(windows)]
+pub use watcher::{Dialog, DialogReport, DialogWatcher};
+
+/// 标准对话框（包括 `MessageBox`）的窗口类名
+pub const DIALOG_CLASS: &str = "#32770";
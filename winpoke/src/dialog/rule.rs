@@ -0,0 +1,121 @@
+use regex::Regex;
+
+use crate::error::Error;
+use crate::label;
+use crate::prelude::Result;
+
+/// 对话框匹配规则后执行的操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DialogAction {
+    /// 点击指定文本的按钮，如 `"是"`、`"确定"`、`"Yes"`，忽略助记符和大小写
+    Click(String),
+
+    /// 以“取消”（`IDCANCEL`）关闭对话框
+    Dismiss,
+
+    /// 只记录，不做处理
+    Ignore,
+}
+
+/// 对话框处理规则
+///
+/// 对话框正文（所有静态文本，以换行连接）匹配 `text` 正则，且标题匹配 `title` 正则（如果设置了）时，
+/// 执行对应的操作。
+///
+/// ```
+/// use winpoke::dialog::{DialogAction, DialogRule};
+///
+/// let rule = DialogRule::new("是否保存", DialogAction::Click("否".into()))?
+///     .with_title("^记事本$")?;
+/// assert!(rule.matches("记事本", "是否保存对 无标题 的更改?"));
+/// # Ok::<(), winpoke::error::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct DialogRule {
+    title: Option<Regex>,
+    text: Regex,
+    action: DialogAction,
+}
+
+fn compile(pattern: &str) -> Result<Regex> {
+    Regex::new(pattern).map_err(|e| Error::InvalidPattern(e.to_string()))
+}
+
+impl DialogRule {
+    /// 正文匹配 `text` 正则时执行 `action`
+    pub fn new(text: &str, action: DialogAction) -> Result<Self> {
+        Ok(Self {
+            title: None,
+            text: compile(text)?,
+            action,
+        })
+    }
+
+    /// 追加标题条件
+    pub fn with_title(mut self, title: &str) -> Result<Self> {
+        self.title = Some(compile(title)?);
+        Ok(self)
+    }
+
+    /// 匹配后执行的操作
+    pub fn action(&self) -> &DialogAction {
+        &self.action
+    }
+
+    /// 判断对话框是否匹配
+    pub fn matches(&self, title: &str, text: &str) -> bool {
+        self.title.as_ref().is_none_or(|t| t.is_match(title)) && self.text.is_match(text)
+    }
+}
+
+/// 在按钮文本中查找 `wanted`，忽略助记符、省略号和大小写
+pub fn find_button(labels: &[impl AsRef<str>], wanted: &str) -> Option<usize> {
+    labels
+        .iter()
+        .position(|l| label::matches(l.as_ref(), wanted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_and_title() {
+        let rule = DialogRule::new(r"(?i)overwrite|覆盖", DialogAction::Click("是".into()))
+            .unwrap()
+            .with_title("确认")
+            .unwrap();
+
+        assert!(rule.matches("确认另存为", "文件已存在，要覆盖吗?"));
+        assert!(rule.matches("确认", "Overwrite existing file?"));
+        assert!(!rule.matches("提示", "文件已存在，要覆盖吗?"));
+        assert!(!rule.matches("确认", "要保存吗?"));
+    }
+
+    #[test]
+    fn any_title() {
+        let rule = DialogRule::new("", DialogAction::Dismiss).unwrap();
+        assert!(rule.matches("", ""));
+        assert_eq!(rule.action(), &DialogAction::Dismiss);
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        let err = DialogRule::new("(", DialogAction::Ignore).unwrap_err();
+        assert!(matches!(err, Error::InvalidPattern(_)));
+
+        let rule = DialogRule::new("a", DialogAction::Ignore).unwrap();
+        assert!(rule.with_title("[").is_err());
+    }
+
+    #[test]
+    fn buttons() {
+        let labels = ["是(&Y)", "否(&N)", "取消"];
+        assert_eq!(find_button(&labels, "是"), Some(0));
+        assert_eq!(find_button(&labels, "否"), Some(1));
+        assert_eq!(find_button(&labels, "取消"), Some(2));
+        assert_eq!(find_button(&labels, "确定"), None);
+
+        assert_eq!(find_button(&["&OK", "Cancel"], "ok"), Some(0));
+    }
+}
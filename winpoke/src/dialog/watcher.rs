@@ -0,0 +1,283 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use windows::Win32::UI::WindowsAndMessaging::{BN_CLICKED, IDCANCEL, WM_COMMAND, WS_VISIBLE};
+
+use crate::control::{Button, Control, expect_class, notify_parent};
+use crate::dialog::{DIALOG_CLASS, DialogAction, DialogRule, find_button};
+use crate::error::Error;
+use crate::prelude::Result;
+use crate::selector::Selector;
+use crate::window::WindowInfo;
+use crate::window::event::{EventSubscription, WindowEventKind};
+
+/// 后台线程检查停止标志的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 静态文本控件的窗口类名
+const STATIC_CLASS: &str = "Static";
+
+/// 标准对话框（`#32770`）
+///
+/// 创建时读取标题、正文（所有非空静态文本，以换行连接，无法读取的跳过）和按钮。
+/// 任务对话框（`TaskDialog`）的内容由 `DirectUIHWND` 绘制，无法读取。
+#[derive(Debug)]
+pub struct Dialog {
+    window: WindowInfo,
+    text: String,
+    buttons: Vec<Button>,
+}
+
+impl Dialog {
+    /// 读取对话框，类名不是 `#32770` 时返回错误
+    pub fn new(window: WindowInfo) -> Result<Self> {
+        let window = expect_class(window, DIALOG_CLASS)?;
+
+        let children = match window.get_child_windows() {
+            Ok(children) => children,
+            Err(Error::WindowNotFound) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+        let mut lines = Vec::new();
+        let mut buttons = Vec::new();
+        for child in children {
+            match Control::from(child) {
+                Control::Button(button) => buttons.push(button),
+                Control::Other(child) if child.class_name.eq_ignore_ascii_case(STATIC_CLASS) => {
+                    // 读取失败（如控件已销毁）时跳过该控件，不影响读取整个对话框
+                    match child.text() {
+                        Ok(text) if !text.trim().is_empty() => lines.push(text),
+                        Ok(_) => {}
+                        #[cfg(feature = "tracing")]
+                        Err(e) => {
+                            tracing::debug!(hwnd = ?child.hwnd.0, error = %e, "跳过无法读取的静态文本")
+                        }
+                        #[cfg(not(feature = "tracing"))]
+                        Err(_) => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            window,
+            text: lines.join("\n"),
+            buttons,
+        })
+    }
+
+    /// 对话框窗口
+    pub fn window(&self) -> &WindowInfo {
+        &self.window
+    }
+
+    /// 标题
+    pub fn title(&self) -> &str {
+        &self.window.caption
+    }
+
+    /// 正文
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// 按钮文本（原样，包含助记符）
+    pub fn button_labels(&self) -> Vec<String> {
+        self.buttons
+            .iter()
+            .map(|button| button.window().caption.clone())
+            .collect()
+    }
+
+    /// 点击指定文本的按钮，忽略助记符和大小写
+    ///
+    /// 直接以 `BN_CLICKED` 通知对话框，不要求对话框位于前台。
+    pub fn click(&self, label: &str) -> Result<()> {
+        let index = find_button(&self.button_labels(), label)
+            .ok_or_else(|| Error::ButtonNotFound(label.to_string()))?;
+
+        notify_parent(self.buttons[index].window(), BN_CLICKED)
+    }
+
+    /// 以“取消”关闭对话框，相当于按下 Esc
+    pub fn dismiss(&self) -> Result<()> {
+        self.window.post_raw(WM_COMMAND, IDCANCEL.0 as usize, 0)
+    }
+
+    /// 执行操作
+    pub fn apply(&self, action: &DialogAction) -> Result<()> {
+        match action {
+            DialogAction::Click(label) => self.click(label),
+            DialogAction::Dismiss => self.dismiss(),
+            DialogAction::Ignore => Ok(()),
+        }
+    }
+}
+
+/// 自动处理对话框的结果
+#[derive(Debug)]
+pub struct DialogReport {
+    /// 对话框标题
+    pub title: String,
+
+    /// 对话框正文
+    pub text: String,
+
+    /// 按钮文本
+    pub buttons: Vec<String>,
+
+    /// 匹配的规则对应的操作，没有规则匹配时为 `None`
+    pub action: Option<DialogAction>,
+
+    /// 操作的执行结果
+    pub result: Result<()>,
+}
+
+/// 按规则处理对话框
+fn respond(window: WindowInfo, rules: &[DialogRule]) -> Result<DialogReport> {
+    let dialog = Dialog::new(window)?;
+
+    let action = rules
+        .iter()
+        .find(|rule| rule.matches(dialog.title(), dialog.text()))
+        .map(|rule| rule.action().clone());
+    let result = action
+        .as_ref()
+        .map_or(Ok(()), |action| dialog.apply(action));
+
+    Ok(DialogReport {
+        title: dialog.title().to_string(),
+        text: dialog.text().to_string(),
+        buttons: dialog.button_labels(),
+        action,
+        result,
+    })
+}
+
+/// 对话框自动应答
+///
+/// 在后台线程中监视指定进程弹出的对话框，按顺序找到第一条匹配的规则并执行其操作。
+/// 每个出现的对话框（包括没有规则匹配的）都会产生一条 [`DialogReport`]，可用于记录日志。
+/// `Drop` 时停止监视。
+///
+/// ```no_run
+/// use winpoke::dialog::{DialogAction, DialogRule, DialogWatcher};
+///
+/// let rules = vec![
+///     DialogRule::new("已存在.*替换", DialogAction::Click("是".into()))?,
+///     DialogRule::new("", DialogAction::Dismiss)?,
+/// ];
+/// let watcher = DialogWatcher::spawn(1234, rules)?;
+///
+/// // ... 正常的自动化操作 ...
+///
+/// while let Some(report) = watcher.try_recv() {
+///     println!("{} {:?} => {:?}", report.title, report.text, report.action);
+/// }
+/// # Ok::<(), winpoke::error::Error>(())
+/// ```
+pub struct DialogWatcher {
+    reports: Receiver<DialogReport>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl DialogWatcher {
+    /// 开始监视进程 `pid` 的对话框，已经显示的对话框会立即处理
+    pub fn spawn(pid: u32, rules: Vec<DialogRule>) -> Result<Self> {
+        let selector = Selector::class_name(DIALOG_CLASS).with_pid(pid);
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, reports) = mpsc::channel();
+        let (ready_sender, ready) = mpsc::sync_channel(1);
+
+        let thread = thread::spawn({
+            let stop = stop.clone();
+
+            move || {
+                let events = match EventSubscription::new(selector.clone()) {
+                    Ok(events) => {
+                        let _ = ready_sender.send(Ok(()));
+                        events
+                    }
+                    Err(e) => {
+                        let _ = ready_sender.send(Err(e));
+                        return;
+                    }
+                };
+
+                // 同一个对话框可能多次显示，只在第一次显示时处理
+                let mut handled = HashSet::new();
+                let handle = |handled: &mut HashSet<usize>, window: WindowInfo| {
+                    if handled.insert(window.hwnd.0 as usize)
+                        && let Ok(report) = respond(window, &rules)
+                    {
+                        let _ = sender.send(report);
+                    }
+                };
+
+                for window in WindowInfo::find(&selector).unwrap_or_default() {
+                    if window.style.style.0 & WS_VISIBLE.0 != 0 {
+                        handle(&mut handled, window);
+                    }
+                }
+
+                while !stop.load(Ordering::Relaxed) {
+                    let Some(event) = events.recv_timeout(POLL_INTERVAL) else {
+                        continue;
+                    };
+
+                    match event.kind {
+                        WindowEventKind::Shown => {
+                            if let Ok(window) = event.window_info() {
+                                handle(&mut handled, window);
+                            }
+                        }
+                        WindowEventKind::Destroyed => {
+                            handled.remove(&(event.hwnd.0 as usize));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        });
+
+        ready.recv().map_err(|_| Error::MessageLoopExited)??;
+
+        Ok(Self {
+            reports,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// 阻塞等待下一条处理结果
+    pub fn recv(&self) -> Option<DialogReport> {
+        self.reports.recv().ok()
+    }
+
+    /// 等待下一条处理结果，超时返回 `None`
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<DialogReport> {
+        self.reports.recv_timeout(timeout).ok()
+    }
+
+    /// 获取已有的处理结果，不阻塞
+    pub fn try_recv(&self) -> Option<DialogReport> {
+        self.reports.try_recv().ok()
+    }
+}
+
+impl Drop for DialogWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
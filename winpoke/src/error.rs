@@ -1,8 +1,10 @@
 use thiserror::Error;
+#[cfg(windows)]
 use windows::core;
 
 #[derive(Error, Debug)]
 pub enum Error {
+    #[cfg(windows)]
     #[error("Windows API 调用失败: {0}")]
     WindowsError(#[from] core::Error),
     #[error("找不到指定窗口")]
@@ -19,4 +21,61 @@ pub enum Error {
     GetWindowInfoFailed,
     #[error("枚举窗口失败")]
     EnumWindowsFailed,
+    #[error("发送消息超时")]
+    MessageTimeout,
+    #[error("无效的选择器: {0}")]
+    InvalidSelector(String),
+    #[error("注册窗口事件钩子失败")]
+    SetWinEventHookFailed,
+    #[error("消息循环线程已退出")]
+    MessageLoopExited,
+    #[error("无效的热键: {0}")]
+    InvalidHotkey(String),
+    #[error("热键已被占用: {0}")]
+    HotkeyConflict(String),
+    #[error("注入输入失败，目标程序可能以更高权限运行")]
+    SendInputFailed,
+    #[error("窗口类名不匹配: 需要 {expected}，实际为 {found}")]
+    ClassMismatch {
+        expected: &'static str,
+        found: String,
+    },
+    #[error("读写目标进程内存失败")]
+    RemoteMemoryFailed,
+    #[error("找不到树节点: {0}")]
+    TreeItemNotFound(String),
+    #[error("找不到列表项: {0}")]
+    ListItemNotFound(String),
+    #[error("窗口没有菜单")]
+    MenuNotFound,
+    #[error("找不到菜单项: {0}")]
+    MenuItemNotFound(String),
+    #[error("需要以管理员身份运行")]
+    ElevationRequired,
+    #[error("无效的注册表路径 {0}")]
+    InvalidRegistryPath(String),
+    #[error("无效的正则表达式: {0}")]
+    InvalidPattern(String),
+    #[error("找不到按钮: {0}")]
+    ButtonNotFound(String),
+    #[error("无效的颜色: {0}")]
+    InvalidColor(String),
+    #[error("窗口大小为零，无法高亮")]
+    EmptyWindow,
+    #[error("脚本第 {line} 行: {message}")]
+    ScriptSyntax { line: usize, message: String },
+    #[error("脚本第 {line} 行执行失败: {source}")]
+    ScriptFailed {
+        line: usize,
+        #[source]
+        source: Box<Error>,
+    },
+    #[error("未定义的变量或窗口: {0}")]
+    Undefined(String),
+    #[error("断言失败: {0}")]
+    AssertionFailed(String),
+    #[error("等待超时: {0}")]
+    WaitTimeout(String),
+    #[error("Rhai 脚本错误: {0}")]
+    Rhai(String),
 }
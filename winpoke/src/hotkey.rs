@@ -0,0 +1,350 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use windows::Win32::Foundation::{LPARAM, WPARAM};
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    HOT_KEY_MODIFIERS, MOD_ALT, MOD_CONTROL, MOD_NOREPEAT, MOD_SHIFT, MOD_WIN, RegisterHotKey,
+    UnregisterHotKey,
+};
+use windows::Win32::UI::WindowsAndMessaging::{MSG, WM_APP, WM_HOTKEY};
+
+use crate::error::Error;
+use crate::keys::{name_from_vk, vk_from_name};
+use crate::prelude::Result;
+use crate::window::message_loop::MessageLoop;
+
+/// 热键（修饰键 + 按键）
+///
+/// 可以从 `"Ctrl+Alt+J"` 这样的组合键字符串解析，修饰键名称不区分大小写，
+/// 按键名称见 [`vk_from_name`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Hotkey {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub win: bool,
+
+    /// 虚拟键码
+    pub vk: u32,
+}
+
+impl Hotkey {
+    fn modifiers(&self) -> HOT_KEY_MODIFIERS {
+        let mut modifiers = MOD_NOREPEAT;
+
+        for (pressed, modifier) in [
+            (self.ctrl, MOD_CONTROL),
+            (self.alt, MOD_ALT),
+            (self.shift, MOD_SHIFT),
+            (self.win, MOD_WIN),
+        ] {
+            if pressed {
+                modifiers |= modifier;
+            }
+        }
+
+        modifiers
+    }
+}
+
+impl FromStr for Hotkey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = |reason: &str| Error::InvalidHotkey(format!("{s}: {reason}"));
+
+        let mut hotkey = Hotkey {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            win: false,
+            vk: 0,
+        };
+
+        let parts: Vec<&str> = s.split('+').map(str::trim).collect();
+        let (key, modifiers) = parts.split_last().ok_or_else(|| invalid("缺少按键"))?;
+
+        for modifier in modifiers {
+            let flag = match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => &mut hotkey.ctrl,
+                "alt" => &mut hotkey.alt,
+                "shift" => &mut hotkey.shift,
+                "win" | "super" | "meta" => &mut hotkey.win,
+                _ => return Err(invalid("未知的修饰键")),
+            };
+
+            if *flag {
+                return Err(invalid("修饰键重复"));
+            }
+            *flag = true;
+        }
+
+        hotkey.vk = vk_from_name(key).ok_or_else(|| invalid("未知的按键"))?;
+
+        Ok(hotkey)
+    }
+}
+
+impl Display for Hotkey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (pressed, name) in [
+            (self.ctrl, "Ctrl"),
+            (self.alt, "Alt"),
+            (self.shift, "Shift"),
+            (self.win, "Win"),
+        ] {
+            if pressed {
+                write!(f, "{name}+")?;
+            }
+        }
+
+        match name_from_vk(self.vk) {
+            Some(name) => write!(f, "{name}"),
+            None => write!(f, "0x{:02X}", self.vk),
+        }
+    }
+}
+
+/// 热键ID，用于注销热键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HotkeyId(i32);
+
+type Callback = Box<dyn FnMut() + Send>;
+
+/// 发往消息循环线程的请求
+enum Request {
+    Register {
+        id: i32,
+        hotkey: Hotkey,
+        callback: Callback,
+        reply: Sender<Result<()>>,
+    },
+    Unregister {
+        id: i32,
+        reply: Sender<Result<()>>,
+    },
+}
+
+/// 通知消息循环线程处理请求的线程消息
+const WM_HOTKEY_REQUEST: u32 = WM_APP + 1;
+
+/// 消息循环线程中已注册的热键
+///
+/// `RegisterHotKey` 注册的线程热键只能在同一线程注销，因此在该线程上随消息循环一起释放。
+struct Registrations {
+    requests: Receiver<Request>,
+    callbacks: HashMap<i32, Callback>,
+}
+
+impl Registrations {
+    fn handle(&mut self, msg: &MSG) {
+        match msg.message {
+            WM_HOTKEY => {
+                if let Some(callback) = self.callbacks.get_mut(&(msg.wParam.0 as i32)) {
+                    callback();
+                }
+            }
+            WM_HOTKEY_REQUEST => {
+                while let Ok(request) = self.requests.try_recv() {
+                    self.handle_request(request);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_request(&mut self, request: Request) {
+        match request {
+            Request::Register {
+                id,
+                hotkey,
+                callback,
+                reply,
+            } => {
+                let result = unsafe { RegisterHotKey(None, id, hotkey.modifiers(), hotkey.vk) }
+                    // 注册失败通常是因为组合键已被其它程序占用
+                    .map_err(|_| Error::HotkeyConflict(hotkey.to_string()));
+
+                if result.is_ok() {
+                    self.callbacks.insert(id, callback);
+                }
+                let _ = reply.send(result);
+            }
+            Request::Unregister { id, reply } => {
+                self.callbacks.remove(&id);
+                let result = unsafe { UnregisterHotKey(None, id) }.map_err(Error::from);
+                let _ = reply.send(result);
+            }
+        }
+    }
+}
+
+impl Drop for Registrations {
+    fn drop(&mut self) {
+        for &id in self.callbacks.keys() {
+            let _ = unsafe { UnregisterHotKey(None, id) };
+        }
+    }
+}
+
+/// 全局热键管理器
+///
+/// 在后台消息循环线程中通过 `RegisterHotKey` 注册热键，热键按下时在该线程上调用回调。
+/// `Drop` 时注销全部热键。
+///
+/// ```no_run
+/// use winpoke::hotkey::HotkeyManager;
+///
+/// let manager = HotkeyManager::new().unwrap();
+/// manager.register("Ctrl+Alt+J", || println!("热键被按下")).unwrap();
+/// std::thread::park();
+/// ```
+pub struct HotkeyManager {
+    requests: Sender<Request>,
+    registered: Mutex<HashMap<Hotkey, i32>>,
+    next_id: AtomicI32,
+    message_loop: MessageLoop,
+}
+
+impl HotkeyManager {
+    pub fn new() -> Result<Self> {
+        let (requests, receiver) = mpsc::channel();
+
+        let message_loop = MessageLoop::spawn(move || {
+            let mut registrations = Registrations {
+                requests: receiver,
+                callbacks: HashMap::new(),
+            };
+
+            Ok(move |msg: &MSG| registrations.handle(msg))
+        })?;
+
+        Ok(Self {
+            requests,
+            registered: Mutex::new(HashMap::new()),
+            next_id: AtomicI32::new(1),
+            message_loop,
+        })
+    }
+
+    /// 注册热键，组合键已被注册（本管理器或其它程序）时返回 [`Error::HotkeyConflict`]
+    pub fn register<F>(&self, hotkey: impl AsRef<str>, callback: F) -> Result<HotkeyId>
+    where
+        F: FnMut() + Send + 'static,
+    {
+        let hotkey: Hotkey = hotkey.as_ref().parse()?;
+        self.register_hotkey(hotkey, callback)
+    }
+
+    /// 注册已解析的热键
+    pub fn register_hotkey<F>(&self, hotkey: Hotkey, callback: F) -> Result<HotkeyId>
+    where
+        F: FnMut() + Send + 'static,
+    {
+        let mut registered = self.registered.lock().unwrap();
+        if registered.contains_key(&hotkey) {
+            return Err(Error::HotkeyConflict(hotkey.to_string()));
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+        let (reply, result) = mpsc::channel();
+        self.request(
+            Request::Register {
+                id,
+                hotkey,
+                callback: Box::new(callback),
+                reply,
+            },
+            result,
+        )?;
+
+        registered.insert(hotkey, id);
+
+        Ok(HotkeyId(id))
+    }
+
+    /// 注销热键
+    pub fn unregister(&self, id: HotkeyId) -> Result<()> {
+        let mut registered = self.registered.lock().unwrap();
+
+        let (reply, result) = mpsc::channel();
+        self.request(Request::Unregister { id: id.0, reply }, result)?;
+
+        registered.retain(|_, &mut registered_id| registered_id != id.0);
+
+        Ok(())
+    }
+
+    /// 已注册的热键
+    pub fn hotkeys(&self) -> Vec<Hotkey> {
+        self.registered.lock().unwrap().keys().copied().collect()
+    }
+
+    /// 将请求交给消息循环线程处理并等待结果
+    fn request(&self, request: Request, result: Receiver<Result<()>>) -> Result<()> {
+        self.requests
+            .send(request)
+            .map_err(|_| Error::MessageLoopExited)?;
+        self.message_loop
+            .post(WM_HOTKEY_REQUEST, WPARAM(0), LPARAM(0))?;
+
+        result.recv().map_err(|_| Error::MessageLoopExited)?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_chord() {
+        let hotkey: Hotkey = "Ctrl+Alt+J".parse().unwrap();
+        assert!(hotkey.ctrl && hotkey.alt && !hotkey.shift && !hotkey.win);
+        assert_eq!(hotkey.vk, 0x4A);
+
+        let hotkey: Hotkey = " win + shift + F5 ".parse().unwrap();
+        assert!(hotkey.win && hotkey.shift && !hotkey.ctrl && !hotkey.alt);
+        assert_eq!(hotkey.vk, 0x74);
+    }
+
+    #[test]
+    fn parse_invalid_chord() {
+        for s in ["", "Ctrl+", "Hyper+J", "Ctrl+Ctrl+J", "Ctrl+Alt+Nope"] {
+            assert!(s.parse::<Hotkey>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let hotkey: Hotkey = "alt+ctrl+left".parse().unwrap();
+        assert_eq!(hotkey.to_string(), "Ctrl+Alt+Left");
+        assert_eq!(hotkey.to_string().parse::<Hotkey>().unwrap(), hotkey);
+    }
+
+    #[test]
+    fn modifiers() {
+        let hotkey: Hotkey = "Ctrl+Shift+A".parse().unwrap();
+        assert_eq!(hotkey.modifiers(), MOD_NOREPEAT | MOD_CONTROL | MOD_SHIFT);
+    }
+
+    #[test]
+    fn test_register_conflict() {
+        let manager = HotkeyManager::new().expect("创建热键管理器失败");
+        let id = manager
+            .register("Ctrl+Alt+Shift+F11", || {})
+            .expect("注册热键失败");
+
+        assert!(matches!(
+            manager.register("Ctrl+Shift+Alt+F11", || {}),
+            Err(Error::HotkeyConflict(_))
+        ));
+
+        manager.unregister(id).expect("注销热键失败");
+        assert!(manager.hotkeys().is_empty());
+    }
+}
@@ -0,0 +1,35 @@
+//! HTTP 接口（需要 `http` 特性）
+//!
+//! 以 REST 风格提供查找和操作窗口的接口，请求和响应的正文都是 JSON，接口说明见
+//! `GET /openapi.json`（即 [`openapi`] 生成的 OpenAPI 3.0 文档）。
+//!
+//! | 接口 | 说明 |
+//! | --- | --- |
+//! | `GET /windows?class=&caption=&pid=` | 查找顶层窗口，未指定的条件匹配任意值 |
+//! | `GET /windows?path=` | 按选择器路径查找窗口，如 `Notepad > Edit` |
+//! | `GET /windows/{hwnd}` | 窗口 |
+//! | `GET /windows/{hwnd}/children` | 一级子窗口 |
+//! | `GET /windows/{hwnd}/text` | 窗口当前的文本 |
+//! | `POST /windows/{hwnd}/focus` | 激活窗口 |
+//! | `POST /windows/{hwnd}/messages` | 按顺序发送消息，正文为消息数组 |
+//!
+//! 窗口表示为 `{"hwnd", "class_name", "caption"}`，`hwnd` 可以写成十进制或 `0x` 开头的
+//! 十六进制。消息的格式与 [`rpc`](crate::rpc) 的 `send` 方法相同，如
+//! `[{"type": "text", "text": "hello"}, {"type": "key", "key": "Enter"}]`。出错时返回
+//! `{"error": "说明"}`，找不到窗口为 404，参数错误为 400。
+//!
+//! 为防止本机的网页跨域调用接口：
+//!
+//! - 除 `GET /openapi.json` 外的请求都需要 `Authorization: Bearer <令牌>` 头，令牌在创建
+//!   [`Server`] 时指定，否则返回 401；
+//! - POST 请求的 `Content-Type` 必须是 `application/json`，否则返回 415；
+//! - `Host` 头必须是监听的地址（监听回环地址时也可以是 `localhost`），否则返回 403，
+//!   以防 DNS 重绑定。
+//!
+//! 请求头检查通过后才读取正文。POST 请求必须带 `Content-Length`，正文超过 1 MiB 时返回 413。
+
+mod openapi;
+mod server;
+
+pub use openapi::openapi;
+pub use server::{Response, Server};
@@ -0,0 +1,178 @@
+use serde_json::{Value, json};
+
+/// 生成 HTTP 接口的 OpenAPI 3.0 文档
+pub fn openapi() -> Value {
+    let hwnd = json!({
+        "name": "hwnd",
+        "in": "path",
+        "required": true,
+        "description": "窗口句柄，十进制或 0x 开头的十六进制",
+        "schema": { "type": "string" },
+    });
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "winpoke",
+            "description": "查找和操作 Windows 窗口。除本文档外的请求都需要 `Authorization: Bearer <令牌>` 头，POST 请求的 `Content-Type` 必须是 `application/json`",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "security": [{ "bearer": [] }],
+        "paths": {
+            "/windows": {
+                "get": {
+                    "summary": "查找窗口",
+                    "description": "按类名、标题和进程 ID 查找顶层窗口，或按选择器路径查找窗口",
+                    "parameters": [
+                        query("class", "窗口类名", json!({ "type": "string" })),
+                        query("caption", "窗口标题", json!({ "type": "string" })),
+                        query("pid", "进程 ID", json!({ "type": "integer", "format": "int32" })),
+                        query(
+                            "path",
+                            "选择器路径，如 `Notepad > Edit`，不能与其它条件同时使用",
+                            json!({ "type": "string" }),
+                        ),
+                    ],
+                    "responses": {
+                        "200": json_response("匹配的窗口", json!({
+                            "type": "array",
+                            "items": schema_ref("Window"),
+                        })),
+                        "400": error_response("查询参数错误"),
+                    },
+                },
+            },
+            "/windows/{hwnd}": {
+                "get": {
+                    "summary": "读取窗口",
+                    "parameters": [hwnd],
+                    "responses": {
+                        "200": json_response("窗口", schema_ref("Window")),
+                        "404": error_response("找不到窗口"),
+                    },
+                },
+            },
+            "/windows/{hwnd}/children": {
+                "get": {
+                    "summary": "一级子窗口",
+                    "parameters": [hwnd],
+                    "responses": {
+                        "200": json_response("子窗口", json!({
+                            "type": "array",
+                            "items": schema_ref("Window"),
+                        })),
+                        "404": error_response("找不到窗口"),
+                    },
+                },
+            },
+            "/windows/{hwnd}/text": {
+                "get": {
+                    "summary": "窗口当前的文本",
+                    "parameters": [hwnd],
+                    "responses": {
+                        "200": json_response("窗口文本", json!({
+                            "type": "object",
+                            "required": ["text"],
+                            "properties": { "text": { "type": "string" } },
+                        })),
+                        "404": error_response("找不到窗口"),
+                    },
+                },
+            },
+            "/windows/{hwnd}/focus": {
+                "post": {
+                    "summary": "激活窗口",
+                    "parameters": [hwnd],
+                    "responses": {
+                        "204": { "description": "已激活" },
+                        "404": error_response("找不到窗口"),
+                    },
+                },
+            },
+            "/windows/{hwnd}/messages": {
+                "post": {
+                    "summary": "按顺序发送消息",
+                    "parameters": [hwnd],
+                    "requestBody": {
+                        "required": true,
+                        "content": {
+                            "application/json": {
+                                "schema": { "type": "array", "items": schema_ref("Message") },
+                            },
+                        },
+                    },
+                    "responses": {
+                        "204": { "description": "已发送" },
+                        "400": error_response("消息格式错误"),
+                        "404": error_response("找不到窗口"),
+                    },
+                },
+            },
+            "/openapi.json": {
+                "get": {
+                    "summary": "本文档",
+                    "security": [],
+                    "responses": { "200": json_response("OpenAPI 文档", json!({ "type": "object" })) },
+                },
+            },
+        },
+        "components": {
+            "securitySchemes": {
+                "bearer": { "type": "http", "scheme": "bearer" },
+            },
+            "schemas": {
+                "Window": {
+                    "type": "object",
+                    "required": ["hwnd", "class_name", "caption"],
+                    "properties": {
+                        "hwnd": { "type": "integer", "description": "窗口句柄" },
+                        "class_name": { "type": "string" },
+                        "caption": { "type": "string" },
+                    },
+                },
+                "Message": {
+                    "type": "object",
+                    "description": "按 `type` 区分的消息，`key` 和 `char` 可以带重复次数 `count`",
+                    "required": ["type"],
+                    "properties": {
+                        "type": {
+                            "type": "string",
+                            "enum": ["key", "char", "text", "command", "mouse_move"],
+                        },
+                        "key": { "description": "按键名称或虚拟键码，如 `Enter`、`0x41`、65" },
+                        "char": { "type": "string", "maxLength": 1 },
+                        "text": { "type": "string", "description": "逐个字符发送" },
+                        "id": { "type": "integer", "description": "菜单或按钮的命令 ID" },
+                        "x": { "type": "integer" },
+                        "y": { "type": "integer" },
+                        "count": { "type": "integer", "minimum": 1, "default": 1 },
+                    },
+                },
+                "Error": {
+                    "type": "object",
+                    "required": ["error"],
+                    "properties": { "error": { "type": "string" } },
+                },
+            },
+        },
+    })
+}
+
+fn query(name: &str, description: &str, schema: Value) -> Value {
+    json!({ "name": name, "in": "query", "description": description, "schema": schema })
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{name}") })
+}
+
+fn json_response(description: &str, schema: Value) -> Value {
+    json!({
+        "description": description,
+        "content": { "application/json": { "schema": schema } },
+    })
+}
+
+fn error_response(description: &str) -> Value {
+    json_response(description, schema_ref("Error"))
+}
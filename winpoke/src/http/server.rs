@@ -0,0 +1,645 @@
+use std::io::{self, Read};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+
+use serde_json::{Value, json};
+use tiny_http::Header;
+
+use crate::http::openapi;
+use crate::rpc::protocol::{
+    self, INVALID_PARAMS, MessageParam, REQUEST_TIMEOUT, RpcError, WINDOW_NOT_FOUND,
+};
+use crate::rpc::server::{lock, token_eq};
+use crate::script::{Backend, WindowHandle};
+use crate::selector::{Selector, SelectorPath};
+
+/// 请求正文的最大字节数
+const MAX_BODY: usize = 1 << 20;
+
+/// 同时处理请求的线程数
+const WORKERS: usize = 8;
+
+/// HTTP 响应，`body` 为 `None` 时状态码为 204
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Option<Value>,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Self {
+            status: 200,
+            body: Some(body),
+        }
+    }
+
+    fn no_content() -> Self {
+        Self {
+            status: 204,
+            body: None,
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: Some(json!({ "error": message.into() })),
+        }
+    }
+}
+
+impl From<RpcError> for Response {
+    fn from(error: RpcError) -> Self {
+        let status = match error.code {
+            WINDOW_NOT_FOUND => 404,
+            INVALID_PARAMS => 400,
+            REQUEST_TIMEOUT => 504,
+            _ => 500,
+        };
+
+        Self::error(status, error.message)
+    }
+}
+
+impl From<crate::error::Error> for Response {
+    fn from(e: crate::error::Error) -> Self {
+        RpcError::from(e).into()
+    }
+}
+
+/// HTTP 服务端
+///
+/// [`handle`](Self::handle) 只处理请求的方法、地址、请求头和正文，不涉及网络，可以直接配合
+/// [`FakeDesktop`](crate::script::FakeDesktop) 测试。
+///
+/// 为防止网页跨域调用接口，除 `GET /openapi.json` 外的请求都要在 `Authorization` 头中带上
+/// `Bearer <令牌>`，POST 请求的 `Content-Type` 必须是 `application/json`。
+/// [`serve`](Self::serve) 还检查 `Host` 是否为监听的地址，以防 DNS 重绑定。
+///
+/// ```
+/// use winpoke::http::Server;
+/// use winpoke::script::{FakeDesktop, FakeWindow};
+///
+/// let desktop = FakeDesktop::new().with_window(FakeWindow::new("#32770", "保存"));
+/// let server = Server::new(desktop, "secret");
+///
+/// let headers = [("Authorization", "Bearer secret")];
+/// let response = server.handle("GET", "/windows?class=%2332770", &headers, "");
+/// assert_eq!(response.status, 200);
+/// assert_eq!(response.body.unwrap()[0]["caption"], "保存");
+///
+/// assert_eq!(server.handle("GET", "/windows", &[], "").status, 401);
+/// ```
+#[derive(Debug)]
+pub struct Server<B> {
+    backend: Arc<Mutex<B>>,
+    token: Arc<str>,
+
+    /// 监听的地址，设置后检查 `Host`
+    addr: Option<SocketAddr>,
+}
+
+impl<B> Clone for Server<B> {
+    fn clone(&self) -> Self {
+        Self {
+            backend: self.backend.clone(),
+            token: self.token.clone(),
+            addr: self.addr,
+        }
+    }
+}
+
+impl<B: Backend + Send + 'static> Server<B> {
+    /// 客户端需要用 `token` 认证
+    pub fn new(backend: B, token: impl Into<String>) -> Self {
+        Self {
+            backend: Arc::new(Mutex::new(backend)),
+            token: token.into().into(),
+            addr: None,
+        }
+    }
+
+    /// 锁定并返回后端
+    pub fn backend(&self) -> MutexGuard<'_, B> {
+        lock(&self.backend)
+    }
+
+    /// 接受连接，由固定数量的线程处理请求
+    ///
+    /// `Host` 头必须是监听的地址；监听回环地址时也可以是 `localhost` 加端口。
+    /// 读取正文前先检查请求头，POST 请求必须带 `Content-Length`，正文最多 1 MiB。
+    pub fn serve(&self, listener: TcpListener) -> io::Result<()> {
+        let mut this = self.clone();
+        this.addr = Some(listener.local_addr()?);
+
+        let server =
+            Arc::new(tiny_http::Server::from_listener(listener, None).map_err(io::Error::other)?);
+        let workers: Vec<_> = (0..WORKERS)
+            .map(|_| {
+                let (server, this) = (server.clone(), this.clone());
+                thread::spawn(move || {
+                    for request in server.incoming_requests() {
+                        this.respond(request);
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            let _ = worker.join();
+        }
+
+        Ok(())
+    }
+
+    fn respond(&self, mut request: tiny_http::Request) {
+        let headers: Vec<(String, String)> = request
+            .headers()
+            .iter()
+            .map(|header| (header.field.to_string(), header.value.to_string()))
+            .collect();
+        let headers: Vec<(&str, &str)> = headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        let method = request.method().as_str().to_string();
+        let url = request.url().to_string();
+
+        let response = match self.check(&method, &url, &headers) {
+            Err(response) => response,
+            Ok(()) => match request.body_length() {
+                Some(length) if length > MAX_BODY => {
+                    Response::error(413, format!("请求正文不能超过 {MAX_BODY} 字节"))
+                }
+                None if method == "POST" => Response::error(413, "POST 请求需要 Content-Length"),
+                _ => {
+                    let mut body = String::new();
+                    let mut reader = request.as_reader().take(MAX_BODY as u64);
+                    match reader.read_to_string(&mut body) {
+                        Ok(_) => self.route(&method, &url, &body),
+                        Err(e) => Response::error(400, format!("无法读取请求正文: {e}")),
+                    }
+                }
+            },
+        };
+
+        let _ = request.respond(into_http(response));
+    }
+
+    /// 处理一个请求，`url` 为路径和查询字符串，如 `/windows?class=Notepad`，
+    /// `headers` 为请求头的名称和值
+    pub fn handle(
+        &self,
+        method: &str,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: &str,
+    ) -> Response {
+        match self.check(method, url, headers) {
+            Ok(()) => self.route(method, url, body),
+            Err(response) => response,
+        }
+    }
+
+    fn route(&self, method: &str, url: &str, body: &str) -> Response {
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+        let result = match (method, segments.as_slice()) {
+            ("GET", ["openapi.json"]) => Ok(Response::ok(openapi())),
+            ("GET", ["windows"]) => self.find(query),
+            ("GET", ["windows", hwnd]) => {
+                self.window(hwnd).map(|window| Response::ok(json(window)))
+            }
+            ("GET", ["windows", hwnd, "children"]) => self.window(hwnd).and_then(|window| {
+                let children = self.backend().children(&window)?;
+                Ok(Response::ok(children.into_iter().map(json).collect()))
+            }),
+            ("GET", ["windows", hwnd, "text"]) => self.window(hwnd).and_then(|window| {
+                let text = self.backend().text(&window)?;
+                Ok(Response::ok(json!({ "text": text })))
+            }),
+            ("POST", ["windows", hwnd, "focus"]) => self.window(hwnd).and_then(|window| {
+                self.backend().focus(&window)?;
+                Ok(Response::no_content())
+            }),
+            ("POST", ["windows", hwnd, "messages"]) => self.window(hwnd).and_then(|window| {
+                let params: Vec<MessageParam> =
+                    serde_json::from_str(body).map_err(RpcError::invalid_params)?;
+                self.backend().send(&window, &protocol::messages(params)?)?;
+                Ok(Response::no_content())
+            }),
+            _ if routed(&segments) => Ok(Response::error(405, format!("{path} 不支持 {method}"))),
+            _ => Ok(Response::error(404, format!("找不到 {path}"))),
+        };
+
+        match result {
+            Ok(response) | Err(response) => response,
+        }
+    }
+
+    /// 检查 `Host`、令牌和 `Content-Type`
+    fn check(
+        &self,
+        method: &str,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> std::result::Result<(), Response> {
+        let header = |name: &str| {
+            headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.trim())
+        };
+
+        if let Some(addr) = self.addr
+            && !header("Host").is_some_and(|host| allowed_host(addr, host))
+        {
+            return Err(Response::error(403, "Host 不是服务端监听的地址"));
+        }
+
+        let authorized = header("Authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| token_eq(&self.token, token.trim()));
+        let path = url.split('?').next().unwrap_or_default();
+        if !authorized && path.trim_matches('/') != "openapi.json" {
+            return Err(Response::error(401, "需要 Authorization: Bearer <令牌>"));
+        }
+
+        let json = header("Content-Type").is_some_and(|value| {
+            let media_type = value.split(';').next().unwrap_or_default().trim();
+            media_type.eq_ignore_ascii_case("application/json")
+        });
+        if method == "POST" && !json {
+            return Err(Response::error(415, "Content-Type 必须是 application/json"));
+        }
+
+        Ok(())
+    }
+
+    fn find(&self, query: &str) -> std::result::Result<Response, Response> {
+        let mut path = None;
+        let mut selector = Selector::any();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = decode(value)
+                .ok_or_else(|| Response::error(400, format!("无效的查询参数 {pair}")))?;
+            match key {
+                "path" => path = Some(value),
+                "class" => selector.class_name = Some(value),
+                "caption" => selector.caption = Some(value),
+                "pid" => {
+                    selector.pid = Some(
+                        value
+                            .parse()
+                            .map_err(|_| Response::error(400, format!("无效的进程 ID {value}")))?,
+                    )
+                }
+                _ => return Err(Response::error(400, format!("未知的查询参数 {key}"))),
+            }
+        }
+
+        let path = match path {
+            Some(path) if selector == Selector::any() => path.parse()?,
+            Some(_) => return Err(Response::error(400, "path 不能与其它条件同时使用")),
+            None => SelectorPath(vec![selector]),
+        };
+        let windows = self.backend().find(&path)?;
+
+        Ok(Response::ok(windows.into_iter().map(json).collect()))
+    }
+
+    fn window(&self, hwnd: &str) -> std::result::Result<WindowHandle, Response> {
+        let id = match hwnd.strip_prefix("0x").or_else(|| hwnd.strip_prefix("0X")) {
+            Some(hex) => usize::from_str_radix(hex, 16),
+            None => hwnd.parse(),
+        };
+        let id = id.map_err(|_| Response::error(400, format!("无效的窗口句柄 {hwnd}")))?;
+
+        Ok(self.backend().resolve(id)?)
+    }
+}
+
+/// `Host` 头是否为监听的地址，端口为 80 时可以省略
+fn allowed_host(addr: SocketAddr, host: &str) -> bool {
+    let port = addr.port().to_string();
+    let (name, host_port) = match host.rsplit_once(':') {
+        Some((name, host_port)) if !host_port.contains(']') => (name, host_port),
+        _ => (host, "80"),
+    };
+    if host_port != port {
+        return false;
+    }
+
+    let name = name.trim_start_matches('[').trim_end_matches(']');
+    match name.parse::<std::net::IpAddr>() {
+        // 监听所有地址时接受任意 IP，但不接受域名
+        Ok(ip) => addr.ip().is_unspecified() || ip == addr.ip(),
+        Err(_) => {
+            (addr.ip().is_loopback() || addr.ip().is_unspecified())
+                && name.eq_ignore_ascii_case("localhost")
+        }
+    }
+}
+
+/// 路径存在但方法不对
+fn routed(segments: &[&str]) -> bool {
+    matches!(
+        segments,
+        ["openapi.json"]
+            | ["windows"]
+            | ["windows", _]
+            | ["windows", _, "children" | "text" | "focus" | "messages"]
+    )
+}
+
+fn json(window: WindowHandle) -> Value {
+    json!({
+        "hwnd": window.id,
+        "class_name": window.class_name,
+        "caption": window.caption,
+    })
+}
+
+/// 解码查询参数中的 `%XX` 和 `+`
+fn decode(s: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut iter = s.bytes();
+    while let Some(b) = iter.next() {
+        bytes.push(match b {
+            b'+' => b' ',
+            b'%' => {
+                let hex = [iter.next()?, iter.next()?];
+                u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?
+            }
+            b => b,
+        });
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+fn into_http(response: Response) -> tiny_http::Response<io::Cursor<Vec<u8>>> {
+    let status = response.status;
+    match response.body {
+        None => tiny_http::Response::from_data(Vec::new()).with_status_code(status),
+        Some(body) => {
+            let content_type =
+                Header::from_bytes("Content-Type", "application/json; charset=utf-8")
+                    .expect("固定的响应头");
+            tiny_http::Response::from_data(body.to_string())
+                .with_status_code(status)
+                .with_header(content_type)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    use super::*;
+    use crate::script::{FakeDesktop, FakeWindow};
+
+    const HEADERS: [(&str, &str); 2] = [
+        ("Authorization", "Bearer secret"),
+        ("Content-Type", "application/json"),
+    ];
+
+    fn server() -> Server<FakeDesktop> {
+        Server::new(
+            FakeDesktop::new()
+                .with_window(
+                    FakeWindow::new("Notepad", "无标题 - 记事本")
+                        .with_pid(7)
+                        .with_child(FakeWindow::new("Edit", "").with_text("abc")),
+                )
+                .with_window(FakeWindow::new("#32770", "保存")),
+            "secret",
+        )
+    }
+
+    #[test]
+    fn find() {
+        let server = server();
+
+        let response = server.handle("GET", "/windows", &HEADERS, "");
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body.unwrap().as_array().unwrap().len(), 2);
+
+        let response = server.handle("GET", "/windows?class=Notepad&pid=7", &HEADERS, "");
+        assert_eq!(
+            response.body.unwrap(),
+            json!([{ "hwnd": 0, "class_name": "Notepad", "caption": "无标题 - 记事本" }])
+        );
+
+        let response = server.handle("GET", "/windows?caption=%E4%BF%9D%E5%AD%98", &HEADERS, "");
+        assert_eq!(response.body.unwrap()[0]["class_name"], "#32770");
+
+        let response = server.handle("GET", "/windows?path=Notepad+%3E+Edit", &HEADERS, "");
+        assert_eq!(response.body.unwrap()[0]["hwnd"], 1);
+
+        assert_eq!(
+            server.handle("GET", "/windows?pid=x", &HEADERS, "").status,
+            400
+        );
+        assert_eq!(
+            server
+                .handle("GET", "/windows?path=Edit&pid=7", &HEADERS, "")
+                .status,
+            400
+        );
+        assert_eq!(
+            server
+                .handle("GET", "/windows?path=Edit[", &HEADERS, "")
+                .status,
+            400
+        );
+        assert_eq!(
+            server
+                .handle("GET", "/windows?color=red", &HEADERS, "")
+                .status,
+            400
+        );
+    }
+
+    #[test]
+    fn window() {
+        let server = server();
+
+        let response = server.handle("GET", "/windows/0x1", &HEADERS, "");
+        assert_eq!(response.body.unwrap()["class_name"], "Edit");
+
+        let response = server.handle("GET", "/windows/0/children", &HEADERS, "");
+        assert_eq!(response.body.unwrap()[0]["hwnd"], 1);
+
+        let response = server.handle("GET", "/windows/1/text", &HEADERS, "");
+        assert_eq!(response.body.unwrap(), json!({ "text": "abc" }));
+
+        let response = server.handle("GET", "/windows/99", &HEADERS, "");
+        assert_eq!(response.status, 404);
+        assert_eq!(response.body.unwrap(), json!({ "error": "找不到指定窗口" }));
+        assert_eq!(
+            server.handle("GET", "/windows/abc", &HEADERS, "").status,
+            400
+        );
+    }
+
+    #[test]
+    fn actions() {
+        let server = server();
+
+        assert_eq!(
+            server.handle("POST", "/windows/0/focus", &HEADERS, ""),
+            Response::no_content()
+        );
+        let body = r#"[{"type": "text", "text": "de"}, {"type": "key", "key": "Enter"}]"#;
+        assert_eq!(
+            server
+                .handle("POST", "/windows/1/messages", &HEADERS, body)
+                .status,
+            204
+        );
+        assert_eq!(server.backend().window(1).unwrap().text, "abcde");
+        assert_eq!(server.backend().focused(), Some(0));
+
+        assert_eq!(
+            server
+                .handle("POST", "/windows/1/messages", &HEADERS, "{")
+                .status,
+            400
+        );
+        let body = r#"[{"type": "key", "key": "Nope"}]"#;
+        assert_eq!(
+            server
+                .handle("POST", "/windows/1/messages", &HEADERS, body)
+                .status,
+            400
+        );
+    }
+
+    #[test]
+    fn routes() {
+        let server = server();
+
+        assert_eq!(
+            server.handle("DELETE", "/windows/0", &HEADERS, "").status,
+            405
+        );
+        assert_eq!(
+            server
+                .handle("GET", "/windows/0/focus", &HEADERS, "")
+                .status,
+            405
+        );
+        assert_eq!(server.handle("GET", "/menus", &HEADERS, "").status, 404);
+
+        let document = server
+            .handle("GET", "/openapi.json", &HEADERS, "")
+            .body
+            .unwrap();
+        for (path, operations) in document["paths"].as_object().unwrap() {
+            let url = path.replace("{hwnd}", "0");
+            for method in operations.as_object().unwrap().keys() {
+                let status = server
+                    .handle(&method.to_uppercase(), &url, &HEADERS, "[]")
+                    .status;
+                assert!(status < 400, "{method} {path}: {status}");
+            }
+        }
+    }
+
+    #[test]
+    fn security() {
+        let server = server();
+
+        assert_eq!(server.handle("GET", "/windows", &[], "").status, 401);
+        let wrong = [("Authorization", "Bearer guess")];
+        assert_eq!(server.handle("GET", "/windows", &wrong, "").status, 401);
+        assert_eq!(server.handle("GET", "/openapi.json", &[], "").status, 200);
+
+        // 网页表单能发出的跨域请求不带 application/json
+        let form = [
+            ("authorization", "Bearer secret"),
+            ("Content-Type", "text/plain"),
+        ];
+        assert_eq!(
+            server.handle("POST", "/windows/0/focus", &form, "").status,
+            415
+        );
+        let json = [
+            ("authorization", "Bearer secret"),
+            ("content-type", "application/json; charset=utf-8"),
+        ];
+        assert_eq!(
+            server.handle("POST", "/windows/0/focus", &json, "").status,
+            204
+        );
+    }
+
+    #[test]
+    fn hosts() {
+        let loopback: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+        assert!(allowed_host(loopback, "127.0.0.1:8080"));
+        assert!(allowed_host(loopback, "LOCALHOST:8080"));
+        assert!(!allowed_host(loopback, "localhost"));
+        assert!(!allowed_host(loopback, "127.0.0.1:80"));
+        assert!(!allowed_host(loopback, "evil.example:8080"));
+
+        let any: SocketAddr = "0.0.0.0:80".parse().unwrap();
+        assert!(allowed_host(any, "192.168.1.5"));
+        assert!(!allowed_host(any, "evil.example"));
+
+        let v6: SocketAddr = "[::1]:8080".parse().unwrap();
+        assert!(allowed_host(v6, "[::1]:8080"));
+        assert!(!allowed_host(v6, "127.0.0.1:8080"));
+    }
+
+    #[test]
+    fn serve() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = server();
+        let serving = server.clone();
+        thread::spawn(move || serving.serve(listener));
+
+        let send = |request: String| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let get = |host: &str| {
+            send(format!(
+                "GET /windows/1/text HTTP/1.1\r\nHost: {host}\r\n\
+                 Authorization: Bearer secret\r\nConnection: close\r\n\r\n"
+            ))
+        };
+        let post = |token: &str, length: &str| {
+            send(format!(
+                "POST /windows/1/focus HTTP/1.1\r\nHost: {addr}\r\n\
+                 Authorization: Bearer {token}\r\nContent-Type: application/json\r\n\
+                 {length}Connection: close\r\n\r\n"
+            ))
+        };
+
+        let response = get(&format!("localhost:{}", addr.port()));
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.contains("application/json"));
+        assert!(response.ends_with(r#"{"text":"abc"}"#));
+
+        let response = get(&format!("evil.example:{}", addr.port()));
+        assert!(response.starts_with("HTTP/1.1 403"), "{response}");
+
+        // 请求头不通过时不读取正文
+        let response = post("wrong", "Content-Length: 100000000\r\n");
+        assert!(response.starts_with("HTTP/1.1 401"), "{response}");
+        let response = post("secret", "Content-Length: 100000000\r\n");
+        assert!(response.starts_with("HTTP/1.1 413"), "{response}");
+        let response = post("secret", "Transfer-Encoding: chunked\r\n");
+        assert!(response.starts_with("HTTP/1.1 413"), "{response}");
+    }
+}
@@ -0,0 +1,331 @@
+use windows::Win32::Foundation::{HWND, POINT};
+use windows::Win32::Graphics::Gdi::ClientToScreen;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    GetAsyncKeyState, INPUT, INPUT_0, INPUT_KEYBOARD, INPUT_MOUSE, KEYBD_EVENT_FLAGS, KEYBDINPUT,
+    KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP, KEYEVENTF_UNICODE, MOUSE_EVENT_FLAGS,
+    MOUSEEVENTF_ABSOLUTE, MOUSEEVENTF_HWHEEL, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP,
+    MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP, MOUSEEVENTF_MOVE, MOUSEEVENTF_RIGHTDOWN,
+    MOUSEEVENTF_RIGHTUP, MOUSEEVENTF_VIRTUALDESK, MOUSEEVENTF_WHEEL, MOUSEINPUT, SendInput,
+    VIRTUAL_KEY,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetSystemMetrics, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN, SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN,
+};
+
+use crate::error::Error;
+use crate::prelude::Result;
+use crate::window::msg::{Message, WindowMessage, send_message_seq};
+
+/// 鼠标按键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MouseButton {
+    #[default]
+    Left,
+    Right,
+    Middle,
+}
+
+impl MouseButton {
+    /// 按下和松开时的 `SendInput` 标志
+    fn input_flags(self) -> (MOUSE_EVENT_FLAGS, MOUSE_EVENT_FLAGS) {
+        match self {
+            MouseButton::Left => (MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP),
+            MouseButton::Right => (MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP),
+            MouseButton::Middle => (MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP),
+        }
+    }
+}
+
+/// 虚拟桌面（所有显示器组成的区域）的屏幕坐标范围(左,上,宽,高)
+pub fn virtual_screen() -> (i32, i32, i32, i32) {
+    unsafe {
+        (
+            GetSystemMetrics(SM_XVIRTUALSCREEN),
+            GetSystemMetrics(SM_YVIRTUALSCREEN),
+            GetSystemMetrics(SM_CXVIRTUALSCREEN),
+            GetSystemMetrics(SM_CYVIRTUALSCREEN),
+        )
+    }
+}
+
+/// 将虚拟桌面中的屏幕坐标换算为 `MOUSEEVENTF_ABSOLUTE` 使用的 0~65535 归一化坐标
+pub(crate) fn normalize_absolute(
+    (x, y): (i32, i32),
+    (left, top, width, height): (i32, i32, i32, i32),
+) -> (i32, i32) {
+    let normalize = |value: i32, origin: i32, size: i32| {
+        let span = (size - 1).max(1) as i64;
+        let offset = (value - origin).clamp(0, size.max(1) - 1) as i64;
+
+        // 四舍五入，避免换算回像素时偏差一个像素
+        ((offset * 65535 + span / 2) / span) as i32
+    };
+
+    (normalize(x, left, width), normalize(y, top, height))
+}
+
+/// 需要 `KEYEVENTF_EXTENDEDKEY` 标志的按键（方向键、编辑键区等）
+pub(crate) fn is_extended_key(vk: u16) -> bool {
+    matches!(
+        vk,
+        0x21..=0x28 // PageUp、PageDown、End、Home、方向键
+            | 0x2D | 0x2E // Insert、Delete
+            | 0x5B..=0x5D // LWin、RWin、Apps
+            | 0x6F // Numpad /
+            | 0x90 // NumLock
+            | 0xA3 | 0xA5 // RCtrl、RAlt
+    )
+}
+
+fn keyboard_input(vk: u16, scan: u16, flags: KEYBD_EVENT_FLAGS) -> INPUT {
+    INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: VIRTUAL_KEY(vk),
+                wScan: scan,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    }
+}
+
+fn mouse_input(dx: i32, dy: i32, data: i32, flags: MOUSE_EVENT_FLAGS) -> INPUT {
+    INPUT {
+        r#type: INPUT_MOUSE,
+        Anonymous: INPUT_0 {
+            mi: MOUSEINPUT {
+                dx,
+                dy,
+                mouseData: data as u32,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    }
+}
+
+fn key_input(vk: u16, up: bool) -> INPUT {
+    let mut flags = KEYBD_EVENT_FLAGS(0);
+    if is_extended_key(vk) {
+        flags |= KEYEVENTF_EXTENDEDKEY;
+    }
+    if up {
+        flags |= KEYEVENTF_KEYUP;
+    }
+
+    keyboard_input(vk, 0, flags)
+}
+
+/// 将文本转换为 Unicode 键盘输入，每个 UTF-16 单元一对按下/松开事件
+fn text_inputs(text: &str) -> Vec<INPUT> {
+    text.encode_utf16()
+        .flat_map(|unit| {
+            [
+                keyboard_input(0, unit, KEYEVENTF_UNICODE),
+                keyboard_input(0, unit, KEYEVENTF_UNICODE | KEYEVENTF_KEYUP),
+            ]
+        })
+        .collect()
+}
+
+/// 调用 `SendInput` 注入输入事件
+///
+/// 注入的事件少于预期时返回 [`Error::SendInputFailed`]，通常是因为目标程序的权限更高（UIPI）。
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(inputs = inputs.len()), err)
+)]
+pub fn send_inputs(inputs: &[INPUT]) -> Result<()> {
+    if inputs.is_empty() {
+        return Ok(());
+    }
+
+    let sent = unsafe { SendInput(inputs, std::mem::size_of::<INPUT>() as i32) };
+
+    (sent as usize == inputs.len())
+        .then_some(())
+        .ok_or(Error::SendInputFailed)
+}
+
+/// 按下按键
+pub fn key_down(vk: u16) -> Result<()> {
+    send_inputs(&[key_input(vk, false)])
+}
+
+/// 松开按键
+pub fn key_up(vk: u16) -> Result<()> {
+    send_inputs(&[key_input(vk, true)])
+}
+
+/// 按下并松开按键
+pub fn key_press(vk: u16) -> Result<()> {
+    send_inputs(&[key_input(vk, false), key_input(vk, true)])
+}
+
+/// 按键当前是否处于按下状态（物理按键或注入的输入）
+pub fn is_key_down(vk: u16) -> bool {
+    // 最高位表示按下
+    let state = unsafe { GetAsyncKeyState(vk as i32) };
+    state < 0
+}
+
+/// 按下组合键，如 `key_chord(&[VK_CONTROL, VK_S])`，按相反顺序松开
+pub fn key_chord(vks: &[u16]) -> Result<()> {
+    let inputs: Vec<INPUT> = vks
+        .iter()
+        .map(|&vk| key_input(vk, false))
+        .chain(vks.iter().rev().map(|&vk| key_input(vk, true)))
+        .collect();
+
+    send_inputs(&inputs)
+}
+
+/// 输入文本，与键盘布局和输入法无关
+pub fn type_text(text: impl AsRef<str>) -> Result<()> {
+    send_inputs(&text_inputs(text.as_ref()))
+}
+
+/// 将鼠标移动到屏幕坐标，支持多显示器组成的虚拟桌面
+pub fn mouse_move_to(x: i32, y: i32) -> Result<()> {
+    let (dx, dy) = normalize_absolute((x, y), virtual_screen());
+
+    send_inputs(&[mouse_input(
+        dx,
+        dy,
+        0,
+        MOUSEEVENTF_MOVE | MOUSEEVENTF_ABSOLUTE | MOUSEEVENTF_VIRTUALDESK,
+    )])
+}
+
+/// 相对当前位置移动鼠标（受系统鼠标加速设置影响）
+pub fn mouse_move_by(dx: i32, dy: i32) -> Result<()> {
+    send_inputs(&[mouse_input(dx, dy, 0, MOUSEEVENTF_MOVE)])
+}
+
+/// 按下鼠标按键
+pub fn mouse_down(button: MouseButton) -> Result<()> {
+    send_inputs(&[mouse_input(0, 0, 0, button.input_flags().0)])
+}
+
+/// 松开鼠标按键
+pub fn mouse_up(button: MouseButton) -> Result<()> {
+    send_inputs(&[mouse_input(0, 0, 0, button.input_flags().1)])
+}
+
+/// 在当前位置单击鼠标按键
+pub fn mouse_click(button: MouseButton) -> Result<()> {
+    let (down, up) = button.input_flags();
+
+    send_inputs(&[mouse_input(0, 0, 0, down), mouse_input(0, 0, 0, up)])
+}
+
+/// 滚动鼠标滚轮，`delta` 为正时向上滚动，一格为 120
+pub fn mouse_wheel(delta: i32) -> Result<()> {
+    send_inputs(&[mouse_input(0, 0, delta, MOUSEEVENTF_WHEEL)])
+}
+
+/// 水平滚动鼠标滚轮，`delta` 为正时向右滚动
+pub fn mouse_hwheel(delta: i32) -> Result<()> {
+    send_inputs(&[mouse_input(0, 0, delta, MOUSEEVENTF_HWHEEL)])
+}
+
+/// 以真实输入的方式执行消息序列
+///
+/// 与 [`send_message_seq`] 接受相同的消息，但键盘和鼠标事件通过 `SendInput` 注入，
+/// 会发送到前台窗口的焦点控件，调用前需要先让目标窗口获得焦点：
+///
+/// - [`WindowMessage::KeyDown`] 按下并松开按键
+/// - [`WindowMessage::Char`] 以 Unicode 方式输入字符
+/// - [`WindowMessage::MouseMove`] 将鼠标移动到 `hwnd` 工作区坐标对应的位置
+/// - [`WindowMessage::Command`] 没有对应的输入事件，仍以消息方式发送
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(hwnd = ?hwnd.0, messages = msg_seq.len()), err)
+)]
+pub(crate) fn send_input_seq(hwnd: HWND, msg_seq: Vec<Message>) -> Result<()> {
+    for message in msg_seq {
+        let count = message.count.max(1);
+
+        match message.msg {
+            WindowMessage::KeyDown(vk) => {
+                let inputs: Vec<INPUT> = (0..count)
+                    .flat_map(|_| [key_input(vk as u16, false), key_input(vk as u16, true)])
+                    .collect();
+                send_inputs(&inputs)?;
+            }
+            WindowMessage::Char(c) => {
+                let text: String = std::iter::repeat_n(c, count as usize).collect();
+                type_text(text)?;
+            }
+            WindowMessage::MouseMove(x, y) => {
+                let mut point = POINT { x, y };
+                unsafe { ClientToScreen(hwnd, &mut point) }
+                    .ok()
+                    .map_err(|_| Error::WindowNotFound)?;
+                mouse_move_to(point.x, point.y)?;
+            }
+            msg @ WindowMessage::Command(_) => {
+                send_message_seq(hwnd, vec![Message { msg, count }])?
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: (i32, i32, i32, i32) = (0, 0, 1920, 1080);
+
+    #[test]
+    fn normalize_corners() {
+        assert_eq!(normalize_absolute((0, 0), SCREEN), (0, 0));
+        assert_eq!(normalize_absolute((1919, 1079), SCREEN), (65535, 65535));
+        assert_eq!(normalize_absolute((960, 540), SCREEN), (32785, 32798));
+    }
+
+    #[test]
+    fn normalize_multi_monitor() {
+        // 副屏位于主屏左侧，虚拟桌面原点为负坐标
+        let screen = (-1280, -200, 3200, 1280);
+        assert_eq!(normalize_absolute((-1280, -200), screen), (0, 0));
+        assert_eq!(normalize_absolute((1919, 1079), screen), (65535, 65535));
+        assert_eq!(normalize_absolute((0, 0), screen).0, 26222);
+    }
+
+    #[test]
+    fn normalize_clamps_outside_points() {
+        assert_eq!(normalize_absolute((-10, 5000), SCREEN), (0, 65535));
+    }
+
+    #[test]
+    fn extended_keys() {
+        assert!(is_extended_key(0x25)); // Left
+        assert!(is_extended_key(0x2E)); // Delete
+        assert!(!is_extended_key(0x41)); // A
+        assert!(!is_extended_key(0x0D)); // Enter
+    }
+
+    #[test]
+    fn text_inputs_use_utf16_units() {
+        let inputs = text_inputs("a😀");
+        // 'a' 一个单元，emoji 为代理对两个单元，每个单元按下+松开
+        assert_eq!(inputs.len(), 6);
+
+        let scans: Vec<u16> = inputs
+            .iter()
+            .map(|i| unsafe { i.Anonymous.ki.wScan })
+            .collect();
+        assert_eq!(scans, [0x61, 0x61, 0xD83D, 0xD83D, 0xDE00, 0xDE00]);
+        assert_eq!(
+            unsafe { inputs[1].Anonymous.ki.dwFlags },
+            KEYEVENTF_UNICODE | KEYEVENTF_KEYUP
+        );
+    }
+}
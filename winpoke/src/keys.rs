@@ -0,0 +1,179 @@
+/// 按键名称与虚拟键码的映射表
+///
+/// 同一键码有多个名称时，排在前面的作为规范名称。
+const KEY_NAMES: &[(&str, u32)] = &[
+    ("Backspace", 0x08),
+    ("Back", 0x08),
+    ("Tab", 0x09),
+    ("Enter", 0x0D),
+    ("Return", 0x0D),
+    ("Shift", 0x10),
+    ("Ctrl", 0x11),
+    ("Control", 0x11),
+    ("Alt", 0x12),
+    ("Menu", 0x12),
+    ("Pause", 0x13),
+    ("CapsLock", 0x14),
+    ("Capital", 0x14),
+    ("Esc", 0x1B),
+    ("Escape", 0x1B),
+    ("Space", 0x20),
+    ("PageUp", 0x21),
+    ("Prior", 0x21),
+    ("PageDown", 0x22),
+    ("Next", 0x22),
+    ("End", 0x23),
+    ("Home", 0x24),
+    ("Left", 0x25),
+    ("Up", 0x26),
+    ("Right", 0x27),
+    ("Down", 0x28),
+    ("PrintScreen", 0x2C),
+    ("Snapshot", 0x2C),
+    ("Insert", 0x2D),
+    ("Ins", 0x2D),
+    ("Delete", 0x2E),
+    ("Del", 0x2E),
+    ("LWin", 0x5B),
+    ("RWin", 0x5C),
+    ("Apps", 0x5D),
+    ("Multiply", 0x6A),
+    ("Add", 0x6B),
+    ("Subtract", 0x6D),
+    ("Decimal", 0x6E),
+    ("Divide", 0x6F),
+    ("NumLock", 0x90),
+    ("ScrollLock", 0x91),
+    ("Scroll", 0x91),
+];
+
+/// 通过按键名称获取虚拟键码
+///
+/// 名称不区分大小写，可带 `VK_` 前缀，支持单个字母和数字、`F1`~`F24`、
+/// `Numpad0`~`Numpad9`，以及 `Enter`、`Left`、`PageUp` 等常用按键名，
+/// 也可以直接写十六进制键码（如 `0x25`）。
+pub fn vk_from_name(name: impl AsRef<str>) -> Option<u32> {
+    let name = name.as_ref().trim();
+    let name = strip_prefix_ignore_case(name, "VK_").unwrap_or(name);
+
+    if let Some(hex) = strip_prefix_ignore_case(name, "0x") {
+        return u32::from_str_radix(hex, 16)
+            .ok()
+            .filter(|&vk| vk > 0 && vk < 0xFF);
+    }
+
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next())
+        && c.is_ascii_alphanumeric()
+    {
+        return Some(c.to_ascii_uppercase() as u32);
+    }
+
+    if let Some(n) = strip_prefix_ignore_case(name, "Numpad").and_then(|n| n.parse::<u32>().ok()) {
+        return (n <= 9).then_some(0x60 + n);
+    }
+
+    if let Some(n) = strip_prefix_ignore_case(name, "F").and_then(|n| n.parse::<u32>().ok()) {
+        return (1..=24).contains(&n).then_some(0x70 + n - 1);
+    }
+
+    KEY_NAMES
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|&(_, vk)| vk)
+}
+
+/// 获取虚拟键码的规范名称
+pub fn name_from_vk(vk: u32) -> Option<String> {
+    match vk {
+        0x30..=0x39 | 0x41..=0x5A => char::from_u32(vk).map(String::from),
+        0x60..=0x69 => Some(format!("Numpad{}", vk - 0x60)),
+        0x70..=0x87 => Some(format!("F{}", vk - 0x70 + 1)),
+        _ => KEY_NAMES
+            .iter()
+            .find(|&&(_, code)| code == vk)
+            .map(|(name, _)| name.to_string()),
+    }
+}
+
+/// 解析以 `+` 连接的组合键，如 `Ctrl+Shift+S`，按书写顺序返回各键的虚拟键码
+///
+/// 除 [`vk_from_name`] 支持的名称外，还接受 `Win` 表示左 Windows 键。
+pub fn parse_chord(chord: impl AsRef<str>) -> Option<Vec<u32>> {
+    chord
+        .as_ref()
+        .split('+')
+        .map(|name| match name.trim() {
+            name if name.eq_ignore_ascii_case("Win") => Some(0x5B),
+            name => vk_from_name(name),
+        })
+        .collect()
+}
+
+/// 以 `+` 连接各键的规范名称，是 [`parse_chord`] 的逆操作
+pub fn format_chord(vks: &[u32]) -> String {
+    vks.iter()
+        .map(|&vk| name_from_vk(vk).unwrap_or_else(|| format!("0x{vk:02X}")))
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = s.get(..prefix.len())?;
+
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &s[prefix.len()..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letters_and_digits() {
+        assert_eq!(vk_from_name("j"), Some(0x4A));
+        assert_eq!(vk_from_name("J"), Some(0x4A));
+        assert_eq!(vk_from_name("7"), Some(0x37));
+    }
+
+    #[test]
+    fn named_keys() {
+        assert_eq!(vk_from_name("VK_LEFT"), Some(0x25));
+        assert_eq!(vk_from_name("left"), Some(0x25));
+        assert_eq!(vk_from_name("Return"), Some(0x0D));
+        assert_eq!(vk_from_name("F12"), Some(0x7B));
+        assert_eq!(vk_from_name("Numpad3"), Some(0x63));
+        assert_eq!(vk_from_name("0x2E"), Some(0x2E));
+    }
+
+    #[test]
+    fn unknown_keys() {
+        for name in ["", "F0", "F25", "Numpad10", "0x0", "0x100", "Foo", "é"] {
+            assert_eq!(vk_from_name(name), None, "{name}");
+        }
+    }
+
+    #[test]
+    fn chords() {
+        assert_eq!(parse_chord("Ctrl+S"), Some(vec![0x11, 0x53]));
+        assert_eq!(parse_chord(" win + e "), Some(vec![0x5B, 0x45]));
+        assert_eq!(parse_chord("Enter"), Some(vec![0x0D]));
+
+        for chord in ["", "Ctrl+", "+S", "Ctrl++S", "Ctrl+Foo"] {
+            assert_eq!(parse_chord(chord), None, "{chord}");
+        }
+
+        let vks = parse_chord("control+shift+0xBA").unwrap();
+        assert_eq!(format_chord(&vks), "Ctrl+Shift+0xBA");
+        assert_eq!(parse_chord(format_chord(&vks)), Some(vks));
+    }
+
+    #[test]
+    fn canonical_names_round_trip() {
+        for vk in [0x08, 0x0D, 0x25, 0x41, 0x39, 0x63, 0x7B] {
+            let name = name_from_vk(vk).unwrap();
+            assert_eq!(vk_from_name(&name), Some(vk), "{name}");
+        }
+        assert_eq!(name_from_vk(0x0D).as_deref(), Some("Enter"));
+    }
+}
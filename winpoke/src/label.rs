@@ -0,0 +1,78 @@
+/// 菜单路径各级之间的分隔符
+pub const PATH_SEPARATOR: char = '>';
+
+/// 去掉菜单、按钮文本中的助记符和快捷键说明
+///
+/// - `\t` 之后为快捷键说明，整体去掉
+/// - 中文菜单常见的 `(&F)` 后缀整体去掉
+/// - 其余的 `&` 去掉，`&&` 还原为 `&`
+pub(crate) fn normalize(text: &str) -> String {
+    let text = text.split('\t').next().unwrap_or_default();
+
+    let mut label = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '(' if chars.peek() == Some(&'&') => {
+                // 形如 (&F) 的助记符后缀
+                let rest: String = chars.clone().take(3).collect();
+                let mut rest_chars = rest.chars();
+                if let (Some('&'), Some(key), Some(')')) =
+                    (rest_chars.next(), rest_chars.next(), rest_chars.next())
+                    && key != '&'
+                {
+                    chars.nth(2);
+                } else {
+                    label.push(c);
+                }
+            }
+            '&' => {
+                if chars.peek() == Some(&'&') {
+                    chars.next();
+                    label.push('&');
+                }
+            }
+            _ => label.push(c),
+        }
+    }
+
+    label.trim().to_string()
+}
+
+/// 比较控件文本与用户输入的名称，忽略助记符、末尾的省略号和大小写
+pub(crate) fn matches(text: &str, segment: &str) -> bool {
+    let strip = |s: &str| {
+        normalize(s)
+            .trim_end_matches("...")
+            .trim_end_matches('…')
+            .trim()
+            .to_lowercase()
+    };
+
+    strip(text) == strip(segment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_mnemonics() {
+        assert_eq!(normalize("&File"), "File");
+        assert_eq!(normalize("文件(&F)"), "文件");
+        assert_eq!(normalize("导出(&E)...\tCtrl+E"), "导出...");
+        assert_eq!(normalize("Save && Close"), "Save & Close");
+        assert_eq!(normalize("(&&)"), "(&)");
+        assert_eq!(normalize("Open (&O"), "Open (O");
+    }
+
+    #[test]
+    fn matching() {
+        assert!(matches("确定", "确定"));
+        assert!(matches("&Yes", "yes"));
+        assert!(matches("是(&Y)", "是"));
+        assert!(matches("导出(&E)...", "导出"));
+        assert!(matches("Save &As…", "save as"));
+        assert!(!matches("&No", "yes"));
+    }
+}
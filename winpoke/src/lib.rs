@@ -1,11 +1,40 @@
+#[cfg(windows)]
+pub mod apps;
+#[cfg(feature = "async")]
+pub mod asynchronous;
+pub mod color;
+#[cfg(windows)]
+pub mod control;
+pub mod dialog;
 pub mod error;
+#[cfg(windows)]
+pub mod hotkey;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(windows)]
+pub mod input;
+pub mod keys;
+pub(crate) mod label;
+#[cfg(windows)]
+pub mod menu;
+pub mod message;
+pub mod recorder;
+pub mod registry;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+pub mod script;
+pub mod selector;
+#[cfg(windows)]
 pub mod window;
 
 pub mod prelude {
     pub use crate::error::Error;
+    pub use crate::message::{Message, WindowMessage};
+    pub use crate::selector::{Selector, SelectorPath};
+    #[cfg(windows)]
     pub use crate::window::WindowInfo;
-    pub use crate::window::msg::Message;
-    pub use crate::window::msg::WindowMessage;
+    #[cfg(windows)]
+    pub use crate::window::msg::Delivery;
 
     pub type Result<T> = std::result::Result<T, Error>;
 }
@@ -0,0 +1,318 @@
+use std::fmt::Display;
+
+use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetMenu, GetMenuItemCount, GetMenuItemInfoW, HMENU, MENUITEMINFOW, MFS_CHECKED, MFS_DISABLED,
+    MFT_SEPARATOR, MIIM_FTYPE, MIIM_ID, MIIM_STATE, MIIM_STRING, MIIM_SUBMENU, WM_COMMAND,
+};
+use windows::core::PWSTR;
+
+use crate::error::Error;
+use crate::label;
+use crate::prelude::Result;
+use crate::window::WindowInfo;
+use crate::window::msg::post_message;
+
+pub use crate::label::PATH_SEPARATOR;
+
+/// 菜单项
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MenuItem {
+    /// 菜单项的原始文本，包含 `&` 助记符和 `\t` 后的快捷键说明
+    pub text: String,
+
+    /// 命令 ID，弹出子菜单的菜单项没有意义
+    pub id: u32,
+
+    /// 是否为分隔线
+    pub is_separator: bool,
+
+    /// 是否勾选
+    pub is_checked: bool,
+
+    /// 是否可用
+    pub is_enabled: bool,
+
+    /// 子菜单
+    pub children: Vec<MenuItem>,
+}
+
+impl MenuItem {
+    /// 去掉助记符和快捷键说明后的文本，如 `导出(&E)...\tCtrl+E` 为 `导出...`
+    pub fn label(&self) -> String {
+        label::normalize(&self.text)
+    }
+
+    /// 是否弹出子菜单
+    pub fn is_submenu(&self) -> bool {
+        !self.children.is_empty()
+    }
+}
+
+/// 窗口的菜单栏
+///
+/// 读取时会递归读取所有子菜单。部分程序在子菜单弹出时（`WM_INITMENUPOPUP`）才填充菜单项，
+/// 此时读取到的子菜单可能为空。
+///
+/// ```no_run
+/// use winpoke::menu::Menu;
+/// use winpoke::prelude::*;
+///
+/// let regedit = WindowInfo::find_by_class_name("RegEdit_RegEdit")?.remove(0);
+/// let menu = Menu::of(&regedit)?;
+/// println!("{menu}");
+///
+/// menu.invoke("查看 > 刷新")?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Menu {
+    hwnd: HWND,
+
+    /// 菜单栏中的顶级菜单
+    pub items: Vec<MenuItem>,
+}
+
+impl Menu {
+    /// 读取窗口的菜单栏，窗口没有菜单时返回 [`Error::MenuNotFound`]
+    pub fn of(window: &WindowInfo) -> Result<Self> {
+        let menu = unsafe { GetMenu(window.hwnd) };
+        if menu.is_invalid() {
+            return Err(Error::MenuNotFound);
+        }
+
+        Ok(Self {
+            hwnd: window.hwnd,
+            items: read_menu(menu)?,
+        })
+    }
+
+    /// 按路径查找菜单项，如 `"查看 > 刷新"`
+    ///
+    /// 各级以 `>` 分隔，比较时忽略 `&` 助记符、快捷键说明和大小写。
+    pub fn find(&self, path: impl AsRef<str>) -> Result<&MenuItem> {
+        find_item(&self.items, path.as_ref())
+    }
+
+    /// 菜单项的命令 ID
+    pub fn command_id(&self, path: impl AsRef<str>) -> Result<u32> {
+        let path = path.as_ref();
+        let item = self.find(path)?;
+
+        if item.is_submenu() || item.is_separator {
+            return Err(Error::MenuItemNotFound(path.to_string()));
+        }
+
+        Ok(item.id)
+    }
+
+    /// 通过 `WM_COMMAND` 执行菜单项
+    ///
+    /// 消息是投递的，菜单命令弹出对话框时不会阻塞。不检查菜单项是否可用。
+    pub fn invoke(&self, path: impl AsRef<str>) -> Result<()> {
+        let id = self.command_id(path)?;
+
+        // 来自菜单的 WM_COMMAND：高位为 0，lParam 为 0
+        post_message(self.hwnd, WM_COMMAND, WPARAM(id as u16 as usize), LPARAM(0))
+    }
+}
+
+impl Display for Menu {
+    /// 以缩进的树形输出菜单，每行为菜单项文本和命令 ID
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn write_items(
+            f: &mut std::fmt::Formatter<'_>,
+            items: &[MenuItem],
+            depth: usize,
+        ) -> std::fmt::Result {
+            for item in items {
+                let indent = "  ".repeat(depth);
+
+                if item.is_separator {
+                    writeln!(f, "{indent}----")?;
+                } else if item.is_submenu() {
+                    writeln!(f, "{indent}{}", item.label())?;
+                    write_items(f, &item.children, depth + 1)?;
+                } else {
+                    let mut flags = String::new();
+                    if item.is_checked {
+                        flags.push_str(" [√]");
+                    }
+                    if !item.is_enabled {
+                        flags.push_str(" [禁用]");
+                    }
+                    writeln!(f, "{indent}{} (0x{:X}){flags}", item.label(), item.id)?;
+                }
+            }
+
+            Ok(())
+        }
+
+        write_items(f, &self.items, 0)
+    }
+}
+
+/// 递归读取菜单项
+fn read_menu(menu: HMENU) -> Result<Vec<MenuItem>> {
+    let count = unsafe { GetMenuItemCount(Some(menu)) };
+    if count < 0 {
+        return Err(windows::core::Error::from_win32().into());
+    }
+
+    (0..count as u32)
+        .map(|position| read_menu_item(menu, position))
+        .collect()
+}
+
+fn read_menu_item(menu: HMENU, position: u32) -> Result<MenuItem> {
+    let mut info = MENUITEMINFOW {
+        cbSize: size_of::<MENUITEMINFOW>() as u32,
+        fMask: MIIM_FTYPE | MIIM_ID | MIIM_STATE | MIIM_SUBMENU | MIIM_STRING,
+        ..Default::default()
+    };
+
+    // 第一次调用获取文本长度（不含结尾的 NUL）
+    unsafe { GetMenuItemInfoW(menu, position, true, &mut info) }?;
+
+    let mut buffer = vec![0u16; info.cch as usize + 1];
+    info.dwTypeData = PWSTR(buffer.as_mut_ptr());
+    info.cch = buffer.len() as u32;
+    unsafe { GetMenuItemInfoW(menu, position, true, &mut info) }?;
+
+    let text = String::from_utf16_lossy(&buffer[..(info.cch as usize).min(buffer.len())]);
+    let children = if info.hSubMenu.is_invalid() {
+        Vec::new()
+    } else {
+        read_menu(info.hSubMenu)?
+    };
+
+    Ok(MenuItem {
+        text,
+        id: info.wID,
+        is_separator: info.fType.0 & MFT_SEPARATOR.0 != 0,
+        is_checked: info.fState.0 & MFS_CHECKED.0 != 0,
+        // MFS_DISABLED 与 MFS_GRAYED 的值相同，包含了 MF_DISABLED 和 MF_GRAYED 两位
+        is_enabled: info.fState.0 & MFS_DISABLED.0 == 0,
+        children,
+    })
+}
+
+/// 按路径在菜单项中查找
+pub(crate) fn find_item<'a>(items: &'a [MenuItem], path: &str) -> Result<&'a MenuItem> {
+    let not_found = || Error::MenuItemNotFound(path.to_string());
+
+    let mut current = items;
+    let mut found = None;
+    for segment in path.split(PATH_SEPARATOR).map(str::trim) {
+        if segment.is_empty() {
+            return Err(not_found());
+        }
+
+        let item = current
+            .iter()
+            .find(|item| !item.is_separator && label::matches(&item.text, segment))
+            .ok_or_else(not_found)?;
+
+        current = &item.children;
+        found = Some(item);
+    }
+
+    found.ok_or_else(not_found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(text: &str, id: u32) -> MenuItem {
+        MenuItem {
+            text: text.to_string(),
+            id,
+            is_enabled: true,
+            ..Default::default()
+        }
+    }
+
+    fn submenu(text: &str, children: Vec<MenuItem>) -> MenuItem {
+        MenuItem {
+            children,
+            ..item(text, 0)
+        }
+    }
+
+    fn sample() -> Vec<MenuItem> {
+        vec![
+            submenu(
+                "文件(&F)",
+                vec![
+                    item("导入(&I)...", 0x101),
+                    item("导出(&E)...", 0x102),
+                    MenuItem {
+                        is_separator: true,
+                        ..Default::default()
+                    },
+                    item("退出(&X)", 0x103),
+                ],
+            ),
+            submenu(
+                "&View",
+                vec![
+                    item("&Refresh\tF5", 0x10288),
+                    submenu("&Sort", vec![item("By &Name", 0x201)]),
+                ],
+            ),
+        ]
+    }
+
+    #[test]
+    fn find_by_path() {
+        let items = sample();
+
+        assert_eq!(find_item(&items, "View > Refresh").unwrap().id, 0x10288);
+        assert_eq!(find_item(&items, "文件 > 导出").unwrap().id, 0x102);
+        assert_eq!(find_item(&items, "文件>导出...").unwrap().id, 0x102);
+        assert_eq!(
+            find_item(&items, "view > sort > by name").unwrap().id,
+            0x201
+        );
+        assert!(find_item(&items, "View").unwrap().is_submenu());
+    }
+
+    #[test]
+    fn find_missing() {
+        let items = sample();
+
+        for path in ["", "View >", "View > Nope", "Nope", "View > Refresh > More"] {
+            let err = find_item(&items, path).unwrap_err();
+            assert!(
+                matches!(&err, Error::MenuItemNotFound(p) if p == path),
+                "{path}: {err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn display_tree() {
+        let menu = Menu {
+            hwnd: HWND::default(),
+            items: sample(),
+        };
+
+        assert_eq!(
+            menu.to_string(),
+            "文件\n  导入... (0x101)\n  导出... (0x102)\n  ----\n  退出 (0x103)\n\
+             View\n  Refresh (0x10288)\n  Sort\n    By Name (0x201)\n"
+        );
+    }
+
+    #[test]
+    fn test_regedit_menu() {
+        let regedit = WindowInfo::find_by_class_name("RegEdit_RegEdit")
+            .unwrap()
+            .remove(0);
+        let menu = Menu::of(&regedit).unwrap();
+        println!("{menu}");
+
+        assert!(!menu.items.is_empty());
+    }
+}
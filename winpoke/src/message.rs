@@ -0,0 +1,22 @@
+/// 发送到窗口的消息，`count` 为重复次数
+///
+/// 只描述消息本身，由 `WindowInfo::send_message_seq` 等方法发送。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Message {
+    pub msg: WindowMessage,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WindowMessage {
+    KeyDown(u32),
+    MouseMove(i32, i32),
+    Char(char),
+    Command(u32),
+}
+
+impl Default for WindowMessage {
+    fn default() -> Self {
+        WindowMessage::KeyDown(0)
+    }
+}
@@ -0,0 +1,47 @@
+//! 录制键盘和鼠标操作，生成可以回放的脚本
+//!
+//! [`Recorder`] 通过低级键盘、鼠标钩子记录输入，并在每次输入时跟踪前台窗口。
+//! 窗口在记录时就转换为选择器路径，而不是保存句柄，因此回放时可以重新定位窗口。
+//!
+//! 记录到的 [`InputEvent`] 由 [`coalesce`] 合并为 [`Action`]：连续输入的字符合并为
+//! 一段文本，退格会删除文本中最后一个字符，按住 Ctrl、Alt 或 Win 时的按键合并为组合键，
+//! 较长的停顿转换为 `sleep`。合并后的操作可以用 [`to_script`] 生成 `.poke` 脚本，
+//! 启用 `rpc` 特性时也可以用 [`to_json`] 生成 JSON。
+//!
+//! ```no_run
+//! # #[cfg(windows)] {
+//! use std::time::Duration;
+//! use winpoke::recorder::{Recorder, coalesce, to_script};
+//!
+//! let recorder = Recorder::start().unwrap();
+//! std::thread::sleep(Duration::from_secs(10));
+//! let events = recorder.stop();
+//!
+//! let actions = coalesce(&events, Some(Duration::from_secs(1)));
+//! print!("{}", to_script(&actions));
+//! # }
+//! ```
+//!
+//! 生成的脚本在第一次用到窗口时以 `wait` 等待它出现，窗口名取自类名：
+//!
+//! ```text
+//! wait notepad = Notepad[caption="无标题 - 记事本"]
+//! wait edit = Notepad[caption="无标题 - 记事本"] > Edit
+//! focus notepad
+//! type edit "hello world"
+//! key edit Enter
+//! sleep 1500ms
+//! key edit Ctrl+S
+//! ```
+
+mod coalesce;
+#[cfg(windows)]
+mod hook;
+mod output;
+
+pub use coalesce::{Action, InputEvent, InputKind, coalesce};
+#[cfg(windows)]
+pub use hook::Recorder;
+#[cfg(feature = "rpc")]
+pub use output::to_json;
+pub use output::to_script;
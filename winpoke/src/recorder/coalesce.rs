@@ -0,0 +1,410 @@
+use std::time::Duration;
+
+use crate::selector::SelectorPath;
+
+const VK_BACK: u32 = 0x08;
+const VK_SHIFT: u32 = 0x10;
+const VK_CONTROL: u32 = 0x11;
+const VK_MENU: u32 = 0x12;
+const VK_LWIN: u32 = 0x5B;
+
+/// 停顿时长按此精度向下取整
+const PAUSE_PRECISION: Duration = Duration::from_millis(100);
+
+/// 录制到的一次输入
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputEvent {
+    /// 距开始录制的时间
+    pub time: Duration,
+
+    pub kind: InputKind,
+}
+
+/// 输入类型，窗口均以选择器路径表示
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputKind {
+    /// 前台窗口已切换
+    Foreground(SelectorPath),
+
+    /// 按下按键，`char` 为按键产生的字符，`target` 为拥有键盘焦点的控件
+    KeyDown {
+        target: SelectorPath,
+        vk: u32,
+        char: Option<char>,
+    },
+
+    /// 松开按键
+    KeyUp { vk: u32 },
+
+    /// 单击鼠标左键，坐标相对于 `target` 的工作区
+    Click {
+        target: SelectorPath,
+        x: i32,
+        y: i32,
+    },
+}
+
+/// 合并后的操作，对应脚本中的一条命令
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    /// 激活窗口
+    Focus(SelectorPath),
+
+    /// 输入文本
+    Type { target: SelectorPath, text: String },
+
+    /// 按键或组合键
+    Key { target: SelectorPath, vks: Vec<u32> },
+
+    /// 单击工作区坐标
+    Click {
+        target: SelectorPath,
+        x: i32,
+        y: i32,
+    },
+
+    /// 停顿
+    Sleep(Duration),
+}
+
+/// 把输入事件合并为操作
+///
+/// 相邻事件的间隔不小于 `min_pause` 时插入 [`Action::Sleep`]，为 `None` 时不记录停顿。
+pub fn coalesce(events: &[InputEvent], min_pause: Option<Duration>) -> Vec<Action> {
+    let mut coalescer = Coalescer::default();
+
+    let mut last_time = None;
+    for event in events {
+        if let (Some(last), Some(min_pause)) = (last_time, min_pause) {
+            let gap = event.time.saturating_sub(last);
+            if gap >= min_pause {
+                coalescer.flush();
+                coalescer.actions.push(Action::Sleep(round_down(gap)));
+            }
+        }
+        last_time = Some(event.time);
+
+        coalescer.push(&event.kind);
+    }
+    coalescer.flush();
+
+    coalescer.actions
+}
+
+#[derive(Default)]
+struct Coalescer {
+    actions: Vec<Action>,
+
+    /// 正在输入的文本及其目标控件
+    text: Option<(SelectorPath, String)>,
+
+    /// 按住的修饰键，按按下的顺序排列
+    modifiers: Vec<u32>,
+
+    foreground: Option<SelectorPath>,
+}
+
+impl Coalescer {
+    fn push(&mut self, kind: &InputKind) {
+        match kind {
+            InputKind::Foreground(path) => {
+                if self.foreground.as_ref() != Some(path) {
+                    self.flush();
+                    self.actions.push(Action::Focus(path.clone()));
+                    self.foreground = Some(path.clone());
+                }
+            }
+            InputKind::KeyDown { target, vk, char } => self.key_down(target, *vk, *char),
+            InputKind::KeyUp { vk } => {
+                let vk = modifier(*vk).unwrap_or(*vk);
+                self.modifiers.retain(|&held| held != vk);
+            }
+            InputKind::Click { target, x, y } => {
+                self.flush();
+                self.actions.push(Action::Click {
+                    target: target.clone(),
+                    x: *x,
+                    y: *y,
+                });
+            }
+        }
+    }
+
+    fn key_down(&mut self, target: &SelectorPath, vk: u32, char: Option<char>) {
+        if let Some(vk) = modifier(vk) {
+            if !self.modifiers.contains(&vk) {
+                self.modifiers.push(vk);
+            }
+            return;
+        }
+
+        let held = |vk| self.modifiers.contains(&vk);
+        let char = char.filter(|c| !c.is_control());
+        // AltGr 表现为同时按住 Ctrl 和 Alt，产生字符时仍当作文本
+        let altgr = held(VK_CONTROL) && held(VK_MENU) && char.is_some();
+        let chord = (held(VK_CONTROL) || held(VK_MENU) || held(VK_LWIN)) && !altgr;
+
+        if !chord {
+            if let Some(c) = char {
+                match &mut self.text {
+                    Some((path, text)) if path == target => text.push(c),
+                    _ => {
+                        self.flush();
+                        self.text = Some((target.clone(), c.to_string()));
+                    }
+                }
+                return;
+            }
+
+            if vk == VK_BACK
+                && let Some((path, text)) = &mut self.text
+                && path == target
+                && text.pop().is_some()
+            {
+                return;
+            }
+        }
+
+        self.flush();
+        let vks = self.modifiers.iter().copied().chain([vk]).collect();
+        self.actions.push(Action::Key {
+            target: target.clone(),
+            vks,
+        });
+    }
+
+    /// 结束正在输入的文本
+    fn flush(&mut self) {
+        if let Some((target, text)) = self.text.take()
+            && !text.is_empty()
+        {
+            self.actions.push(Action::Type { target, text });
+        }
+    }
+}
+
+/// 修饰键统一为不分左右的键码，不是修饰键时返回 `None`
+fn modifier(vk: u32) -> Option<u32> {
+    match vk {
+        VK_SHIFT | 0xA0 | 0xA1 => Some(VK_SHIFT),
+        VK_CONTROL | 0xA2 | 0xA3 => Some(VK_CONTROL),
+        VK_MENU | 0xA4 | 0xA5 => Some(VK_MENU),
+        VK_LWIN | 0x5C => Some(VK_LWIN),
+        _ => None,
+    }
+}
+
+fn round_down(gap: Duration) -> Duration {
+    let precision = PAUSE_PRECISION.as_millis();
+
+    Duration::from_millis((gap.as_millis() / precision * precision) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VK_LSHIFT: u32 = 0xA0;
+    const VK_LCONTROL: u32 = 0xA2;
+    const VK_RMENU: u32 = 0xA5;
+
+    fn path(s: &str) -> SelectorPath {
+        s.parse().unwrap()
+    }
+
+    /// 按给定的事件生成间隔 10ms 的事件流
+    fn events(kinds: Vec<InputKind>) -> Vec<InputEvent> {
+        kinds
+            .into_iter()
+            .enumerate()
+            .map(|(index, kind)| InputEvent {
+                time: Duration::from_millis(index as u64 * 10),
+                kind,
+            })
+            .collect()
+    }
+
+    fn down(target: &str, vk: u32, char: Option<char>) -> InputKind {
+        InputKind::KeyDown {
+            target: path(target),
+            vk,
+            char,
+        }
+    }
+
+    fn up(vk: u32) -> InputKind {
+        InputKind::KeyUp { vk }
+    }
+
+    /// 按下并松开一个产生字符的按键
+    fn typed(target: &str, c: char) -> [InputKind; 2] {
+        let vk = c.to_ascii_uppercase() as u32;
+        [down(target, vk, Some(c)), up(vk)]
+    }
+
+    #[test]
+    fn characters_become_text() {
+        let mut kinds = vec![InputKind::Foreground(path("Notepad"))];
+        kinds.extend("hi!".chars().flat_map(|c| typed("Notepad > Edit", c)));
+        kinds.extend([down("Notepad > Edit", 0x0D, Some('\r')), up(0x0D)]);
+        kinds.extend(typed("Notepad > Edit", 'x'));
+        kinds.extend([down("Notepad > Edit", VK_BACK, Some('\x08')), up(VK_BACK)]);
+        kinds.extend(typed("Notepad > Edit", 'y'));
+
+        assert_eq!(
+            coalesce(&events(kinds), None),
+            [
+                Action::Focus(path("Notepad")),
+                Action::Type {
+                    target: path("Notepad > Edit"),
+                    text: "hi!".to_string(),
+                },
+                Action::Key {
+                    target: path("Notepad > Edit"),
+                    vks: vec![0x0D],
+                },
+                Action::Type {
+                    target: path("Notepad > Edit"),
+                    text: "y".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn backspace_without_text_is_a_key() {
+        let kinds = vec![down("Edit", VK_BACK, Some('\x08')), up(VK_BACK)];
+
+        assert_eq!(
+            coalesce(&events(kinds), None),
+            [Action::Key {
+                target: path("Edit"),
+                vks: vec![VK_BACK],
+            }]
+        );
+    }
+
+    #[test]
+    fn modifiers_make_chords() {
+        let kinds = vec![
+            // Shift 只改变字符
+            down("Edit", VK_LSHIFT, None),
+            down("Edit", 0x41, Some('A')),
+            up(0x41),
+            up(VK_LSHIFT),
+            down("Edit", VK_LCONTROL, None),
+            down("Edit", VK_LSHIFT, None),
+            down("Edit", 0x53, Some('\x13')),
+            up(0x53),
+            up(VK_LSHIFT),
+            up(VK_LCONTROL),
+            // AltGr 产生的字符
+            down("Edit", VK_LCONTROL, None),
+            down("Edit", VK_RMENU, None),
+            down("Edit", 0x51, Some('@')),
+            up(0x51),
+            up(VK_RMENU),
+            up(VK_LCONTROL),
+            down("Edit", VK_LSHIFT, None),
+            down("Edit", 0x09, Some('\t')),
+        ];
+
+        assert_eq!(
+            coalesce(&events(kinds), None),
+            [
+                Action::Type {
+                    target: path("Edit"),
+                    text: "A".to_string(),
+                },
+                Action::Key {
+                    target: path("Edit"),
+                    vks: vec![VK_CONTROL, VK_SHIFT, 0x53],
+                },
+                Action::Type {
+                    target: path("Edit"),
+                    text: "@".to_string(),
+                },
+                Action::Key {
+                    target: path("Edit"),
+                    vks: vec![VK_SHIFT, 0x09],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn targets_and_clicks_split_text() {
+        let mut kinds = Vec::new();
+        kinds.extend(typed("Dialog > Edit", 'a'));
+        kinds.push(InputKind::Foreground(path("Dialog")));
+        kinds.extend(typed("Dialog > Edit", 'b'));
+        kinds.extend(typed("Dialog > ComboBox", 'c'));
+        kinds.push(InputKind::Click {
+            target: path("Dialog > Button"),
+            x: 5,
+            y: 8,
+        });
+        kinds.extend(typed("Dialog > ComboBox", 'd'));
+        // 前台窗口没有变化时不重复激活
+        kinds.push(InputKind::Foreground(path("Dialog")));
+
+        assert_eq!(
+            coalesce(&events(kinds), None),
+            [
+                Action::Type {
+                    target: path("Dialog > Edit"),
+                    text: "a".to_string(),
+                },
+                Action::Focus(path("Dialog")),
+                Action::Type {
+                    target: path("Dialog > Edit"),
+                    text: "b".to_string(),
+                },
+                Action::Type {
+                    target: path("Dialog > ComboBox"),
+                    text: "c".to_string(),
+                },
+                Action::Click {
+                    target: path("Dialog > Button"),
+                    x: 5,
+                    y: 8,
+                },
+                Action::Type {
+                    target: path("Dialog > ComboBox"),
+                    text: "d".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn pauses_become_sleeps() {
+        let event = |ms, kind| InputEvent {
+            time: Duration::from_millis(ms),
+            kind,
+        };
+        let stream = [
+            event(0, down("Edit", 0x41, Some('a'))),
+            event(100, up(0x41)),
+            event(1780, down("Edit", 0x42, Some('b'))),
+            event(1800, up(0x42)),
+            event(2000, down("Edit", 0x43, Some('c'))),
+        ];
+
+        assert_eq!(
+            coalesce(&stream, Some(Duration::from_secs(1))),
+            [
+                Action::Type {
+                    target: path("Edit"),
+                    text: "a".to_string(),
+                },
+                Action::Sleep(Duration::from_millis(1600)),
+                Action::Type {
+                    target: path("Edit"),
+                    text: "bc".to_string(),
+                },
+            ]
+        );
+        assert_eq!(coalesce(&stream, None).len(), 1);
+    }
+}
@@ -0,0 +1,329 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, POINT, WPARAM};
+use windows::Win32::Graphics::Gdi::ScreenToClient;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    GetAsyncKeyState, GetKeyState, GetKeyboardLayout, ToUnicodeEx, VK_CAPITAL, VK_CONTROL, VK_MENU,
+    VK_SHIFT,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, GA_ROOT, GUITHREADINFO, GetAncestor, GetForegroundWindow, GetGUIThreadInfo,
+    GetWindowThreadProcessId, HC_ACTION, HHOOK, KBDLLHOOKSTRUCT, LLKHF_INJECTED, LLMHF_INJECTED,
+    MSG, MSLLHOOKSTRUCT, SetWindowsHookExW, UnhookWindowsHookEx, WH_KEYBOARD_LL, WH_MOUSE_LL,
+    WM_KEYDOWN, WM_KEYUP, WM_LBUTTONDOWN, WM_SYSKEYDOWN, WM_SYSKEYUP,
+};
+
+use crate::prelude::Result;
+use crate::recorder::{InputEvent, InputKind};
+use crate::selector::SelectorPath;
+use crate::window::WindowInfo;
+use crate::window::info::window_from_point;
+use crate::window::message_loop::MessageLoop;
+
+/// `ToUnicodeEx` 不修改键盘状态，避免打断目标程序中的死键输入
+const TO_UNICODE_NO_STATE_CHANGE: u32 = 0x4;
+
+/// 钩子回调中记录的原始输入，窗口以句柄表示
+enum RawInput {
+    Key {
+        time: Duration,
+        vk: u32,
+        down: bool,
+        char: Option<char>,
+        foreground: isize,
+        focus: isize,
+    },
+    /// 单击的屏幕坐标，所在的窗口由转换线程查找
+    Click { time: Duration, point: POINT },
+}
+
+/// 钩子线程的上下文
+///
+/// 低级钩子的回调在注册钩子的线程的消息循环中执行，没有用户数据参数，
+/// 因此上下文保存在该线程的线程局部变量中。
+struct HookContext {
+    start: Instant,
+    sender: Sender<RawInput>,
+
+    /// 钩子线程收不到键盘输入，`GetKeyState` 读到的大写锁定状态不会更新，需要自行跟踪
+    caps_lock: bool,
+}
+
+thread_local! {
+    static HOOK_CONTEXT: RefCell<Option<HookContext>> = const { RefCell::new(None) };
+}
+
+/// 在钩子线程上注销钩子并清除上下文
+struct HookGuard(Vec<HHOOK>);
+
+impl Drop for HookGuard {
+    fn drop(&mut self) {
+        for hook in &self.0 {
+            let _ = unsafe { UnhookWindowsHookEx(*hook) };
+        }
+
+        HOOK_CONTEXT.with_borrow_mut(|context| *context = None);
+    }
+}
+
+/// 低级键盘钩子回调（unsafe：C调用约定）
+unsafe extern "system" fn keyboard_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code == HC_ACTION as i32 {
+        let info = unsafe { &*(lparam.0 as *const KBDLLHOOKSTRUCT) };
+        let down = match wparam.0 as u32 {
+            WM_KEYDOWN | WM_SYSKEYDOWN => Some(true),
+            WM_KEYUP | WM_SYSKEYUP => Some(false),
+            _ => None,
+        };
+
+        // 其它程序（包括回放脚本）注入的输入不记录
+        if let Some(down) = down
+            && (info.flags & LLKHF_INJECTED).0 == 0
+        {
+            HOOK_CONTEXT.with_borrow_mut(|context| {
+                if let Some(context) = context {
+                    context.key(info, down);
+                }
+            });
+        }
+    }
+
+    unsafe { CallNextHookEx(None, code, wparam, lparam) }
+}
+
+/// 低级鼠标钩子回调（unsafe：C调用约定）
+unsafe extern "system" fn mouse_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code == HC_ACTION as i32 && wparam.0 as u32 == WM_LBUTTONDOWN {
+        let info = unsafe { &*(lparam.0 as *const MSLLHOOKSTRUCT) };
+
+        if info.flags & LLMHF_INJECTED == 0 {
+            HOOK_CONTEXT.with_borrow_mut(|context| {
+                if let Some(context) = context {
+                    context.click(info.pt);
+                }
+            });
+        }
+    }
+
+    unsafe { CallNextHookEx(None, code, wparam, lparam) }
+}
+
+impl HookContext {
+    fn key(&mut self, info: &KBDLLHOOKSTRUCT, down: bool) {
+        let foreground = unsafe { GetForegroundWindow() };
+        let tid = unsafe { GetWindowThreadProcessId(foreground, None) };
+
+        let mut gui = GUITHREADINFO {
+            cbSize: size_of::<GUITHREADINFO>() as u32,
+            ..Default::default()
+        };
+        let focus = match unsafe { GetGUIThreadInfo(tid, &mut gui) } {
+            Ok(()) if !gui.hwndFocus.is_invalid() => gui.hwndFocus,
+            _ => foreground,
+        };
+
+        if down && info.vkCode == VK_CAPITAL.0 as u32 {
+            self.caps_lock = !self.caps_lock;
+        }
+        let char = match down {
+            true => self.translate(info, tid),
+            false => None,
+        };
+
+        let _ = self.sender.send(RawInput::Key {
+            time: self.start.elapsed(),
+            vk: info.vkCode,
+            down,
+            char,
+            foreground: foreground.0 as isize,
+            focus: focus.0 as isize,
+        });
+    }
+
+    /// 按前台线程的键盘布局把按键转换为字符
+    fn translate(&self, info: &KBDLLHOOKSTRUCT, tid: u32) -> Option<char> {
+        let mut state = [0u8; 256];
+        for vk in [VK_SHIFT, VK_CONTROL, VK_MENU] {
+            if unsafe { GetAsyncKeyState(vk.0 as i32) } < 0 {
+                state[vk.0 as usize] = 0x80;
+            }
+        }
+        if self.caps_lock {
+            state[VK_CAPITAL.0 as usize] = 0x01;
+        }
+
+        let mut buffer = [0u16; 8];
+        let len = unsafe {
+            ToUnicodeEx(
+                info.vkCode,
+                info.scanCode,
+                &state,
+                &mut buffer,
+                TO_UNICODE_NO_STATE_CHANGE,
+                Some(GetKeyboardLayout(tid)),
+            )
+        };
+
+        // 死键返回负数，多个字符的组合不当作普通字符
+        match len {
+            1 => char::from_u32(buffer[0] as u32),
+            _ => None,
+        }
+    }
+
+    fn click(&mut self, point: POINT) {
+        let _ = self.sender.send(RawInput::Click {
+            time: self.start.elapsed(),
+            point,
+        });
+    }
+}
+
+/// 单击位置的窗口、所在的顶层窗口和工作区坐标
+fn click_target(point: POINT) -> Option<(HWND, HWND, POINT)> {
+    let hwnd = window_from_point(point.x, point.y).ok()?;
+
+    let mut client = point;
+    if !unsafe { ScreenToClient(hwnd, &mut client) }.as_bool() {
+        return None;
+    }
+
+    // 单击会激活所在的顶层窗口
+    let foreground = unsafe { GetAncestor(hwnd, GA_ROOT) };
+    Some((hwnd, foreground, client))
+}
+
+/// 把原始输入中的窗口句柄转换为选择器路径，并查找单击位置的窗口
+///
+/// 同一窗口只在第一次出现时读取路径，之后标题变化（如记事本标题加上 `*`）
+/// 也沿用同一路径，使生成的脚本中同一窗口对应同一个变量。
+fn convert(receiver: Receiver<RawInput>) -> Vec<InputEvent> {
+    let mut paths: HashMap<isize, Option<SelectorPath>> = HashMap::new();
+    let mut path = |hwnd: isize| {
+        paths
+            .entry(hwnd)
+            .or_insert_with(|| {
+                WindowInfo::from_hwnd(HWND(hwnd as _))
+                    .and_then(|window| window.selector_path())
+                    .ok()
+            })
+            .clone()
+    };
+
+    let mut events = Vec::new();
+    let mut last_foreground = None;
+    for raw in receiver {
+        let (time, foreground, kind) = match raw {
+            RawInput::Key {
+                time,
+                vk,
+                down: false,
+                ..
+            } => {
+                events.push(InputEvent {
+                    time,
+                    kind: InputKind::KeyUp { vk },
+                });
+                continue;
+            }
+            RawInput::Key {
+                time,
+                vk,
+                char,
+                foreground,
+                focus,
+                ..
+            } => {
+                let Some(target) = path(focus) else {
+                    continue;
+                };
+                (time, foreground, InputKind::KeyDown { target, vk, char })
+            }
+            RawInput::Click { time, point } => {
+                let Some((hwnd, foreground, client)) = click_target(point) else {
+                    continue;
+                };
+                let Some(target) = path(hwnd.0 as isize) else {
+                    continue;
+                };
+                let kind = InputKind::Click {
+                    target,
+                    x: client.x,
+                    y: client.y,
+                };
+                (time, foreground.0 as isize, kind)
+            }
+        };
+
+        if last_foreground != Some(foreground) {
+            last_foreground = Some(foreground);
+            if let Some(path) = path(foreground) {
+                events.push(InputEvent {
+                    time,
+                    kind: InputKind::Foreground(path),
+                });
+            }
+        }
+        events.push(InputEvent { time, kind });
+    }
+
+    events
+}
+
+/// 输入录制器
+///
+/// 在后台线程中安装低级键盘、鼠标钩子，另一个线程查找单击位置的窗口并把窗口句柄
+/// 转换为选择器路径，避免拖慢钩子回调。只记录鼠标左键单击，其它程序注入的输入会被忽略。
+pub struct Recorder {
+    message_loop: MessageLoop,
+    worker: JoinHandle<Vec<InputEvent>>,
+}
+
+impl Recorder {
+    /// 开始录制
+    pub fn start() -> Result<Self> {
+        let (sender, receiver) = mpsc::channel();
+        let worker = std::thread::spawn(move || convert(receiver));
+
+        let message_loop = MessageLoop::spawn(move || {
+            let caps_lock = unsafe { GetKeyState(VK_CAPITAL.0 as i32) } & 1 != 0;
+            HOOK_CONTEXT.with_borrow_mut(|context| {
+                *context = Some(HookContext {
+                    start: Instant::now(),
+                    sender,
+                    caps_lock,
+                })
+            });
+
+            let mut guard = HookGuard(Vec::new());
+            guard
+                .0
+                .push(unsafe { SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_proc), None, 0) }?);
+            guard
+                .0
+                .push(unsafe { SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_proc), None, 0) }?);
+
+            // 处理函数持有钩子，消息循环退出时随之注销
+            Ok(move |_: &MSG| {
+                let _ = &guard;
+            })
+        })?;
+
+        Ok(Self {
+            message_loop,
+            worker,
+        })
+    }
+
+    /// 停止录制，返回录制到的输入
+    pub fn stop(self) -> Vec<InputEvent> {
+        // 注销钩子后发送端随上下文释放，转换线程随之结束
+        drop(self.message_loop);
+
+        self.worker.join().unwrap_or_default()
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+
+use crate::keys::format_chord;
+use crate::recorder::Action;
+use crate::script::{Command, DEFAULT_WAIT_TIMEOUT, Script, Statement, Text};
+use crate::selector::SelectorPath;
+
+/// 生成 `.poke` 脚本
+///
+/// 每个窗口在第一次用到时以 `wait` 等待其出现，窗口名取自最后一级的类名，重名时加上序号。
+pub fn to_script(actions: &[Action]) -> Script {
+    let mut names = Names::default();
+    let mut commands = Vec::new();
+
+    for action in actions {
+        let mut target = |path: &SelectorPath| {
+            let (name, new) = names.get(path);
+            if new {
+                commands.push(Command::Wait {
+                    name: name.clone(),
+                    path: Text::literal(path.to_string()),
+                    timeout: DEFAULT_WAIT_TIMEOUT,
+                });
+            }
+            name
+        };
+
+        let command = match action {
+            Action::Focus(path) => Command::Focus {
+                target: target(path),
+            },
+            Action::Type { target: path, text } => Command::Type {
+                target: target(path),
+                text: Text::literal(text.as_str()),
+            },
+            Action::Key { target: path, vks } => Command::Key {
+                target: target(path),
+                chord: Text::literal(format_chord(vks)),
+            },
+            Action::Click { target: path, x, y } => Command::Click {
+                target: target(path),
+                point: Some((*x, *y)),
+            },
+            Action::Sleep(time) => Command::Sleep(*time),
+        };
+        commands.push(command);
+    }
+
+    Script {
+        statements: commands
+            .into_iter()
+            .enumerate()
+            .map(|(index, command)| Statement {
+                line: index + 1,
+                command,
+            })
+            .collect(),
+    }
+}
+
+/// 生成 JSON 数组，每个元素为一步操作
+///
+/// 连续发给同一窗口的文本和单个按键合并为一组 `messages`，格式与 JSON-RPC 的
+/// `send` 方法相同；组合键、单击和停顿各占一步：
+///
+/// ```json
+/// [
+///   { "focus": "Notepad" },
+///   { "target": "Notepad > Edit", "messages": [{ "type": "text", "text": "hi" }, { "type": "key", "key": "Enter" }] },
+///   { "target": "Notepad > Edit", "chord": "Ctrl+S" },
+///   { "target": "Notepad > Button", "click": { "x": 5, "y": 8 } },
+///   { "sleep_ms": 1500 }
+/// ]
+/// ```
+#[cfg(feature = "rpc")]
+pub fn to_json(actions: &[Action]) -> serde_json::Value {
+    use serde_json::{Value, json};
+
+    use crate::keys::name_from_vk;
+
+    let mut steps: Vec<Value> = Vec::new();
+    // 正在合并的 messages 所属的窗口
+    let mut sending: Option<&SelectorPath> = None;
+
+    for action in actions {
+        let message = match action {
+            Action::Type { target, text } => {
+                Some((target, json!({ "type": "text", "text": text })))
+            }
+            Action::Key { target, vks } if vks.len() == 1 => {
+                let key = match name_from_vk(vks[0]) {
+                    Some(name) => json!(name),
+                    None => json!(vks[0]),
+                };
+                Some((target, json!({ "type": "key", "key": key })))
+            }
+            _ => None,
+        };
+
+        if let Some((target, message)) = message {
+            if sending == Some(target)
+                && let Some(Value::Array(messages)) =
+                    steps.last_mut().and_then(|step| step.get_mut("messages"))
+            {
+                messages.push(message);
+            } else {
+                steps.push(json!({ "target": target.to_string(), "messages": [message] }));
+                sending = Some(target);
+            }
+            continue;
+        }
+
+        sending = None;
+        steps.push(match action {
+            Action::Focus(path) => json!({ "focus": path.to_string() }),
+            Action::Key { target, vks } => {
+                json!({ "target": target.to_string(), "chord": format_chord(vks) })
+            }
+            Action::Click { target, x, y } => {
+                json!({ "target": target.to_string(), "click": { "x": x, "y": y } })
+            }
+            Action::Sleep(time) => json!({ "sleep_ms": time.as_millis() as u64 }),
+            Action::Type { .. } => unreachable!(),
+        });
+    }
+
+    Value::Array(steps)
+}
+
+/// 为窗口分配脚本中的变量名
+#[derive(Default)]
+struct Names {
+    names: HashMap<SelectorPath, String>,
+    counts: HashMap<String, usize>,
+}
+
+impl Names {
+    /// 窗口的变量名，第一次出现时返回 `true`
+    fn get(&mut self, path: &SelectorPath) -> (String, bool) {
+        if let Some(name) = self.names.get(path) {
+            return (name.clone(), false);
+        }
+
+        let base = base_name(path);
+        let count = self.counts.entry(base.clone()).or_default();
+        *count += 1;
+        let name = match *count {
+            1 => base,
+            n => format!("{base}{n}"),
+        };
+        self.names.insert(path.clone(), name.clone());
+
+        (name, true)
+    }
+}
+
+/// 最后一级类名中的字母、数字和下划线，转为小写
+fn base_name(path: &SelectorPath) -> String {
+    let name: String = path
+        .0
+        .last()
+        .and_then(|selector| selector.class_name.as_deref())
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '_')
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    match name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        true => name,
+        false => format!("window{name}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn path(s: &str) -> SelectorPath {
+        s.parse().unwrap()
+    }
+
+    fn actions() -> Vec<Action> {
+        let notepad = path(r#"Notepad[caption="无标题 - 记事本"]"#);
+        let edit = path(r#"Notepad[caption="无标题 - 记事本"] > Edit"#);
+        let dialog = path(r##"#32770[caption="另存为"] > Edit"##);
+
+        vec![
+            Action::Focus(notepad),
+            Action::Type {
+                target: edit.clone(),
+                text: "say \"hi\"".to_string(),
+            },
+            Action::Key {
+                target: edit.clone(),
+                vks: vec![0x0D],
+            },
+            Action::Sleep(Duration::from_millis(1500)),
+            Action::Key {
+                target: edit,
+                vks: vec![0x11, 0x53],
+            },
+            Action::Type {
+                target: dialog.clone(),
+                text: "a.txt".to_string(),
+            },
+            Action::Click {
+                target: path("Other > Edit"),
+                x: 5,
+                y: -8,
+            },
+        ]
+    }
+
+    #[test]
+    fn script() {
+        let script = to_script(&actions());
+
+        assert_eq!(
+            script.to_string(),
+            r##"wait notepad = Notepad[caption="无标题 - 记事本"]
+focus notepad
+wait edit = Notepad[caption="无标题 - 记事本"] > Edit
+type edit "say \"hi\""
+key edit Enter
+sleep 1500ms
+key edit Ctrl+S
+wait edit2 = #32770[caption="另存为"] > Edit
+type edit2 a.txt
+wait edit3 = Other > Edit
+click edit3 5 -8
+"##
+        );
+        assert_eq!(Script::parse(&script.to_string()).unwrap(), script);
+    }
+
+    #[test]
+    fn names() {
+        let mut names = Names::default();
+
+        assert_eq!(
+            names.get(&path("#32770")),
+            ("window32770".to_string(), true)
+        );
+        assert_eq!(names.get(&path("*")), ("window".to_string(), true));
+        assert_eq!(
+            names.get(&path("#32770")),
+            ("window32770".to_string(), false)
+        );
+        assert_eq!(
+            names.get(&path("A > SysListView32")),
+            ("syslistview32".to_string(), true)
+        );
+    }
+
+    #[cfg(feature = "rpc")]
+    #[test]
+    fn json() {
+        use serde_json::json;
+
+        assert_eq!(
+            to_json(&actions()),
+            json!([
+                { "focus": r#"Notepad[caption="无标题 - 记事本"]"# },
+                {
+                    "target": r#"Notepad[caption="无标题 - 记事本"] > Edit"#,
+                    "messages": [
+                        { "type": "text", "text": "say \"hi\"" },
+                        { "type": "key", "key": "Enter" },
+                    ],
+                },
+                { "sleep_ms": 1500 },
+                { "target": r#"Notepad[caption="无标题 - 记事本"] > Edit"#, "chord": "Ctrl+S" },
+                {
+                    "target": r##"#32770[caption="另存为"] > Edit"##,
+                    "messages": [{ "type": "text", "text": "a.txt" }],
+                },
+                { "target": "Other > Edit", "click": { "x": 5, "y": -8 } },
+            ])
+        );
+    }
+}
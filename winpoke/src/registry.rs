@@ -0,0 +1,315 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::error::Error;
+
+/// 注册表键名的最大长度（字符）
+const MAX_KEY_NAME_LEN: usize = 255;
+
+/// 注册表编辑器地址栏中根键之前的“计算机”节点
+const COMPUTER_NODES: &[&str] = &["Computer", "计算机", "電腦"];
+
+/// 根键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegistryRoot {
+    ClassesRoot,
+    CurrentUser,
+    LocalMachine,
+    Users,
+    CurrentConfig,
+}
+
+impl RegistryRoot {
+    pub const ALL: [RegistryRoot; 5] = [
+        RegistryRoot::ClassesRoot,
+        RegistryRoot::CurrentUser,
+        RegistryRoot::LocalMachine,
+        RegistryRoot::Users,
+        RegistryRoot::CurrentConfig,
+    ];
+
+    /// 完整名称，如 `HKEY_LOCAL_MACHINE`
+    pub fn name(self) -> &'static str {
+        match self {
+            RegistryRoot::ClassesRoot => "HKEY_CLASSES_ROOT",
+            RegistryRoot::CurrentUser => "HKEY_CURRENT_USER",
+            RegistryRoot::LocalMachine => "HKEY_LOCAL_MACHINE",
+            RegistryRoot::Users => "HKEY_USERS",
+            RegistryRoot::CurrentConfig => "HKEY_CURRENT_CONFIG",
+        }
+    }
+
+    /// 缩写，如 `HKLM`
+    pub fn short_name(self) -> &'static str {
+        match self {
+            RegistryRoot::ClassesRoot => "HKCR",
+            RegistryRoot::CurrentUser => "HKCU",
+            RegistryRoot::LocalMachine => "HKLM",
+            RegistryRoot::Users => "HKU",
+            RegistryRoot::CurrentConfig => "HKCC",
+        }
+    }
+
+    /// 按完整名称或缩写（不区分大小写）查找根键
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|root| {
+            root.name().eq_ignore_ascii_case(name) || root.short_name().eq_ignore_ascii_case(name)
+        })
+    }
+}
+
+impl Display for RegistryRoot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// 规范化的注册表键路径
+///
+/// 解析时接受常见的各种写法，输出时统一为 `HKEY_LOCAL_MACHINE\SOFTWARE\...`：
+///
+/// ```text
+/// HKLM\SOFTWARE\Microsoft
+/// HKEY_LOCAL_MACHINE\SOFTWARE\Microsoft\
+/// 计算机\HKEY_LOCAL_MACHINE\SOFTWARE\Microsoft
+/// HKLM:\SOFTWARE\Microsoft
+/// Registry::HKEY_LOCAL_MACHINE\SOFTWARE\Microsoft
+/// "HKLM/SOFTWARE/Microsoft"
+/// ```
+///
+/// 只有路径中没有 `\` 时 `/` 才是分隔符，否则 `/` 属于键名（如 `Content Type\text/html`）。
+/// 只去掉整个输入首尾的空白，键名中的空格原样保留。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RegistryPath {
+    /// 根键
+    pub root: RegistryRoot,
+
+    /// 根键之下的各级子键，保留原始大小写
+    pub subkeys: Vec<String>,
+}
+
+impl RegistryPath {
+    /// 根键本身
+    pub fn root(root: RegistryRoot) -> Self {
+        Self {
+            root,
+            subkeys: Vec::new(),
+        }
+    }
+
+    /// 根键名和各级子键，可直接用于逐级定位
+    pub fn segments(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.root.name()).chain(self.subkeys.iter().map(String::as_str))
+    }
+}
+
+impl Display for RegistryPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.root)?;
+        for subkey in &self.subkeys {
+            write!(f, "\\{subkey}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// 去掉成对的首尾引号
+fn strip_quotes(s: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = s
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return inner.trim();
+        }
+    }
+
+    s
+}
+
+/// 去掉 PowerShell 的 `Registry::` 前缀（可带提供程序名，如 `Microsoft.PowerShell.Core\Registry::`）
+fn strip_provider(s: &str) -> &str {
+    const PROVIDER: &str = "Registry::";
+
+    let lower = s.to_ascii_lowercase();
+    match lower.find(&PROVIDER.to_ascii_lowercase()) {
+        Some(index) => &s[index + PROVIDER.len()..],
+        None => s,
+    }
+}
+
+impl FromStr for RegistryPath {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: String| Error::InvalidRegistryPath(format!("{s:?}: {reason}"));
+
+        // 键名中可以有 '/'（如 `text/html`），只有整个路径没有 '\' 时才把 '/' 当作分隔符
+        let path = strip_provider(strip_quotes(s.trim()));
+        let separator = match path.contains('\\') {
+            true => '\\',
+            false => '/',
+        };
+        let mut segments = path
+            .split(separator)
+            .filter(|segment| !segment.is_empty())
+            .peekable();
+
+        if segments
+            .next_if(|first| COMPUTER_NODES.iter().any(|c| c.eq_ignore_ascii_case(first)))
+            .is_some()
+            && segments.peek().is_none()
+        {
+            return Err(invalid("缺少根键".to_string()));
+        }
+
+        let first = segments
+            .next()
+            .ok_or_else(|| invalid("路径为空".to_string()))?;
+
+        // PowerShell 驱动器写作 HKLM:
+        let root_name = first.strip_suffix(':').unwrap_or(first);
+        let root = RegistryRoot::from_name(root_name)
+            .ok_or_else(|| invalid(format!("未知的根键 {first:?}")))?;
+
+        let mut subkeys = Vec::new();
+        for segment in segments {
+            let len = segment.chars().count();
+            if len > MAX_KEY_NAME_LEN {
+                return Err(invalid(format!(
+                    "键名超过 {MAX_KEY_NAME_LEN} 个字符 ({len})"
+                )));
+            }
+
+            subkeys.push(segment.to_string());
+        }
+
+        Ok(Self { root, subkeys })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> RegistryPath {
+        s.parse().unwrap_or_else(|e| panic!("{s}: {e}"))
+    }
+
+    fn hklm_software_microsoft() -> RegistryPath {
+        RegistryPath {
+            root: RegistryRoot::LocalMachine,
+            subkeys: vec!["SOFTWARE".to_string(), "Microsoft".to_string()],
+        }
+    }
+
+    #[test]
+    fn accepted_forms() {
+        for input in [
+            r"HKEY_LOCAL_MACHINE\SOFTWARE\Microsoft",
+            r"HKLM\SOFTWARE\Microsoft",
+            r"hklm\SOFTWARE\Microsoft",
+            r"HKLM\SOFTWARE\Microsoft\",
+            r"HKLM\SOFTWARE\Microsoft\\",
+            r"\HKLM\SOFTWARE\Microsoft",
+            r"HKLM\\SOFTWARE\\Microsoft",
+            r"HKLM/SOFTWARE/Microsoft",
+            r"HKLM/SOFTWARE/Microsoft/",
+            r"HKLM:\SOFTWARE\Microsoft",
+            r"Computer\HKEY_LOCAL_MACHINE\SOFTWARE\Microsoft",
+            r"computer\HKLM\SOFTWARE\Microsoft",
+            r"计算机\HKEY_LOCAL_MACHINE\SOFTWARE\Microsoft",
+            r"Registry::HKEY_LOCAL_MACHINE\SOFTWARE\Microsoft",
+            r"Microsoft.PowerShell.Core\Registry::HKEY_LOCAL_MACHINE\SOFTWARE\Microsoft",
+            r#""HKLM\SOFTWARE\Microsoft""#,
+            r#"'HKLM\SOFTWARE\Microsoft\'"#,
+            "  HKLM\\SOFTWARE\\Microsoft \n",
+        ] {
+            assert_eq!(parse(input), hklm_software_microsoft(), "{input}");
+        }
+    }
+
+    #[test]
+    fn all_roots() {
+        for root in RegistryRoot::ALL {
+            assert_eq!(parse(root.name()), RegistryPath::root(root));
+            assert_eq!(parse(root.short_name()), RegistryPath::root(root));
+            assert_eq!(parse(&format!("{}:\\", root.short_name())).root, root);
+        }
+    }
+
+    #[test]
+    fn keeps_inner_spaces_and_case() {
+        let path = parse(r"HKCU\Control Panel\Desktop\WindowMetrics");
+        assert_eq!(
+            path.subkeys,
+            vec!["Control Panel", "Desktop", "WindowMetrics"]
+        );
+
+        let path = parse(r"HKCU\Software\ Leading\Trailing ");
+        assert_eq!(path.subkeys, vec!["Software", " Leading", "Trailing"]);
+    }
+
+    #[test]
+    fn slash_in_key_name() {
+        let path = parse(r"HKCR\MIME\Database\Content Type\text/html");
+        assert_eq!(
+            path.subkeys,
+            vec!["MIME", "Database", "Content Type", "text/html"]
+        );
+    }
+
+    #[test]
+    fn canonical_display() {
+        assert_eq!(
+            parse(r"hkcu:/software/Microsoft/").to_string(),
+            r"HKEY_CURRENT_USER\software\Microsoft"
+        );
+        assert_eq!(parse("HKU").to_string(), "HKEY_USERS");
+
+        let path = parse(r"HKCR\.txt");
+        assert_eq!(
+            path.segments().collect::<Vec<_>>(),
+            ["HKEY_CLASSES_ROOT", ".txt"]
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = parse(r"Computer\HKEY_CURRENT_CONFIG\System\CurrentControlSet");
+        assert_eq!(parse(&path.to_string()), path);
+    }
+
+    #[test]
+    fn errors() {
+        for (input, reason) in [
+            ("", "路径为空"),
+            ("   ", "路径为空"),
+            (r"\\", "路径为空"),
+            ("\"\"", "路径为空"),
+            ("Computer", "缺少根键"),
+            (r"计算机\", "缺少根键"),
+            (r"HKEY_LOCAL\SOFTWARE", "未知的根键 \"HKEY_LOCAL\""),
+            (r"SOFTWARE\Microsoft", "未知的根键 \"SOFTWARE\""),
+            ("HKLM::", "未知的根键 \"HKLM::\""),
+            (r"C:\Windows", "未知的根键 \"C:\""),
+        ] {
+            let err = input.parse::<RegistryPath>().unwrap_err();
+            assert!(
+                matches!(&err, Error::InvalidRegistryPath(message) if message.ends_with(reason)),
+                "{input:?}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn key_name_too_long() {
+        let long = "a".repeat(256);
+        let err = format!(r"HKCU\{long}").parse::<RegistryPath>().unwrap_err();
+        assert!(matches!(err, Error::InvalidRegistryPath(_)));
+
+        let max = "键".repeat(255);
+        assert_eq!(parse(&format!(r"HKCU\{max}")).subkeys, vec![max]);
+    }
+}
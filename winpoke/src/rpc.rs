@@ -0,0 +1,52 @@
+//! JSON-RPC 2.0 自动化服务（需要 `rpc` 特性）
+//!
+//! 供远程的测试程序查找和操作窗口。每行一个请求对象或批量请求数组，服务端对每行返回一行
+//! 响应；通知（不带 `id` 的请求）没有响应。一行最多 1 MiB，超过时服务端返回错误并断开连接。
+//!
+//! ```text
+//! → {"jsonrpc":"2.0","id":1,"method":"auth","params":{"token":"secret"}}
+//! ← {"jsonrpc":"2.0","id":1,"result":true}
+//! → {"jsonrpc":"2.0","id":2,"method":"find","params":{"path":"Notepad > Edit"}}
+//! ← {"jsonrpc":"2.0","id":2,"result":[{"id":1,"class_name":"Edit","caption":""}]}
+//! → [{"jsonrpc":"2.0","id":3,"method":"focus","params":{"window":1}},
+//!    {"jsonrpc":"2.0","id":4,"method":"send","params":{"window":1,"messages":[{"type":"text","text":"hi"}]}}]
+//! ← [{"jsonrpc":"2.0","id":3,"result":null},{"jsonrpc":"2.0","id":4,"result":null}]
+//! ```
+//!
+//! | 方法 | 参数 | 结果 |
+//! | --- | --- | --- |
+//! | `auth` | `token` | `true` |
+//! | `find` | `path`：选择器路径 | 窗口数组 |
+//! | `tree` | `window`（省略时为所有顶层窗口）、`depth` | 带 `children` 的窗口数组 |
+//! | `focus` | `window` | `null` |
+//! | `send` | `window`、`messages` | `null` |
+//! | `wait` | `path`、`gone`（为 `true` 时等待窗口关闭） | 窗口或 `null` |
+//! | `launch` | `command` | `{"pid": …}` |
+//!
+//! 窗口表示为 `{"id", "class_name", "caption"}`，之后的请求用 `id` 指定窗口，只能使用服务端
+//! 返回过的 `id`。消息的格式为 `{"type": "key", "key": "Enter"}`（`key` 也可以是虚拟键码）、
+//! `{"type": "char", "char": "a"}`、`{"type": "text", "text": "abc"}`、
+//! `{"type": "command", "id": 40001}` 或 `{"type": "mouse_move", "x": 10, "y": 20}`，
+//! `key` 和 `char` 可以带重复次数 `count`。
+//!
+//! 所有方法都可以带 `timeout_ms`，`wait` 最多等待这么久；未指定时使用服务端的默认值。
+//! 请求因后端被其它请求占用而超时未开始时被取消，返回 [`REQUEST_TIMEOUT`] 错误；
+//! 已经开始的请求总是执行完并返回结果。
+//!
+//! 服务端设置了令牌时，每个连接需要先调用 `auth`；未设置令牌时不能调用 `launch`。
+//! 令牌按常数时间比较。
+
+mod client;
+#[cfg(windows)]
+mod pipe;
+pub(crate) mod protocol;
+pub(crate) mod server;
+
+pub use client::Client;
+#[cfg(windows)]
+pub use pipe::NamedPipe;
+pub use protocol::{
+    INTERNAL_ERROR, INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND, OPERATION_FAILED,
+    PARSE_ERROR, REQUEST_TIMEOUT, RpcError, UNAUTHORIZED, WINDOW_NOT_FOUND,
+};
+pub use server::{Server, Session};
@@ -0,0 +1,122 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+
+use serde_json::{Value, json};
+
+use crate::rpc::protocol::RpcError;
+
+/// 同步 JSON-RPC 客户端
+///
+/// 外层的 [`io::Result`] 表示连接错误或无法解析的响应，内层为服务端返回的结果。
+#[derive(Debug)]
+pub struct Client<R, W> {
+    reader: BufReader<R>,
+    writer: W,
+    next_id: u64,
+}
+
+impl Client<TcpStream, TcpStream> {
+    /// 连接 TCP 服务端
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        Ok(Self::new(stream.try_clone()?, stream))
+    }
+}
+
+impl<R: Read, W: Write> Client<R, W> {
+    pub fn new(reader: R, writer: W) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            writer,
+            next_id: 1,
+        }
+    }
+
+    /// 调用 `method` 并等待结果
+    pub fn call(
+        &mut self,
+        method: &str,
+        params: Value,
+    ) -> io::Result<std::result::Result<Value, RpcError>> {
+        let id = self.next_id();
+        self.send(&request(id, method, params))?;
+
+        result(self.receive()?, id)
+    }
+
+    /// 以一个批量请求按顺序调用多个方法，结果与 `calls` 一一对应
+    pub fn batch(
+        &mut self,
+        calls: &[(&str, Value)],
+    ) -> io::Result<Vec<std::result::Result<Value, RpcError>>> {
+        let first = self.next_id;
+        self.next_id += calls.len() as u64;
+        let requests: Vec<Value> = (first..)
+            .zip(calls)
+            .map(|(id, (method, params))| request(id, method, params.clone()))
+            .collect();
+        self.send(&Value::Array(requests))?;
+
+        let Value::Array(responses) = self.receive()? else {
+            return Err(invalid_data("批量请求的响应不是数组"));
+        };
+        (first..first + calls.len() as u64)
+            .map(|id| {
+                let response = responses
+                    .iter()
+                    .find(|response| response["id"] == id)
+                    .ok_or_else(|| invalid_data(format!("缺少请求 {id} 的响应")))?;
+                result(response.clone(), id)
+            })
+            .collect()
+    }
+
+    /// 发送通知，不等待响应
+    pub fn notify(&mut self, method: &str, params: Value) -> io::Result<()> {
+        self.send(&json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+    }
+
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn send(&mut self, value: &Value) -> io::Result<()> {
+        writeln!(self.writer, "{value}")?;
+        self.writer.flush()
+    }
+
+    fn receive(&mut self) -> io::Result<Value> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        serde_json::from_str(&line).map_err(|e| invalid_data(format!("无效的响应: {e}")))
+    }
+}
+
+fn request(id: u64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+fn result(mut response: Value, id: u64) -> io::Result<std::result::Result<Value, RpcError>> {
+    if let Some(error) = response.get_mut("error") {
+        let error = serde_json::from_value(error.take())
+            .map_err(|e| invalid_data(format!("无效的错误对象: {e}")))?;
+        return Ok(Err(error));
+    }
+    if response["id"] != id {
+        return Err(invalid_data(format!("响应的 id 不是 {id}")));
+    }
+
+    match response.get_mut("result") {
+        Some(result) => Ok(Ok(result.take())),
+        None => Err(invalid_data("响应缺少 result")),
+    }
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
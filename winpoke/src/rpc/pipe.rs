@@ -0,0 +1,111 @@
+use std::fs::File;
+use std::io;
+use std::os::windows::io::{FromRawHandle, OwnedHandle};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use windows::Win32::Foundation::{ERROR_PIPE_CONNECTED, HANDLE, HLOCAL, LocalFree};
+use windows::Win32::Security::Authorization::{
+    ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+};
+use windows::Win32::Security::{PSECURITY_DESCRIPTOR, SECURITY_ATTRIBUTES};
+use windows::Win32::Storage::FileSystem::{FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX};
+use windows::Win32::System::Pipes::{
+    ConnectNamedPipe, CreateNamedPipeW, PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS,
+    PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+};
+use windows::core::{HSTRING, w};
+
+const BUFFER_SIZE: u32 = 4096;
+
+/// 只允许管道的所有者（创建服务端的用户）和 SYSTEM 访问
+const SECURITY_DESCRIPTOR: windows::core::PCWSTR = w!("D:P(A;;GA;;;OW)(A;;GA;;;SY)");
+
+/// 命名管道服务端
+///
+/// 每次 [`accept`](Self::accept) 创建一个新的管道实例并等待客户端连接。只有当前用户能连接，
+/// 拒绝远程客户端；第一个实例以 `FILE_FLAG_FIRST_PIPE_INSTANCE` 创建，
+/// 同名管道已被其它程序占用时返回错误。
+#[derive(Debug)]
+pub struct NamedPipe {
+    name: HSTRING,
+    created: AtomicBool,
+}
+
+impl NamedPipe {
+    /// `name` 为完整的管道名，如 `\\.\pipe\winpoke`
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: HSTRING::from(name),
+            created: AtomicBool::new(false),
+        }
+    }
+
+    /// 是否已经创建过管道实例，即已经占用了管道名
+    pub fn is_created(&self) -> bool {
+        self.created.load(Ordering::Acquire)
+    }
+
+    /// 等待下一个客户端连接
+    pub fn accept(&self) -> io::Result<File> {
+        let descriptor = SecurityDescriptor::new()?;
+        let attributes = SECURITY_ATTRIBUTES {
+            nLength: size_of::<SECURITY_ATTRIBUTES>() as u32,
+            lpSecurityDescriptor: descriptor.0.0,
+            bInheritHandle: false.into(),
+        };
+        let mut open_mode = PIPE_ACCESS_DUPLEX;
+        if !self.created.load(Ordering::Acquire) {
+            open_mode |= FILE_FLAG_FIRST_PIPE_INSTANCE;
+        }
+
+        let handle = unsafe {
+            CreateNamedPipeW(
+                &self.name,
+                open_mode,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+                PIPE_UNLIMITED_INSTANCES,
+                BUFFER_SIZE,
+                BUFFER_SIZE,
+                0,
+                Some(&attributes),
+            )
+        };
+        if handle.is_invalid() {
+            return Err(io::Error::last_os_error());
+        }
+        self.created.store(true, Ordering::Release);
+        let pipe = unsafe { File::from(OwnedHandle::from_raw_handle(handle.0)) };
+
+        match unsafe { ConnectNamedPipe(HANDLE(handle.0), None) } {
+            Ok(()) => Ok(pipe),
+            Err(e) if e.code() == ERROR_PIPE_CONNECTED.to_hresult() => Ok(pipe),
+            Err(e) => Err(io::Error::other(e)),
+        }
+    }
+}
+
+/// 由 `LocalAlloc` 分配的安全描述符
+struct SecurityDescriptor(PSECURITY_DESCRIPTOR);
+
+impl SecurityDescriptor {
+    fn new() -> io::Result<Self> {
+        let mut descriptor = PSECURITY_DESCRIPTOR::default();
+        unsafe {
+            ConvertStringSecurityDescriptorToSecurityDescriptorW(
+                SECURITY_DESCRIPTOR,
+                SDDL_REVISION_1,
+                &mut descriptor,
+                None,
+            )
+        }
+        .map_err(io::Error::other)?;
+
+        Ok(Self(descriptor))
+    }
+}
+
+impl Drop for SecurityDescriptor {
+    fn drop(&mut self) {
+        unsafe { LocalFree(Some(HLOCAL(self.0.0))) };
+    }
+}
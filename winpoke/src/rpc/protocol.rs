@@ -0,0 +1,223 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::error::Error;
+use crate::keys::vk_from_name;
+use crate::message::{Message, WindowMessage};
+
+/// 请求不是有效的 JSON
+pub const PARSE_ERROR: i64 = -32700;
+
+/// 请求不是有效的 JSON-RPC 请求对象
+pub const INVALID_REQUEST: i64 = -32600;
+
+pub const METHOD_NOT_FOUND: i64 = -32601;
+
+/// 参数缺失或格式错误，包括无效的选择器、键名
+pub const INVALID_PARAMS: i64 = -32602;
+
+/// 服务端内部错误
+pub const INTERNAL_ERROR: i64 = -32603;
+
+/// 未调用 `auth` 或令牌错误
+pub const UNAUTHORIZED: i64 = -32000;
+
+/// 请求未在 `timeout_ms` 内完成
+pub const REQUEST_TIMEOUT: i64 = -32001;
+
+/// 找不到窗口或窗口 `id` 无效
+pub const WINDOW_NOT_FOUND: i64 = -32002;
+
+/// 操作窗口失败
+pub const OPERATION_FAILED: i64 = -32003;
+
+/// JSON-RPC 错误对象
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    pub(crate) fn invalid_params(e: impl Display) -> Self {
+        Self::new(INVALID_PARAMS, format!("参数错误: {e}"))
+    }
+}
+
+impl Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.message, self.code)
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+impl From<Error> for RpcError {
+    fn from(e: Error) -> Self {
+        let code = match e {
+            Error::WindowNotFound | Error::NoMoreChildWindow => WINDOW_NOT_FOUND,
+            Error::InvalidSelector(_) | Error::InvalidHotkey(_) | Error::InvalidPattern(_) => {
+                INVALID_PARAMS
+            }
+            Error::MessageTimeout | Error::WaitTimeout(_) => REQUEST_TIMEOUT,
+            _ => OPERATION_FAILED,
+        };
+
+        Self::new(code, e.to_string())
+    }
+}
+
+/// 一个请求，`id` 为 `None` 时是通知
+#[derive(Debug)]
+pub(crate) struct Call {
+    pub id: Option<Value>,
+    pub method: String,
+    pub params: Value,
+}
+
+/// 一行请求
+#[derive(Debug)]
+pub(crate) enum Incoming {
+    Single(std::result::Result<Call, RpcError>),
+    Batch(Vec<std::result::Result<Call, RpcError>>),
+}
+
+/// 解析一行请求，不是 JSON 或是空的批量请求时直接返回错误
+pub(crate) fn parse(line: &str) -> std::result::Result<Incoming, RpcError> {
+    let value: Value = serde_json::from_str(line)
+        .map_err(|e| RpcError::new(PARSE_ERROR, format!("无效的 JSON: {e}")))?;
+
+    match value {
+        Value::Array(values) if values.is_empty() => {
+            Err(RpcError::new(INVALID_REQUEST, "批量请求为空"))
+        }
+        Value::Array(values) => Ok(Incoming::Batch(
+            values.into_iter().map(parse_call).collect(),
+        )),
+        value => Ok(Incoming::Single(parse_call(value))),
+    }
+}
+
+fn parse_call(value: Value) -> std::result::Result<Call, RpcError> {
+    let invalid = |message: &str| RpcError::new(INVALID_REQUEST, message);
+    let Value::Object(mut object) = value else {
+        return Err(invalid("请求必须是对象"));
+    };
+
+    if object.get("jsonrpc") != Some(&json!("2.0")) {
+        return Err(invalid("jsonrpc 必须为 \"2.0\""));
+    }
+    let id = match object.remove("id") {
+        Some(id @ (Value::Null | Value::Number(_) | Value::String(_))) => Some(id),
+        Some(_) => return Err(invalid("id 必须是数字、字符串或 null")),
+        None => None,
+    };
+    let Some(Value::String(method)) = object.remove("method") else {
+        return Err(invalid("缺少 method"));
+    };
+    let params = match object.remove("params") {
+        None => Value::Object(Map::new()),
+        Some(params @ (Value::Object(_) | Value::Array(_))) => params,
+        Some(_) => return Err(invalid("params 必须是对象或数组")),
+    };
+
+    Ok(Call { id, method, params })
+}
+
+/// 响应对象，无法读取请求 `id` 时为 `null`
+pub(crate) fn response(id: Value, result: std::result::Result<Value, RpcError>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+    }
+}
+
+/// 转换消息参数，有无效消息时返回错误
+pub(crate) fn messages(params: Vec<MessageParam>) -> std::result::Result<Vec<Message>, RpcError> {
+    params
+        .into_iter()
+        .map(MessageParam::into_messages)
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map(|messages| messages.concat())
+}
+
+/// 消息参数，`text` 展开为逐个字符
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum MessageParam {
+    Key {
+        key: KeyParam,
+        #[serde(default = "one")]
+        count: u32,
+    },
+    Char {
+        char: char,
+        #[serde(default = "one")]
+        count: u32,
+    },
+    Text {
+        text: String,
+    },
+    Command {
+        id: u32,
+    },
+    MouseMove {
+        x: i32,
+        y: i32,
+    },
+}
+
+/// 按键名称或虚拟键码
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(crate) enum KeyParam {
+    Vk(u32),
+    Name(String),
+}
+
+fn one() -> u32 {
+    1
+}
+
+impl MessageParam {
+    fn into_messages(self) -> std::result::Result<Vec<Message>, RpcError> {
+        let message = |msg, count| Message { msg, count };
+        let messages = match self {
+            MessageParam::Key {
+                key: KeyParam::Vk(vk),
+                count,
+            } => vec![message(WindowMessage::KeyDown(vk), count)],
+            MessageParam::Key {
+                key: KeyParam::Name(name),
+                count,
+            } => {
+                let vk = vk_from_name(&name)
+                    .ok_or_else(|| RpcError::invalid_params(format!("无效的按键 {name}")))?;
+                vec![message(WindowMessage::KeyDown(vk), count)]
+            }
+            MessageParam::Char { char, count } => vec![message(WindowMessage::Char(char), count)],
+            MessageParam::Text { text } => text
+                .chars()
+                .map(|c| message(WindowMessage::Char(c), 1))
+                .collect(),
+            MessageParam::Command { id } => vec![message(WindowMessage::Command(id), 1)],
+            MessageParam::MouseMove { x, y } => {
+                vec![message(WindowMessage::MouseMove(x, y), 1)]
+            }
+        };
+
+        Ok(messages)
+    }
+}
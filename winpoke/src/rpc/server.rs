@@ -0,0 +1,834 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+use crate::error::Error;
+use crate::rpc::protocol::{
+    self, Call, INTERNAL_ERROR, INVALID_REQUEST, Incoming, METHOD_NOT_FOUND, REQUEST_TIMEOUT,
+    RpcError, UNAUTHORIZED, WINDOW_NOT_FOUND, parse, response,
+};
+use crate::script::{Backend, WAIT_INTERVAL, WindowHandle};
+use crate::selector::{Selector, SelectorPath};
+
+/// 未指定 `timeout_ms` 时的请求超时
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// `wait` 自身按 `timeout_ms` 计时，超时判断多留出的时间
+const WAIT_GRACE: Duration = Duration::from_millis(500);
+
+/// 接受连接失败后重试前的等待
+const ACCEPT_RETRY: Duration = Duration::from_millis(100);
+
+/// 一行请求的最大字节数（含换行）
+const MAX_LINE: u64 = 1 << 20;
+
+const METHODS: &[&str] = &["auth", "find", "tree", "focus", "send", "wait", "launch"];
+
+type CallResult = std::result::Result<Value, RpcError>;
+
+/// 请求的执行状态：等待后端、已开始、已取消
+const PENDING: u8 = 0;
+const RUNNING: u8 = 1;
+const CANCELLED: u8 = 2;
+
+/// 一个请求的执行状态
+///
+/// 请求在第一次锁定后端时开始执行，超时时还没开始的请求被取消。
+#[derive(Debug, Default)]
+struct Ticket(AtomicU8);
+
+impl Ticket {
+    /// 标记为已开始，请求已取消时返回 `false`
+    fn start(&self) -> bool {
+        match self
+            .0
+            .compare_exchange(PENDING, RUNNING, Ordering::AcqRel, Ordering::Acquire)
+        {
+            Ok(_) => true,
+            Err(state) => state == RUNNING,
+        }
+    }
+
+    /// 取消还没开始的请求，已开始时返回 `false`
+    fn cancel(&self) -> bool {
+        self.0
+            .compare_exchange(PENDING, CANCELLED, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+    }
+}
+
+#[derive(Debug)]
+struct Shared<B> {
+    backend: Mutex<B>,
+
+    /// 已返回给客户端的窗口，按 `id` 索引
+    windows: Mutex<HashMap<usize, WindowHandle>>,
+}
+
+/// JSON-RPC 服务端
+///
+/// 所有连接共用同一个后端和窗口 `id` 表，每个请求在单独的线程中执行。请求在锁定后端后才开始
+/// 执行：超时时还没开始的请求被取消并返回错误，已经开始的请求等到完成后返回结果，
+/// 因此客户端重试超时的请求不会重复执行操作。`wait` 在两次查找之间不锁定后端。
+///
+/// ```
+/// use winpoke::rpc::Server;
+/// use winpoke::script::{FakeDesktop, FakeWindow};
+///
+/// let server = Server::new(FakeDesktop::new().with_window(FakeWindow::new("Notepad", "无标题")));
+/// let mut session = server.session();
+///
+/// let reply = session.handle(r#"{"jsonrpc":"2.0","id":1,"method":"find","params":{"path":"Notepad"}}"#);
+/// assert_eq!(
+///     reply.as_deref(),
+///     Some(r#"{"id":1,"jsonrpc":"2.0","result":[{"caption":"无标题","class_name":"Notepad","id":0}]}"#)
+/// );
+/// ```
+#[derive(Debug)]
+pub struct Server<B> {
+    shared: Arc<Shared<B>>,
+    token: Option<Arc<str>>,
+    timeout: Duration,
+}
+
+impl<B> Clone for Server<B> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            token: self.token.clone(),
+            timeout: self.timeout,
+        }
+    }
+}
+
+impl<B: Backend + Send + 'static> Server<B> {
+    pub fn new(backend: B) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                backend: Mutex::new(backend),
+                windows: Mutex::new(HashMap::new()),
+            }),
+            token: None,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// 要求每个连接先用 `token` 调用 `auth`
+    ///
+    /// 未设置令牌时 `launch` 不可用。
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into().into());
+        self
+    }
+
+    /// 未指定 `timeout_ms` 时的请求超时，默认 30 秒
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 锁定并返回后端
+    pub fn backend(&self) -> MutexGuard<'_, B> {
+        lock(&self.shared.backend)
+    }
+
+    /// 新连接的会话
+    pub fn session(&self) -> Session<B> {
+        Session {
+            server: self.clone(),
+            authenticated: self.token.is_none(),
+        }
+    }
+
+    /// 逐行读取请求并写入响应，直到 `reader` 结束
+    ///
+    /// 一行超过 1 MiB 时返回错误响应并结束，不再读取后面的请求。
+    pub fn serve(&self, mut reader: impl BufRead, mut writer: impl Write) -> io::Result<()> {
+        let mut session = self.session();
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = reader.by_ref().take(MAX_LINE).read_line(&mut line)?;
+            if read == 0 {
+                return Ok(());
+            }
+            if read as u64 == MAX_LINE && !line.ends_with('\n') {
+                let error = RpcError::new(INVALID_REQUEST, format!("请求超过 {MAX_LINE} 字节"));
+                writeln!(writer, "{}", response(Value::Null, Err(error)))?;
+                writer.flush()?;
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "请求过长"));
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if let Some(reply) = session.handle(line.trim_end()) {
+                writeln!(writer, "{reply}")?;
+                writer.flush()?;
+            }
+        }
+    }
+
+    /// 接受 TCP 连接，每个连接在单独的线程中处理
+    ///
+    /// 接受或复制某个连接失败时跳过该连接，继续监听。
+    pub fn serve_tcp(&self, listener: TcpListener) -> io::Result<()> {
+        for stream in listener.incoming() {
+            match stream.and_then(|stream| Ok((BufReader::new(stream.try_clone()?), stream))) {
+                Ok((reader, stream)) => {
+                    let server = self.clone();
+                    thread::spawn(move || server.serve(reader, stream));
+                }
+                Err(e) => skip_connection(e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 在命名管道 `name`（如 `\\.\pipe\winpoke`）上接受连接，每个连接在单独的线程中处理
+    ///
+    /// 无法占用管道名时返回错误；之后接受或复制某个连接失败时跳过该连接，继续监听。
+    #[cfg(windows)]
+    pub fn serve_pipe(&self, name: &str) -> io::Result<()> {
+        let pipe = super::NamedPipe::new(name);
+        loop {
+            match pipe
+                .accept()
+                .and_then(|stream| Ok((BufReader::new(stream.try_clone()?), stream)))
+            {
+                Ok((reader, stream)) => {
+                    let server = self.clone();
+                    thread::spawn(move || server.serve(reader, stream));
+                }
+                Err(e) if !pipe.is_created() => return Err(e),
+                Err(e) => skip_connection(e),
+            }
+        }
+    }
+
+    fn dispatch(
+        &self,
+        method: &str,
+        params: Value,
+        timeout: Duration,
+        ticket: &Ticket,
+    ) -> CallResult {
+        match method {
+            "find" => {
+                let FindParams { path } = params_from(params)?;
+                let windows = self.acquire(ticket)?.find(&selector_path(&path)?)?;
+                Ok(Value::Array(self.register(windows)))
+            }
+            "tree" => {
+                let TreeParams { window, depth } = params_from(params)?;
+                let roots = match window {
+                    Some(id) => vec![self.window(id)?],
+                    None => self
+                        .acquire(ticket)?
+                        .find(&SelectorPath(vec![Selector::any()]))?,
+                };
+                roots
+                    .into_iter()
+                    .map(|window| self.tree(window, depth, ticket))
+                    .collect::<std::result::Result<_, _>>()
+                    .map(Value::Array)
+            }
+            "focus" => {
+                let WindowParams { window } = params_from(params)?;
+                let window = self.window(window)?;
+                self.acquire(ticket)?.focus(&window)?;
+                Ok(Value::Null)
+            }
+            "send" => {
+                let SendParams { window, messages } = params_from(params)?;
+                let window = self.window(window)?;
+                let messages = protocol::messages(messages)?;
+                self.acquire(ticket)?.send(&window, &messages)?;
+                Ok(Value::Null)
+            }
+            "wait" => {
+                let WaitParams { path, gone } = params_from(params)?;
+                let path = selector_path(&path)?;
+                let deadline = Instant::now() + timeout;
+                loop {
+                    let found = self.acquire(ticket)?.find(&path)?;
+                    match (found.into_iter().next(), gone) {
+                        (Some(window), false) => return Ok(self.register(vec![window]).remove(0)),
+                        (None, true) => return Ok(Value::Null),
+                        _ => {}
+                    }
+                    if Instant::now() >= deadline {
+                        return Err(match gone {
+                            false => {
+                                RpcError::new(REQUEST_TIMEOUT, format!("等待超时: 找不到 {path}"))
+                            }
+                            true => Error::WaitTimeout(format!("{path} 仍未关闭")).into(),
+                        });
+                    }
+
+                    thread::sleep(WAIT_INTERVAL);
+                }
+            }
+            "launch" => {
+                let LaunchParams { command } = params_from(params)?;
+                let pid = self.acquire(ticket)?.launch(&command)?;
+                Ok(json!({ "pid": pid }))
+            }
+            _ => unreachable!("未检查的方法 {method}"),
+        }
+    }
+
+    fn tree(&self, window: WindowHandle, depth: Option<usize>, ticket: &Ticket) -> CallResult {
+        let mut node = self.register(vec![window.clone()]).remove(0);
+        if depth != Some(0) {
+            let children = self.acquire(ticket)?.children(&window)?;
+            node["children"] = children
+                .into_iter()
+                .map(|child| self.tree(child, depth.map(|depth| depth - 1), ticket))
+                .collect::<std::result::Result<_, _>>()?;
+        }
+
+        Ok(node)
+    }
+
+    /// 为请求锁定后端并标记请求已开始，请求已取消时返回错误
+    fn acquire(&self, ticket: &Ticket) -> std::result::Result<MutexGuard<'_, B>, RpcError> {
+        let backend = self.backend();
+        match ticket.start() {
+            true => Ok(backend),
+            false => Err(RpcError::new(REQUEST_TIMEOUT, "请求已取消")),
+        }
+    }
+
+    fn window(&self, id: usize) -> std::result::Result<WindowHandle, RpcError> {
+        lock(&self.shared.windows)
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| RpcError::new(WINDOW_NOT_FOUND, format!("未知的窗口 id {id}")))
+    }
+
+    /// 记录窗口并转换为 JSON
+    fn register(&self, windows: Vec<WindowHandle>) -> Vec<Value> {
+        let mut registered = lock(&self.shared.windows);
+        windows
+            .into_iter()
+            .map(|window| {
+                let value = json!({
+                    "id": window.id,
+                    "class_name": window.class_name,
+                    "caption": window.caption,
+                });
+                registered.insert(window.id, window);
+                value
+            })
+            .collect()
+    }
+}
+
+/// 一个连接的状态
+#[derive(Debug)]
+pub struct Session<B> {
+    server: Server<B>,
+    authenticated: bool,
+}
+
+impl<B: Backend + Send + 'static> Session<B> {
+    /// 处理一行请求，返回一行响应，全部是通知时返回 `None`
+    pub fn handle(&mut self, line: &str) -> Option<String> {
+        let reply = match parse(line) {
+            Err(error) => Some(response(Value::Null, Err(error))),
+            Ok(Incoming::Single(call)) => self.reply(call),
+            Ok(Incoming::Batch(calls)) => {
+                let replies: Vec<Value> = calls
+                    .into_iter()
+                    .filter_map(|call| self.reply(call))
+                    .collect();
+                (!replies.is_empty()).then_some(Value::Array(replies))
+            }
+        };
+
+        reply.map(|reply| reply.to_string())
+    }
+
+    fn reply(&mut self, call: std::result::Result<Call, RpcError>) -> Option<Value> {
+        match call {
+            Err(error) => Some(response(Value::Null, Err(error))),
+            Ok(Call { id, method, params }) => {
+                let result = self.call(&method, params);
+                id.map(|id| response(id, result))
+            }
+        }
+    }
+
+    fn call(&mut self, method: &str, params: Value) -> CallResult {
+        if !METHODS.contains(&method) {
+            return Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("未知的方法 {method}"),
+            ));
+        }
+        if method == "auth" {
+            return self.auth(params);
+        }
+        if !self.authenticated {
+            return Err(RpcError::new(UNAUTHORIZED, "需要先调用 auth"));
+        }
+        if method == "launch" && self.server.token.is_none() {
+            return Err(RpcError::new(
+                UNAUTHORIZED,
+                "服务端未设置令牌，不能调用 launch",
+            ));
+        }
+
+        let timeout = match params.get("timeout_ms") {
+            None => self.server.timeout,
+            Some(ms) => ms
+                .as_u64()
+                .map(Duration::from_millis)
+                .ok_or_else(|| RpcError::invalid_params("timeout_ms 必须是非负整数"))?,
+        };
+        let limit = match method {
+            "wait" => timeout + WAIT_GRACE,
+            _ => timeout,
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        let server = self.server.clone();
+        let method = method.to_string();
+        let ticket = Arc::new(Ticket::default());
+        let running = ticket.clone();
+        thread::spawn(move || {
+            let _ = sender.send(server.dispatch(&method, params, timeout, &running));
+        });
+
+        let internal_error = || RpcError::new(INTERNAL_ERROR, "处理请求时发生内部错误");
+        match receiver.recv_timeout(limit) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) if ticket.cancel() => Err(RpcError::new(
+                REQUEST_TIMEOUT,
+                format!("请求超过 {} 毫秒未开始执行", limit.as_millis()),
+            )),
+            // 已经开始执行，等待完成后返回真实的结果
+            Err(RecvTimeoutError::Timeout) => {
+                receiver.recv().unwrap_or_else(|_| Err(internal_error()))
+            }
+            Err(RecvTimeoutError::Disconnected) => Err(internal_error()),
+        }
+    }
+
+    fn auth(&mut self, params: Value) -> CallResult {
+        let AuthParams { token } = params_from(params)?;
+        self.authenticated = match &self.server.token {
+            Some(expected) => token_eq(expected, &token),
+            None => true,
+        };
+
+        match self.authenticated {
+            true => Ok(Value::Bool(true)),
+            false => Err(RpcError::new(UNAUTHORIZED, "令牌错误")),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct AuthParams {
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct FindParams {
+    path: String,
+}
+
+#[derive(Deserialize)]
+struct TreeParams {
+    window: Option<usize>,
+    depth: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct WindowParams {
+    window: usize,
+}
+
+#[derive(Deserialize)]
+struct SendParams {
+    window: usize,
+    messages: Vec<protocol::MessageParam>,
+}
+
+#[derive(Deserialize)]
+struct WaitParams {
+    path: String,
+    #[serde(default)]
+    gone: bool,
+}
+
+#[derive(Deserialize)]
+struct LaunchParams {
+    command: String,
+}
+
+fn params_from<T: DeserializeOwned>(params: Value) -> std::result::Result<T, RpcError> {
+    serde_json::from_value(params).map_err(RpcError::invalid_params)
+}
+
+fn selector_path(path: &str) -> std::result::Result<SelectorPath, RpcError> {
+    path.parse().map_err(|e: Error| e.into())
+}
+
+/// 比较令牌，耗时只与长度有关
+pub(crate) fn token_eq(expected: &str, actual: &str) -> bool {
+    let (expected, actual) = (expected.as_bytes(), actual.as_bytes());
+    let diff = expected
+        .iter()
+        .zip(actual)
+        .fold(0, |diff, (a, b)| diff | (a ^ b));
+
+    std::hint::black_box(diff) == 0 && expected.len() == actual.len()
+}
+
+/// 记录接受连接的错误，稍后再继续接受，以免错误持续时占满 CPU
+fn skip_connection(_error: io::Error) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(error = %_error, "接受连接失败");
+    thread::sleep(ACCEPT_RETRY);
+}
+
+/// 锁定互斥量，持有锁的线程 panic 后继续使用其中的数据
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::*;
+    use crate::rpc::{
+        Client, INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND, PARSE_ERROR, UNAUTHORIZED,
+    };
+    use crate::script::{FakeDesktop, FakeWindow};
+
+    fn notepad() -> FakeWindow {
+        FakeWindow::new("Notepad", "无标题").with_child(FakeWindow::new("Edit", ""))
+    }
+
+    fn server() -> Server<FakeDesktop> {
+        Server::new(FakeDesktop::new().with_window(notepad()))
+    }
+
+    fn call(session: &mut Session<FakeDesktop>, request: Value) -> Value {
+        let reply = session.handle(&request.to_string()).unwrap();
+        serde_json::from_str(&reply).unwrap()
+    }
+
+    fn request(id: u64, method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+    }
+
+    #[test]
+    fn find_and_send() {
+        let server = server();
+        let mut session = server.session();
+
+        let reply = call(
+            &mut session,
+            request(1, "find", json!({ "path": "Notepad > Edit" })),
+        );
+        assert_eq!(
+            reply,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": [{ "id": 1, "class_name": "Edit", "caption": "" }],
+            })
+        );
+
+        let messages = json!([
+            { "type": "text", "text": "hi" },
+            { "type": "char", "char": "!", "count": 2 },
+            { "type": "key", "key": "Enter" },
+            { "type": "key", "key": 0x41 },
+        ]);
+        let reply = call(
+            &mut session,
+            request(2, "send", json!({ "window": 1, "messages": messages })),
+        );
+        assert_eq!(reply["result"], Value::Null);
+
+        let backend = server.backend();
+        assert_eq!(backend.window(1).unwrap().text, "hi!!");
+        assert_eq!(backend.history().len(), 5);
+    }
+
+    #[test]
+    fn batch_and_notifications() {
+        let server = server().with_token("secret");
+        let mut session = server.session();
+        call(
+            &mut session,
+            request(0, "auth", json!({ "token": "secret" })),
+        );
+        call(
+            &mut session,
+            request(1, "find", json!({ "path": "Notepad" })),
+        );
+
+        let batch = json!([
+            request(2, "focus", json!({ "window": 0 })),
+            { "jsonrpc": "2.0", "method": "launch", "params": { "command": "calc" } },
+            request(3, "ping", json!({})),
+            42,
+        ]);
+        let reply = call(&mut session, batch);
+        assert_eq!(
+            reply[0],
+            json!({ "jsonrpc": "2.0", "id": 2, "result": null })
+        );
+        assert_eq!(reply[1]["id"], 3);
+        assert_eq!(reply[1]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(reply[2]["id"], Value::Null);
+        assert_eq!(reply[2]["error"]["code"], INVALID_REQUEST);
+        assert_eq!(reply.as_array().unwrap().len(), 3);
+
+        let notification =
+            json!({ "jsonrpc": "2.0", "method": "focus", "params": { "window": 0 } });
+        assert_eq!(session.handle(&notification.to_string()), None);
+        assert_eq!(
+            server.backend().history(),
+            [
+                "聚焦 Notepad[caption=\"无标题\"]",
+                "启动 \"calc\"",
+                "聚焦 Notepad[caption=\"无标题\"]"
+            ]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let server = server();
+        let mut session = server.session();
+
+        let reply: Value = serde_json::from_str(&session.handle("{").unwrap()).unwrap();
+        assert_eq!(reply["error"]["code"], PARSE_ERROR);
+        assert_eq!(
+            call(&mut session, json!([]))["error"]["code"],
+            INVALID_REQUEST
+        );
+
+        let reply = call(&mut session, request(1, "focus", json!({ "window": 7 })));
+        assert_eq!(reply["error"]["code"], WINDOW_NOT_FOUND);
+        assert_eq!(reply["error"]["message"], "未知的窗口 id 7");
+
+        let reply = call(
+            &mut session,
+            request(2, "find", json!({ "path": "Notepad[" })),
+        );
+        assert_eq!(reply["error"]["code"], INVALID_PARAMS);
+        let reply = call(&mut session, request(3, "find", json!({})));
+        assert_eq!(reply["error"]["code"], INVALID_PARAMS);
+
+        call(
+            &mut session,
+            request(4, "find", json!({ "path": "Notepad" })),
+        );
+        let messages = json!([{ "type": "key", "key": "Nope" }]);
+        let reply = call(
+            &mut session,
+            request(5, "send", json!({ "window": 0, "messages": messages })),
+        );
+        assert_eq!(reply["error"]["message"], "参数错误: 无效的按键 Nope");
+    }
+
+    #[test]
+    fn auth() {
+        let server = server().with_token("secret");
+        let mut session = server.session();
+
+        let reply = call(
+            &mut session,
+            request(1, "find", json!({ "path": "Notepad" })),
+        );
+        assert_eq!(reply["error"]["code"], UNAUTHORIZED);
+        let reply = call(
+            &mut session,
+            request(2, "auth", json!({ "token": "guess" })),
+        );
+        assert_eq!(reply["error"]["code"], UNAUTHORIZED);
+
+        let reply = call(
+            &mut session,
+            request(3, "auth", json!({ "token": "secret" })),
+        );
+        assert_eq!(reply["result"], true);
+        let reply = call(
+            &mut session,
+            request(4, "find", json!({ "path": "Notepad" })),
+        );
+        assert_eq!(reply["result"][0]["id"], 0);
+
+        let reply = call(
+            &mut server.session(),
+            request(5, "find", json!({ "path": "Notepad" })),
+        );
+        assert_eq!(reply["error"]["code"], UNAUTHORIZED);
+
+        let reply = call(
+            &mut self::server().session(),
+            request(6, "launch", json!({ "command": "calc" })),
+        );
+        assert_eq!(reply["error"]["code"], UNAUTHORIZED);
+        assert!(token_eq("secret", "secret"));
+        assert!(!token_eq("secret", "secre"));
+        assert!(!token_eq("secret", "secreT"));
+    }
+
+    #[test]
+    fn wait_and_launch() {
+        let desktop = FakeDesktop::new().with_program("notepad.exe", notepad());
+        let server = Server::new(desktop).with_token("secret");
+        let mut session = server.session();
+        call(
+            &mut session,
+            request(0, "auth", json!({ "token": "secret" })),
+        );
+
+        // 等待期间不占用后端，其它线程可以操作
+        let shower = server.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(300));
+            shower.backend().add(FakeWindow::new("#32770", "保存"));
+        });
+        let params = json!({ "path": "#32770", "timeout_ms": 2000 });
+        let reply = call(&mut session, request(1, "wait", params));
+        assert_eq!(reply["result"]["caption"], "保存");
+
+        let params = json!({ "path": "Notepad", "timeout_ms": 200 });
+        let reply = call(&mut session, request(2, "wait", params));
+        assert_eq!(reply["error"]["code"], REQUEST_TIMEOUT);
+
+        let reply = call(
+            &mut session,
+            request(3, "launch", json!({ "command": "notepad.exe" })),
+        );
+        assert_eq!(reply["result"], json!({ "pid": 1000 }));
+        let reply = call(
+            &mut session,
+            request(4, "wait", json!({ "path": "*[pid=1000]" })),
+        );
+        assert_eq!(reply["result"]["class_name"], "Notepad");
+
+        let params = json!({ "path": "Calc", "gone": true });
+        let reply = call(&mut session, request(5, "wait", params));
+        assert_eq!(reply["result"], Value::Null);
+    }
+
+    #[test]
+    fn tree() {
+        let server = server();
+        let mut session = server.session();
+
+        let reply = call(&mut session, request(1, "tree", json!({})));
+        assert_eq!(
+            reply["result"],
+            json!([{
+                "id": 0,
+                "class_name": "Notepad",
+                "caption": "无标题",
+                "children": [{ "id": 1, "class_name": "Edit", "caption": "", "children": [] }],
+            }])
+        );
+
+        let reply = call(
+            &mut session,
+            request(2, "tree", json!({ "window": 0, "depth": 0 })),
+        );
+        assert_eq!(reply["result"][0].get("children"), None);
+    }
+
+    #[test]
+    fn timeout() {
+        let server = server();
+        let mut session = server.session();
+        call(
+            &mut session,
+            request(1, "find", json!({ "path": "Notepad" })),
+        );
+
+        let busy = server.backend();
+        let params = json!({ "window": 0, "timeout_ms": 50 });
+        let reply = call(&mut session, request(2, "focus", params));
+        assert_eq!(reply["error"]["code"], REQUEST_TIMEOUT);
+        drop(busy);
+
+        // 超时的请求已取消，释放后端后也不会执行
+        thread::sleep(Duration::from_millis(50));
+        assert!(server.backend().history().is_empty());
+    }
+
+    #[test]
+    fn long_line() {
+        let mut input = vec![b'x'; MAX_LINE as usize + 1];
+        input.push(b'\n');
+        input.extend(
+            request(1, "find", json!({ "path": "Notepad" }))
+                .to_string()
+                .bytes(),
+        );
+
+        let mut output = Vec::new();
+        let result = server().serve(&input[..], &mut output);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 1);
+        let reply: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(reply["error"]["code"], INVALID_REQUEST);
+
+        let mut output = Vec::new();
+        let input = request(1, "find", json!({ "path": "Notepad" })).to_string();
+        server().serve(input.as_bytes(), &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap().lines().count(), 1);
+    }
+
+    #[test]
+    fn tcp_client() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = server().with_token("secret");
+        let serving = server.clone();
+        thread::spawn(move || serving.serve_tcp(listener));
+
+        let mut client = Client::connect(addr).unwrap();
+        client
+            .call("auth", json!({ "token": "secret" }))
+            .unwrap()
+            .unwrap();
+        let found = client
+            .call("find", json!({ "path": "Notepad > Edit" }))
+            .unwrap()
+            .unwrap();
+        assert_eq!(found[0]["id"], 1);
+
+        let results = client
+            .batch(&[
+                ("focus", json!({ "window": 1 })),
+                ("focus", json!({ "window": 9 })),
+            ])
+            .unwrap();
+        assert_eq!(results[0], Ok(Value::Null));
+        assert_eq!(results[1].as_ref().unwrap_err().code, WINDOW_NOT_FOUND);
+        assert_eq!(server.backend().focused(), Some(1));
+    }
+}
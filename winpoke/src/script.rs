@@ -0,0 +1,58 @@
+//! 自动化脚本（`.poke`）
+//!
+//! 每行一条命令，`#` 开头的行为注释。字符串用双引号，其中 `${name}` 会替换为变量的值；
+//! 不含空白的参数可以省略引号。
+//!
+//! ```text
+//! # 在记事本中输入文本并保存
+//! let name = "winpoke"
+//! wait 5s notepad = Notepad
+//! find editor = Notepad > RichEditD2DPT
+//!
+//! focus notepad
+//! loop 3 as i
+//!     type editor "hello ${name} #${i}\n"
+//! end
+//! assert editor text contains "hello winpoke #3"
+//!
+//! key notepad Ctrl+S
+//! sleep 500ms
+//! menu notepad "文件 > 退出"
+//! ```
+//!
+//! | 命令 | 说明 |
+//! | --- | --- |
+//! | `let <变量> = <文本>` | 设置变量 |
+//! | `find <窗口> = <选择器路径>` | 查找窗口，找不到时失败 |
+//! | `wait [时长] <窗口> = <选择器路径>` | 等待窗口出现，默认最多 10 秒 |
+//! | `focus <窗口>` | 激活窗口 |
+//! | `type <窗口> <文本>` | 以 `WM_CHAR` 输入文本 |
+//! | `key <窗口> <组合键>` | 按键，如 `Enter`、`Ctrl+S` |
+//! | `click <窗口> [x y]` | 单击工作区坐标，省略时单击中心 |
+//! | `menu <窗口> <菜单路径>` | 执行菜单项，如 `"文件 > 保存"` |
+//! | `assert <窗口> text\|caption <比较> <文本>` | 比较为 `==`、`!=`、`contains` 或 `matches`（正则） |
+//! | `sleep <时长>` | 暂停，如 `500ms`、`2s`，不带单位时为毫秒 |
+//! | `loop <次数> [as <变量>]` … `end` | 重复执行，变量为从 1 开始的次数 |
+//!
+//! 脚本通过 [`Backend`] 操作窗口：[`Desktop`] 操作真实窗口（仅 Windows），
+//! [`FakeDesktop`] 是内存中的模拟桌面，[`DryRun`] 只记录操作而不执行。
+
+mod ast;
+mod backend;
+#[cfg(windows)]
+mod desktop;
+mod fake;
+mod interpreter;
+mod parser;
+#[cfg(feature = "rhai")]
+pub mod rhai;
+
+pub use ast::{Command, Comparison, Property, Script, Segment, Statement, Text};
+#[cfg(any(feature = "async", feature = "rpc"))]
+pub(crate) use backend::WAIT_INTERVAL;
+pub use backend::{Backend, DryRun, WindowHandle};
+#[cfg(windows)]
+pub use desktop::Desktop;
+pub use fake::{FakeDesktop, FakeWindow};
+pub use interpreter::Interpreter;
+pub(crate) use parser::DEFAULT_WAIT_TIMEOUT;
@@ -0,0 +1,302 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::error::Error;
+use crate::prelude::Result;
+use crate::script::parser::{self, DEFAULT_WAIT_TIMEOUT};
+
+/// 解析后的脚本
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Script {
+    pub statements: Vec<Statement>,
+}
+
+impl Script {
+    /// 解析脚本源码，语法错误带有行号
+    pub fn parse(source: &str) -> Result<Self> {
+        parser::parse(source)
+    }
+}
+
+impl FromStr for Script {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+/// 输出可以再次解析的源码，`loop` 的内容缩进 4 个空格
+impl Display for Script {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_statements(f, &self.statements, 0)
+    }
+}
+
+fn write_statements(
+    f: &mut std::fmt::Formatter<'_>,
+    statements: &[Statement],
+    depth: usize,
+) -> std::fmt::Result {
+    let indent = "    ".repeat(depth);
+    for statement in statements {
+        write!(f, "{indent}")?;
+        match &statement.command {
+            Command::Let { name, value } => writeln!(f, "let {name} = {}", quoted(value))?,
+            Command::Find { name, path } => writeln!(f, "find {name} = {}", raw(path))?,
+            Command::Wait {
+                name,
+                path,
+                timeout,
+            } => match *timeout == DEFAULT_WAIT_TIMEOUT {
+                true => writeln!(f, "wait {name} = {}", raw(path))?,
+                false => writeln!(f, "wait {} {name} = {}", duration(*timeout), raw(path))?,
+            },
+            Command::Focus { target } => writeln!(f, "focus {target}")?,
+            Command::Type { target, text } => writeln!(f, "type {target} {}", quoted(text))?,
+            Command::Key { target, chord } => writeln!(f, "key {target} {}", quoted(chord))?,
+            Command::Click {
+                target,
+                point: Some((x, y)),
+            } => writeln!(f, "click {target} {x} {y}")?,
+            Command::Click {
+                target,
+                point: None,
+            } => writeln!(f, "click {target}")?,
+            Command::Menu { target, path } => writeln!(f, "menu {target} {}", quoted(path))?,
+            Command::Assert {
+                target,
+                property,
+                comparison,
+                expected,
+            } => writeln!(
+                f,
+                "assert {target} {property} {comparison} {}",
+                quoted(expected)
+            )?,
+            Command::Sleep(time) => writeln!(f, "sleep {}", duration(*time))?,
+            Command::Loop {
+                count,
+                variable,
+                body,
+            } => {
+                match variable {
+                    Some(variable) => writeln!(f, "loop {count} as {variable}")?,
+                    None => writeln!(f, "loop {count}")?,
+                }
+                write_statements(f, body, depth + 1)?;
+                writeln!(f, "{indent}end")?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 整秒写成 `2s`，其余写成 `1500ms`
+fn duration(time: Duration) -> String {
+    match time.as_millis() {
+        ms if ms % 1000 == 0 => format!("{}s", ms / 1000),
+        ms => format!("{ms}ms"),
+    }
+}
+
+/// 选择器路径占据行的剩余部分，不处理转义
+fn raw(text: &Text) -> String {
+    text.0
+        .iter()
+        .map(|segment| match segment {
+            Segment::Literal(text) => text.clone(),
+            Segment::Variable(name) => format!("${{{name}}}"),
+        })
+        .collect()
+}
+
+/// 不含空白和特殊字符的文本原样输出，否则加上引号并转义
+fn quoted(text: &Text) -> String {
+    let bare = match text.0.as_slice() {
+        [Segment::Literal(literal)] => {
+            !literal.is_empty()
+                && !literal.starts_with('#')
+                && !literal.contains(|c: char| c.is_whitespace() || matches!(c, '"' | '\\' | '$'))
+        }
+        _ => false,
+    };
+    if bare {
+        return raw(text);
+    }
+
+    let mut quoted = String::from('"');
+    for segment in &text.0 {
+        match segment {
+            Segment::Literal(literal) => {
+                for c in literal.chars() {
+                    match c {
+                        '\n' => quoted.push_str("\\n"),
+                        '\t' => quoted.push_str("\\t"),
+                        '"' | '\\' | '$' => {
+                            quoted.push('\\');
+                            quoted.push(c);
+                        }
+                        c => quoted.push(c),
+                    }
+                }
+            }
+            Segment::Variable(name) => quoted.push_str(&format!("${{{name}}}")),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+/// 一条命令及其所在的行号（从 1 开始）
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    pub line: usize,
+    pub command: Command,
+}
+
+/// 脚本命令
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// `let <变量> = <文本>`
+    Let { name: String, value: Text },
+
+    /// `find <窗口> = <选择器路径>`
+    Find { name: String, path: Text },
+
+    /// `wait [时长] <窗口> = <选择器路径>`
+    Wait {
+        name: String,
+        path: Text,
+        timeout: Duration,
+    },
+
+    /// `focus <窗口>`
+    Focus { target: String },
+
+    /// `type <窗口> <文本>`
+    Type { target: String, text: Text },
+
+    /// `key <窗口> <组合键>`
+    Key { target: String, chord: Text },
+
+    /// `click <窗口> [x y]`
+    Click {
+        target: String,
+        point: Option<(i32, i32)>,
+    },
+
+    /// `menu <窗口> <菜单路径>`
+    Menu { target: String, path: Text },
+
+    /// `assert <窗口> <属性> <比较> <文本>`
+    Assert {
+        target: String,
+        property: Property,
+        comparison: Comparison,
+        expected: Text,
+    },
+
+    /// `sleep <时长>`
+    Sleep(Duration),
+
+    /// `loop <次数> [as <变量>]` … `end`
+    Loop {
+        count: u32,
+        variable: Option<String>,
+        body: Vec<Statement>,
+    },
+}
+
+/// `assert` 检查的窗口属性
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Property {
+    /// `WM_GETTEXT` 读取的文本
+    Text,
+
+    /// 窗口标题
+    Caption,
+}
+
+impl Display for Property {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Property::Text => write!(f, "text"),
+            Property::Caption => write!(f, "caption"),
+        }
+    }
+}
+
+/// `assert` 的比较方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    /// `==`
+    Equals,
+
+    /// `!=`
+    NotEquals,
+
+    /// `contains`
+    Contains,
+
+    /// `matches`，按正则表达式匹配
+    Matches,
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Comparison::Equals => write!(f, "=="),
+            Comparison::NotEquals => write!(f, "!="),
+            Comparison::Contains => write!(f, "contains"),
+            Comparison::Matches => write!(f, "matches"),
+        }
+    }
+}
+
+/// 可包含 `${变量}` 的文本
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Text(pub Vec<Segment>);
+
+/// 文本片段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Literal(String),
+    Variable(String),
+}
+
+impl Text {
+    /// 不含变量的文本
+    pub fn literal(text: impl Into<String>) -> Self {
+        Self(vec![Segment::Literal(text.into())])
+    }
+
+    /// 不含变量时返回文本内容
+    pub fn as_literal(&self) -> Option<String> {
+        self.0
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(text) => Some(text.as_str()),
+                Segment::Variable(_) => None,
+            })
+            .collect()
+    }
+
+    /// 替换变量，变量未定义时返回 [`Error::Undefined`]
+    pub fn render<'a>(&self, lookup: impl Fn(&str) -> Option<&'a str>) -> Result<String> {
+        let mut rendered = String::new();
+        for segment in &self.0 {
+            match segment {
+                Segment::Literal(text) => rendered.push_str(text),
+                Segment::Variable(name) => {
+                    rendered.push_str(lookup(name).ok_or_else(|| Error::Undefined(name.clone()))?)
+                }
+            }
+        }
+
+        Ok(rendered)
+    }
+}
@@ -0,0 +1,208 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use crate::error::Error;
+use crate::keys::format_chord;
+use crate::message::Message;
+use crate::prelude::Result;
+use crate::selector::{Selector, SelectorPath};
+
+/// 等待窗口出现或消失时查找的间隔
+pub(crate) const WAIT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 脚本中绑定的窗口
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowHandle {
+    /// 后端内部的窗口标识，真实桌面上为窗口句柄
+    pub id: usize,
+
+    /// 查找时的窗口类名
+    pub class_name: String,
+
+    /// 查找时的窗口标题
+    pub caption: String,
+}
+
+impl Display for WindowHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let selector = match self.caption.is_empty() {
+            true => Selector::class_name(&self.class_name),
+            false => Selector::class_name(&self.class_name).with_caption(&self.caption),
+        };
+
+        write!(f, "{selector}")
+    }
+}
+
+/// 脚本解释器操作窗口的方式
+///
+/// 解释器只通过这些方法访问桌面，可以换成模拟桌面（[`FakeDesktop`](super::FakeDesktop)）
+/// 或只记录不执行的 [`DryRun`]。
+pub trait Backend {
+    /// 按选择器路径查找窗口
+    fn find(&mut self, path: &SelectorPath) -> Result<Vec<WindowHandle>>;
+
+    /// 按标识重新读取窗口，窗口已关闭时返回 [`Error::WindowNotFound`]
+    fn resolve(&mut self, id: usize) -> Result<WindowHandle>;
+
+    /// 一级子窗口
+    fn children(&mut self, window: &WindowHandle) -> Result<Vec<WindowHandle>>;
+
+    /// 窗口当前的标题
+    fn caption(&mut self, window: &WindowHandle) -> Result<String>;
+
+    /// 窗口当前的文本
+    fn text(&mut self, window: &WindowHandle) -> Result<String>;
+
+    /// 激活窗口
+    fn focus(&mut self, window: &WindowHandle) -> Result<()>;
+
+    /// 输入文本
+    fn type_text(&mut self, window: &WindowHandle, text: &str) -> Result<()>;
+
+    /// 按下组合键，`vks` 为按顺序按下的虚拟键码
+    fn key(&mut self, window: &WindowHandle, vks: &[u32]) -> Result<()>;
+
+    /// 单击工作区坐标，`None` 表示工作区中心
+    fn click(&mut self, window: &WindowHandle, point: Option<(i32, i32)>) -> Result<()>;
+
+    /// 执行菜单项
+    fn menu(&mut self, window: &WindowHandle, path: &str) -> Result<()>;
+
+    /// 按顺序发送消息
+    fn send(&mut self, window: &WindowHandle, messages: &[Message]) -> Result<()>;
+
+    /// 启动程序，返回进程 ID
+    fn launch(&mut self, command: &str) -> Result<u32>;
+
+    /// 暂停
+    fn sleep(&mut self, duration: Duration);
+
+    /// 等待匹配的窗口出现并返回第一个，超时返回 [`Error::WindowNotFound`]
+    fn wait_for(&mut self, path: &SelectorPath, timeout: Duration) -> Result<WindowHandle> {
+        let mut waited = Duration::ZERO;
+        loop {
+            if let Some(window) = self.find(path)?.into_iter().next() {
+                return Ok(window);
+            }
+            if waited >= timeout {
+                return Err(Error::WindowNotFound);
+            }
+
+            self.sleep(WAIT_INTERVAL);
+            waited += WAIT_INTERVAL;
+        }
+    }
+
+    /// 等待匹配的窗口全部消失，超时返回 [`Error::WaitTimeout`]
+    fn wait_gone(&mut self, path: &SelectorPath, timeout: Duration) -> Result<()> {
+        let mut waited = Duration::ZERO;
+        while !self.find(path)?.is_empty() {
+            if waited >= timeout {
+                return Err(Error::WaitTimeout(format!("{path} 仍未关闭")));
+            }
+
+            self.sleep(WAIT_INTERVAL);
+            waited += WAIT_INTERVAL;
+        }
+
+        Ok(())
+    }
+}
+
+/// 只记录操作而不执行的后端
+///
+/// 查找窗口和读取文本交给内部后端，聚焦、输入等操作只记录为一行说明，暂停直接跳过。
+/// 用于在执行前检查脚本会做什么。
+#[derive(Debug)]
+pub struct DryRun<B> {
+    inner: B,
+    actions: Vec<String>,
+}
+
+impl<B> DryRun<B> {
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            actions: Vec::new(),
+        }
+    }
+
+    /// 已记录的操作
+    pub fn actions(&self) -> &[String] {
+        &self.actions
+    }
+
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+impl<B: Backend> Backend for DryRun<B> {
+    fn find(&mut self, path: &SelectorPath) -> Result<Vec<WindowHandle>> {
+        self.inner.find(path)
+    }
+
+    fn resolve(&mut self, id: usize) -> Result<WindowHandle> {
+        self.inner.resolve(id)
+    }
+
+    fn children(&mut self, window: &WindowHandle) -> Result<Vec<WindowHandle>> {
+        self.inner.children(window)
+    }
+
+    fn caption(&mut self, window: &WindowHandle) -> Result<String> {
+        self.inner.caption(window)
+    }
+
+    fn text(&mut self, window: &WindowHandle) -> Result<String> {
+        self.inner.text(window)
+    }
+
+    fn focus(&mut self, window: &WindowHandle) -> Result<()> {
+        self.actions.push(format!("聚焦 {window}"));
+        Ok(())
+    }
+
+    fn type_text(&mut self, window: &WindowHandle, text: &str) -> Result<()> {
+        self.actions.push(format!("输入 {window} {text:?}"));
+        Ok(())
+    }
+
+    fn key(&mut self, window: &WindowHandle, vks: &[u32]) -> Result<()> {
+        self.actions
+            .push(format!("按键 {window} {}", format_chord(vks)));
+        Ok(())
+    }
+
+    fn click(&mut self, window: &WindowHandle, point: Option<(i32, i32)>) -> Result<()> {
+        self.actions.push(match point {
+            Some((x, y)) => format!("单击 {window} ({x}, {y})"),
+            None => format!("单击 {window}"),
+        });
+        Ok(())
+    }
+
+    fn menu(&mut self, window: &WindowHandle, path: &str) -> Result<()> {
+        self.actions.push(format!("菜单 {window} {path:?}"));
+        Ok(())
+    }
+
+    fn send(&mut self, window: &WindowHandle, messages: &[Message]) -> Result<()> {
+        for message in messages {
+            self.actions.push(format!(
+                "消息 {window} {:?} x{}",
+                message.msg,
+                message.count.max(1)
+            ));
+        }
+        Ok(())
+    }
+
+    fn launch(&mut self, command: &str) -> Result<u32> {
+        self.actions.push(format!("启动 {command:?}"));
+        Ok(0)
+    }
+
+    fn sleep(&mut self, _duration: Duration) {}
+}
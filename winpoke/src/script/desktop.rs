@@ -0,0 +1,165 @@
+use std::time::Duration;
+
+use windows::Win32::Foundation::{CloseHandle, HWND};
+use windows::Win32::System::Threading::GetProcessId;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    MAPVK_VK_TO_CHAR, MAPVK_VK_TO_VSC, MapVirtualKeyW,
+};
+use windows::Win32::UI::WindowsAndMessaging::{WM_CHAR, WM_KEYDOWN, WM_KEYUP};
+
+use crate::input::{MouseButton, key_chord};
+use crate::menu::Menu;
+use crate::prelude::*;
+use crate::script::backend::{Backend, WindowHandle};
+use crate::window::active::create_process;
+
+/// 操作真实窗口的后端
+///
+/// 文本以 `WM_CHAR` 发送，换行转为编辑框使用的 `\r`。单个按键依次发送 `WM_KEYDOWN`、
+/// `WM_CHAR`（按键能产生字符时，如 Enter 产生 `\r`）和 `WM_KEYUP`。两者都不需要窗口位于前台，
+/// 但不改变系统的按键状态，通过 `GetKeyState` 判断按键的程序看不到这些输入。
+/// 带修饰键的组合键需要通过 `SendInput` 注入，会先激活窗口。
+#[derive(Debug, Default)]
+pub struct Desktop;
+
+impl Desktop {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 重新读取窗口信息，窗口已关闭时返回错误
+    fn window(&self, handle: &WindowHandle) -> Result<WindowInfo> {
+        WindowInfo::from_hwnd(HWND(handle.id as _))
+    }
+}
+
+/// 向窗口发送一次按键：按下、产生的字符（如果有）、抬起
+fn press(window: &WindowInfo, vk: u32) -> Result<()> {
+    let scan_code = unsafe { MapVirtualKeyW(vk, MAPVK_VK_TO_VSC) };
+    // 重复次数 1，16-23 位为扫描码；抬起时再置上 30、31 位
+    let down = 1 | scan_code << 16;
+    let up = down | 0xC000_0000;
+
+    window.send_raw(WM_KEYDOWN, vk as usize, down as isize)?;
+
+    // 最高位表示死键，不产生字符；未按 Shift 时字母键映射到大写字母，实际输入的是小写
+    let code = unsafe { MapVirtualKeyW(vk, MAPVK_VK_TO_CHAR) };
+    if code != 0 && code & 0x8000_0000 == 0 {
+        let code = char::from_u32(code).map_or(code, |c| c.to_ascii_lowercase() as u32);
+        window.send_raw(WM_CHAR, code as usize, down as isize)?;
+    }
+
+    window.send_raw(WM_KEYUP, vk as usize, up as isize)?;
+
+    Ok(())
+}
+
+fn handle(window: WindowInfo) -> WindowHandle {
+    WindowHandle {
+        id: window.hwnd.0 as usize,
+        class_name: window.class_name,
+        caption: window.caption,
+    }
+}
+
+impl Backend for Desktop {
+    fn find(&mut self, path: &SelectorPath) -> Result<Vec<WindowHandle>> {
+        Ok(WindowInfo::find_path(path)?
+            .into_iter()
+            .map(handle)
+            .collect())
+    }
+
+    fn resolve(&mut self, id: usize) -> Result<WindowHandle> {
+        WindowInfo::from_hwnd(HWND(id as _))
+            .map(handle)
+            .map_err(|_| Error::WindowNotFound)
+    }
+
+    fn children(&mut self, window: &WindowHandle) -> Result<Vec<WindowHandle>> {
+        match self.window(window)?.get_child_windows() {
+            Ok(children) => Ok(children.into_iter().map(handle).collect()),
+            Err(Error::WindowNotFound) => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn caption(&mut self, window: &WindowHandle) -> Result<String> {
+        Ok(self.window(window)?.caption)
+    }
+
+    fn text(&mut self, window: &WindowHandle) -> Result<String> {
+        self.window(window)?.text()
+    }
+
+    fn focus(&mut self, window: &WindowHandle) -> Result<()> {
+        let window = self.window(window)?;
+
+        // 窗口已经可见时 ShowWindow 返回失败，不影响激活
+        let _ = window.show_window();
+        window.set_focus()
+    }
+
+    fn type_text(&mut self, window: &WindowHandle, text: &str) -> Result<()> {
+        let messages = text
+            .replace("\r\n", "\n")
+            .chars()
+            .map(|c| Message {
+                msg: WindowMessage::Char(if c == '\n' { '\r' } else { c }),
+                count: 1,
+            })
+            .collect();
+
+        self.window(window)?.send_message_seq(messages)
+    }
+
+    fn key(&mut self, window: &WindowHandle, vks: &[u32]) -> Result<()> {
+        let window = self.window(window)?;
+
+        if let [vk] = *vks {
+            return press(&window, vk);
+        }
+
+        window.set_focus()?;
+        let vks: Vec<u16> = vks.iter().map(|&vk| vk as u16).collect();
+        key_chord(&vks)
+    }
+
+    fn click(&mut self, window: &WindowHandle, point: Option<(i32, i32)>) -> Result<()> {
+        let window = self.window(window)?;
+
+        match point {
+            Some(point) => window.click(point, MouseButton::Left),
+            None => {
+                // 工作区矩形已是物理像素
+                let (top, right, bottom, left) = window.client_position;
+                let center = ((right - left) / 2, (bottom - top) / 2);
+
+                window
+                    .mouse(Delivery::Message)
+                    .physical()
+                    .click(center, MouseButton::Left)
+            }
+        }
+    }
+
+    fn menu(&mut self, window: &WindowHandle, path: &str) -> Result<()> {
+        Menu::of(&self.window(window)?)?.invoke(path)
+    }
+
+    fn send(&mut self, window: &WindowHandle, messages: &[Message]) -> Result<()> {
+        self.window(window)?.send_message_seq(messages.to_vec())
+    }
+
+    fn launch(&mut self, command: &str) -> Result<u32> {
+        let process = create_process(command)?;
+        let pid = unsafe { GetProcessId(process) };
+        unsafe { CloseHandle(process) }?;
+
+        Ok(pid)
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
@@ -0,0 +1,380 @@
+use std::time::Duration;
+
+use crate::error::Error;
+use crate::keys::format_chord;
+use crate::label::{self, PATH_SEPARATOR};
+use crate::message::{Message, WindowMessage};
+use crate::prelude::Result;
+use crate::script::backend::{Backend, WindowHandle};
+use crate::selector::SelectorPath;
+
+/// 模拟桌面中的窗口
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FakeWindow {
+    pub class_name: String,
+    pub caption: String,
+
+    /// `text` 属性的值，输入的文本会追加到末尾
+    pub text: String,
+
+    pub pid: u32,
+
+    /// 可以执行的菜单项路径，如 `"文件 > 保存"`
+    pub menu: Vec<String>,
+
+    pub children: Vec<FakeWindow>,
+}
+
+impl FakeWindow {
+    pub fn new(class_name: impl Into<String>, caption: impl Into<String>) -> Self {
+        Self {
+            class_name: class_name.into(),
+            caption: caption.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.text = text.into();
+        self
+    }
+
+    pub fn with_pid(mut self, pid: u32) -> Self {
+        self.pid = pid;
+        self
+    }
+
+    pub fn with_menu_item(mut self, path: impl Into<String>) -> Self {
+        self.menu.push(path.into());
+        self
+    }
+
+    pub fn with_child(mut self, child: FakeWindow) -> Self {
+        self.children.push(child);
+        self
+    }
+}
+
+#[derive(Debug)]
+struct Node {
+    window: FakeWindow,
+    children: Vec<usize>,
+}
+
+/// 内存中的模拟桌面
+///
+/// 按选择器路径查找窗口，输入的文本追加到窗口的 `text`，执行的操作记录在
+/// [`history`](Self::history) 中。暂停只推进内部时钟，可以用
+/// [`show_after`](Self::show_after) 模拟稍后出现的窗口。
+///
+/// ```
+/// use winpoke::script::{FakeDesktop, FakeWindow, Interpreter, Script};
+///
+/// let desktop = FakeDesktop::new()
+///     .with_window(FakeWindow::new("Notepad", "无标题").with_child(FakeWindow::new("Edit", "")));
+///
+/// let script: Script = "find editor = Notepad > Edit\ntype editor hello".parse()?;
+/// let mut interpreter = Interpreter::new(desktop);
+/// interpreter.run(&script)?;
+///
+/// assert_eq!(interpreter.backend().history(), ["输入 Edit \"hello\""]);
+/// # Ok::<(), winpoke::error::Error>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct FakeDesktop {
+    nodes: Vec<Node>,
+    top_level: Vec<usize>,
+    pending: Vec<(Duration, FakeWindow)>,
+    programs: Vec<(String, FakeWindow)>,
+    launched: u32,
+    clock: Duration,
+    focused: Option<usize>,
+    history: Vec<String>,
+}
+
+impl FakeDesktop {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加顶层窗口
+    pub fn with_window(mut self, window: FakeWindow) -> Self {
+        self.add(window);
+        self
+    }
+
+    /// 添加顶层窗口，返回其标识
+    pub fn add(&mut self, window: FakeWindow) -> usize {
+        let id = self.insert(window);
+        self.top_level.push(id);
+        id
+    }
+
+    /// 内部时钟经过 `delay` 后添加顶层窗口
+    pub fn show_after(&mut self, delay: Duration, window: FakeWindow) {
+        self.pending.push((self.clock + delay, window));
+    }
+
+    /// 启动 `command` 时添加顶层窗口，窗口的进程 ID 为启动时分配的 ID
+    pub fn with_program(mut self, command: impl Into<String>, window: FakeWindow) -> Self {
+        self.programs.push((command.into(), window));
+        self
+    }
+
+    /// 按标识读取窗口，不包含子窗口
+    pub fn window(&self, id: usize) -> Option<&FakeWindow> {
+        self.nodes.get(id).map(|node| &node.window)
+    }
+
+    /// 当前激活的窗口
+    pub fn focused(&self) -> Option<usize> {
+        self.focused
+    }
+
+    /// 已执行的操作
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// 内部时钟，从创建开始累计的暂停时长
+    pub fn elapsed(&self) -> Duration {
+        self.clock
+    }
+
+    fn insert(&mut self, mut window: FakeWindow) -> usize {
+        let children = std::mem::take(&mut window.children);
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            window,
+            children: Vec::new(),
+        });
+
+        for child in children {
+            let child = self.insert(child);
+            self.nodes[id].children.push(child);
+        }
+
+        id
+    }
+
+    fn node(&mut self, handle: &WindowHandle) -> Result<&mut Node> {
+        self.nodes.get_mut(handle.id).ok_or(Error::WindowNotFound)
+    }
+
+    fn handle(&self, id: usize) -> WindowHandle {
+        let window = &self.nodes[id].window;
+
+        WindowHandle {
+            id,
+            class_name: window.class_name.clone(),
+            caption: window.caption.clone(),
+        }
+    }
+}
+
+impl Backend for FakeDesktop {
+    fn find(&mut self, path: &SelectorPath) -> Result<Vec<WindowHandle>> {
+        let mut current = Vec::new();
+        for (depth, selector) in path.0.iter().enumerate() {
+            let candidates: Vec<usize> = match depth {
+                0 => self.top_level.clone(),
+                _ => current
+                    .iter()
+                    .flat_map(|&id: &usize| self.nodes[id].children.iter().copied())
+                    .collect(),
+            };
+
+            current = candidates
+                .into_iter()
+                .filter(|&id| {
+                    let window = &self.nodes[id].window;
+                    selector.matches_parts(&window.class_name, &window.caption, window.pid)
+                })
+                .collect();
+        }
+
+        Ok(current.into_iter().map(|id| self.handle(id)).collect())
+    }
+
+    fn resolve(&mut self, id: usize) -> Result<WindowHandle> {
+        match id < self.nodes.len() {
+            true => Ok(self.handle(id)),
+            false => Err(Error::WindowNotFound),
+        }
+    }
+
+    fn children(&mut self, window: &WindowHandle) -> Result<Vec<WindowHandle>> {
+        let children = self.node(window)?.children.clone();
+
+        Ok(children.into_iter().map(|id| self.handle(id)).collect())
+    }
+
+    fn caption(&mut self, window: &WindowHandle) -> Result<String> {
+        Ok(self.node(window)?.window.caption.clone())
+    }
+
+    fn text(&mut self, window: &WindowHandle) -> Result<String> {
+        Ok(self.node(window)?.window.text.clone())
+    }
+
+    fn focus(&mut self, window: &WindowHandle) -> Result<()> {
+        self.node(window)?;
+        self.focused = Some(window.id);
+        self.history.push(format!("聚焦 {window}"));
+
+        Ok(())
+    }
+
+    fn type_text(&mut self, window: &WindowHandle, text: &str) -> Result<()> {
+        self.node(window)?.window.text.push_str(text);
+        self.history.push(format!("输入 {window} {text:?}"));
+
+        Ok(())
+    }
+
+    fn key(&mut self, window: &WindowHandle, vks: &[u32]) -> Result<()> {
+        self.node(window)?;
+        self.history
+            .push(format!("按键 {window} {}", format_chord(vks)));
+
+        Ok(())
+    }
+
+    fn click(&mut self, window: &WindowHandle, point: Option<(i32, i32)>) -> Result<()> {
+        self.node(window)?;
+        self.history.push(match point {
+            Some((x, y)) => format!("单击 {window} ({x}, {y})"),
+            None => format!("单击 {window}"),
+        });
+
+        Ok(())
+    }
+
+    fn menu(&mut self, window: &WindowHandle, path: &str) -> Result<()> {
+        let segments: Vec<&str> = path.split(PATH_SEPARATOR).map(str::trim).collect();
+        let found = self.node(window)?.window.menu.iter().any(|item| {
+            let labels: Vec<&str> = item.split(PATH_SEPARATOR).map(str::trim).collect();
+            labels.len() == segments.len()
+                && labels
+                    .iter()
+                    .zip(&segments)
+                    .all(|(label, segment)| label::matches(label, segment))
+        });
+        if !found {
+            return Err(Error::MenuItemNotFound(path.to_string()));
+        }
+        self.history.push(format!("菜单 {window} {path:?}"));
+
+        Ok(())
+    }
+
+    /// `WM_CHAR` 追加到文本，其它消息只记录
+    fn send(&mut self, window: &WindowHandle, messages: &[Message]) -> Result<()> {
+        for message in messages {
+            let count = message.count.max(1) as usize;
+            if let WindowMessage::Char(c) = message.msg {
+                let text = c.to_string().repeat(count);
+                self.node(window)?.window.text.push_str(&text);
+            } else {
+                self.node(window)?;
+            }
+            self.history
+                .push(format!("消息 {window} {:?} x{count}", message.msg));
+        }
+
+        Ok(())
+    }
+
+    /// 分配从 1000 开始的进程 ID，已注册的程序会显示窗口
+    fn launch(&mut self, command: &str) -> Result<u32> {
+        let pid = 1000 + self.launched;
+        self.launched += 1;
+        self.history.push(format!("启动 {command:?}"));
+
+        if let Some((_, window)) = self.programs.iter().find(|(name, _)| name == command) {
+            let window = window.clone().with_pid(pid);
+            self.add(window);
+        }
+
+        Ok(pid)
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        self.clock += duration;
+
+        let (due, pending) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|(at, _)| *at <= self.clock);
+        self.pending = pending;
+        for (_, window) in due {
+            self.add(window);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn desktop() -> FakeDesktop {
+        FakeDesktop::new()
+            .with_window(
+                FakeWindow::new("#32770", "另存为")
+                    .with_child(FakeWindow::new("Edit", "a.txt"))
+                    .with_child(FakeWindow::new("Button", "保存(&S)")),
+            )
+            .with_window(
+                FakeWindow::new("Notepad", "无标题").with_child(FakeWindow::new("Edit", "")),
+            )
+    }
+
+    fn find(desktop: &mut FakeDesktop, path: &str) -> Vec<WindowHandle> {
+        desktop.find(&path.parse().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn find_by_path() {
+        let mut desktop = desktop();
+
+        assert_eq!(find(&mut desktop, "* > Edit").len(), 2);
+        let edits = find(&mut desktop, r#"#32770[caption="另存为"] > Edit"#);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].caption, "a.txt");
+        assert!(find(&mut desktop, "Edit").is_empty());
+        assert!(find(&mut desktop, "Notepad > Button").is_empty());
+    }
+
+    #[test]
+    fn windows_appear_over_time() {
+        let mut desktop = FakeDesktop::new();
+        desktop.show_after(Duration::from_secs(1), FakeWindow::new("Late", ""));
+
+        desktop.sleep(Duration::from_millis(600));
+        assert!(find(&mut desktop, "Late").is_empty());
+        desktop.sleep(Duration::from_millis(400));
+        assert_eq!(find(&mut desktop, "Late").len(), 1);
+        assert_eq!(desktop.elapsed(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn children_and_programs() {
+        let mut desktop = desktop().with_program("notepad.exe", FakeWindow::new("Notepad", "新"));
+
+        let dialog = &find(&mut desktop, "#32770")[0];
+        let children = desktop.children(dialog).unwrap();
+        let classes: Vec<&str> = children.iter().map(|c| c.class_name.as_str()).collect();
+        assert_eq!(classes, ["Edit", "Button"]);
+        assert!(desktop.children(&children[0]).unwrap().is_empty());
+        assert_eq!(desktop.resolve(dialog.id).unwrap(), *dialog);
+        assert!(matches!(desktop.resolve(99), Err(Error::WindowNotFound)));
+
+        assert_eq!(desktop.launch("calc.exe").unwrap(), 1000);
+        assert_eq!(desktop.launch("notepad.exe").unwrap(), 1001);
+        assert_eq!(find(&mut desktop, "Notepad").len(), 2);
+        assert_eq!(find(&mut desktop, "Notepad[pid=1001]")[0].caption, "新");
+        assert_eq!(
+            desktop.history(),
+            [r#"启动 "calc.exe""#, r#"启动 "notepad.exe""#]
+        );
+    }
+}
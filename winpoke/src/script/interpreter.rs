@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use crate::error::Error;
+use crate::keys::parse_chord;
+use crate::prelude::Result;
+use crate::script::ast::{Command, Comparison, Property, Script, Statement, Text};
+use crate::script::backend::{Backend, WindowHandle};
+use crate::selector::SelectorPath;
+
+/// 脚本解释器
+///
+/// 变量和窗口绑定在多次 [`run`](Self::run) 之间保留，可以分段执行脚本。
+#[derive(Debug)]
+pub struct Interpreter<B> {
+    backend: B,
+    variables: HashMap<String, String>,
+    windows: HashMap<String, WindowHandle>,
+}
+
+impl<B: Backend> Interpreter<B> {
+    pub fn new(backend: B) -> Self {
+        Self {
+            backend,
+            variables: HashMap::new(),
+            windows: HashMap::new(),
+        }
+    }
+
+    /// 预先设置变量，如从命令行传入的参数
+    pub fn with_variable(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.variables.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    pub fn backend_mut(&mut self) -> &mut B {
+        &mut self.backend
+    }
+
+    pub fn into_backend(self) -> B {
+        self.backend
+    }
+
+    /// 变量的值
+    pub fn variable(&self, name: &str) -> Option<&str> {
+        self.variables.get(name).map(String::as_str)
+    }
+
+    /// 绑定的窗口
+    pub fn window(&self, name: &str) -> Option<&WindowHandle> {
+        self.windows.get(name)
+    }
+
+    /// 按顺序执行脚本，遇到错误时停止并返回带行号的 [`Error::ScriptFailed`]
+    pub fn run(&mut self, script: &Script) -> Result<()> {
+        self.run_block(&script.statements)
+    }
+
+    fn run_block(&mut self, statements: &[Statement]) -> Result<()> {
+        for statement in statements {
+            self.execute(statement).map_err(|e| match e {
+                // 循环体内的错误已经带有行号
+                e @ Error::ScriptFailed { .. } => e,
+                e => Error::ScriptFailed {
+                    line: statement.line,
+                    source: Box::new(e),
+                },
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn execute(&mut self, statement: &Statement) -> Result<()> {
+        match &statement.command {
+            Command::Let { name, value } => {
+                let value = self.render(value)?;
+                self.variables.insert(name.clone(), value);
+            }
+            Command::Find { name, path } => {
+                let path = self.selector_path(path)?;
+                let window = self
+                    .backend
+                    .find(&path)?
+                    .into_iter()
+                    .next()
+                    .ok_or(Error::WindowNotFound)?;
+                self.windows.insert(name.clone(), window);
+            }
+            Command::Wait {
+                name,
+                path,
+                timeout,
+            } => {
+                let path = self.selector_path(path)?;
+                let window = self.backend.wait_for(&path, *timeout)?;
+                self.windows.insert(name.clone(), window);
+            }
+            Command::Focus { target } => {
+                let window = self.target(target)?;
+                self.backend.focus(&window)?;
+            }
+            Command::Type { target, text } => {
+                let window = self.target(target)?;
+                let text = self.render(text)?;
+                self.backend.type_text(&window, &text)?;
+            }
+            Command::Key { target, chord } => {
+                let window = self.target(target)?;
+                let chord = self.render(chord)?;
+                let vks = parse_chord(&chord).ok_or(Error::InvalidHotkey(chord))?;
+                self.backend.key(&window, &vks)?;
+            }
+            Command::Click { target, point } => {
+                let window = self.target(target)?;
+                self.backend.click(&window, *point)?;
+            }
+            Command::Menu { target, path } => {
+                let window = self.target(target)?;
+                let path = self.render(path)?;
+                self.backend.menu(&window, &path)?;
+            }
+            Command::Assert {
+                target,
+                property,
+                comparison,
+                expected,
+            } => {
+                let window = self.target(target)?;
+                let expected = self.render(expected)?;
+                let actual = match property {
+                    Property::Text => self.backend.text(&window)?,
+                    Property::Caption => self.backend.caption(&window)?,
+                };
+
+                if !compare(&actual, *comparison, &expected)? {
+                    return Err(Error::AssertionFailed(format!(
+                        "{target} {property} 为 {actual:?}，期望 {comparison} {expected:?}"
+                    )));
+                }
+            }
+            Command::Sleep(duration) => self.backend.sleep(*duration),
+            Command::Loop {
+                count,
+                variable,
+                body,
+            } => {
+                for i in 1..=*count {
+                    if let Some(variable) = variable {
+                        self.variables.insert(variable.clone(), i.to_string());
+                    }
+                    self.run_block(body)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn render(&self, text: &Text) -> Result<String> {
+        text.render(|name| self.variable(name))
+    }
+
+    fn selector_path(&self, path: &Text) -> Result<SelectorPath> {
+        self.render(path)?.parse()
+    }
+
+    fn target(&self, name: &str) -> Result<WindowHandle> {
+        self.windows
+            .get(name)
+            .cloned()
+            .ok_or_else(|| Error::Undefined(name.to_string()))
+    }
+}
+
+fn compare(actual: &str, comparison: Comparison, expected: &str) -> Result<bool> {
+    Ok(match comparison {
+        Comparison::Equals => actual == expected,
+        Comparison::NotEquals => actual != expected,
+        Comparison::Contains => actual.contains(expected),
+        Comparison::Matches => Regex::new(expected)
+            .map_err(|e| Error::InvalidPattern(e.to_string()))?
+            .is_match(actual),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    use crate::script::{DryRun, FakeDesktop, FakeWindow};
+
+    fn notepad() -> FakeDesktop {
+        FakeDesktop::new().with_window(
+            FakeWindow::new("Notepad", "无标题 - 记事本")
+                .with_menu_item("文件(&F) > 保存(&S)\tCtrl+S")
+                .with_child(FakeWindow::new("Edit", "")),
+        )
+    }
+
+    fn run<B: Backend>(backend: B, source: &str) -> (Interpreter<B>, Result<()>) {
+        let script = Script::parse(source).unwrap();
+        let mut interpreter = Interpreter::new(backend);
+        let result = interpreter.run(&script);
+
+        (interpreter, result)
+    }
+
+    fn failure(result: Result<()>) -> (usize, Error) {
+        match result {
+            Err(Error::ScriptFailed { line, source }) => (line, *source),
+            other => panic!("{other:?}"),
+        }
+    }
+
+    #[test]
+    fn type_into_notepad() {
+        let (interpreter, result) = run(
+            notepad(),
+            r#"
+            let name = world
+            find main = Notepad
+            find editor = Notepad > Edit
+            focus main
+            loop 2 as i
+                type editor "hello ${name} ${i};"
+            end
+            key editor Ctrl+A
+            click editor
+            menu main "文件 > 保存"
+            assert editor text == "hello world 1;hello world 2;"
+            assert main caption matches "记事本$"
+            "#,
+        );
+        result.unwrap();
+
+        let desktop = interpreter.backend();
+        assert_eq!(
+            desktop.history(),
+            [
+                r#"聚焦 Notepad[caption="无标题 - 记事本"]"#,
+                r#"输入 Edit "hello world 1;""#,
+                r#"输入 Edit "hello world 2;""#,
+                "按键 Edit Ctrl+A",
+                "单击 Edit",
+                r#"菜单 Notepad[caption="无标题 - 记事本"] "文件 > 保存""#,
+            ]
+        );
+        assert_eq!(desktop.focused(), Some(0));
+        assert_eq!(interpreter.variable("i"), Some("2"));
+    }
+
+    #[test]
+    fn wait_for_window() {
+        let mut desktop = FakeDesktop::new();
+        desktop.show_after(
+            Duration::from_millis(450),
+            FakeWindow::new("#32770", "完成"),
+        );
+
+        let (interpreter, result) = run(desktop, "wait 1s done = #32770");
+        result.unwrap();
+        assert_eq!(interpreter.window("done").unwrap().caption, "完成");
+        assert_eq!(interpreter.backend().elapsed(), Duration::from_millis(500));
+
+        let (interpreter, result) = run(FakeDesktop::new(), "sleep 1s\nwait 300ms done = Nope");
+        assert!(matches!(failure(result), (2, Error::WindowNotFound)));
+        assert_eq!(interpreter.backend().elapsed(), Duration::from_millis(1300));
+    }
+
+    #[test]
+    fn failures_report_line() {
+        let source = "find editor = Notepad > Edit\nloop 3\n\n  type editor x\n  assert editor text != xx\nend";
+        let (interpreter, result) = run(notepad(), source);
+        let (line, error) = failure(result);
+        assert_eq!(line, 5);
+        assert_eq!(
+            error.to_string(),
+            r#"断言失败: editor text 为 "xx"，期望 != "xx""#
+        );
+        assert_eq!(interpreter.backend().history().len(), 2);
+
+        for (source, line, expected) in [
+            ("focus main", 1, "未定义的变量或窗口: main"),
+            ("type x ${nope}", 1, "未定义的变量或窗口: x"),
+            (
+                "find w = Notepad\ntype w ${nope}",
+                2,
+                "未定义的变量或窗口: nope",
+            ),
+            ("find w = Missing", 1, "找不到指定窗口"),
+            (
+                "find w = Notepad\nmenu w 文件>打开",
+                2,
+                "找不到菜单项: 文件>打开",
+            ),
+            (
+                "let k = Ctrl+Nope\nfind w = Notepad\nkey w ${k}",
+                3,
+                "无效的热键: Ctrl+Nope",
+            ),
+            ("let c = Edit[\nfind w = ${c}", 2, "无效的选择器"),
+        ] {
+            let (actual_line, error) = failure(run(notepad(), source).1);
+            assert_eq!(actual_line, line, "{source}");
+            assert!(error.to_string().starts_with(expected), "{source}: {error}");
+        }
+    }
+
+    #[test]
+    fn dry_run_records_without_acting() {
+        let (interpreter, result) = run(
+            DryRun::new(notepad()),
+            "find editor = Notepad > Edit\ntype editor abc\nsleep 5s\nkey editor Enter",
+        );
+        result.unwrap();
+
+        let dry_run = interpreter.into_backend();
+        assert_eq!(dry_run.actions(), [r#"输入 Edit "abc""#, "按键 Edit Enter"]);
+
+        let mut desktop = dry_run.into_inner();
+        assert!(desktop.history().is_empty());
+        assert_eq!(desktop.elapsed(), Duration::ZERO);
+        let editor = desktop.find(&"Notepad > Edit".parse().unwrap()).unwrap();
+        assert_eq!(desktop.text(&editor[0]).unwrap(), "");
+    }
+}
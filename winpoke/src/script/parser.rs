@@ -0,0 +1,562 @@
+use std::time::Duration;
+
+use regex::Regex;
+
+use crate::error::Error;
+use crate::keys::parse_chord;
+use crate::prelude::Result;
+use crate::script::ast::{Command, Comparison, Property, Script, Segment, Statement, Text};
+use crate::selector::SelectorPath;
+
+/// `wait` 未指定时长时的默认超时
+pub(crate) const DEFAULT_WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 正在解析的语句块：最外层和每个未结束的 loop 各占一层
+#[derive(Default)]
+struct Block {
+    /// loop 所在行、次数和变量，最外层为 `None`
+    header: Option<(usize, u32, Option<String>)>,
+    statements: Vec<Statement>,
+}
+
+/// 解析脚本
+pub(crate) fn parse(source: &str) -> Result<Script> {
+    let mut blocks = vec![Block::default()];
+
+    for (index, text) in source.lines().enumerate() {
+        let mut cursor = Cursor::new(index + 1, text);
+        let Some(keyword) = cursor.word() else {
+            continue;
+        };
+        if keyword.starts_with('#') {
+            continue;
+        }
+
+        let command = match keyword {
+            "loop" => {
+                let count = cursor.number("次数")?;
+                let variable = match cursor.word() {
+                    Some("as") => Some(cursor.identifier("变量名")?),
+                    Some(word) => return Err(cursor.error(format!("多余的内容 {word:?}"))),
+                    None => None,
+                };
+                cursor.finish()?;
+
+                blocks.push(Block {
+                    header: Some((cursor.line, count, variable)),
+                    statements: Vec::new(),
+                });
+                continue;
+            }
+            "end" => {
+                cursor.finish()?;
+
+                let Block {
+                    header: Some((line, count, variable)),
+                    statements: body,
+                } = blocks.pop().unwrap()
+                else {
+                    return Err(cursor.error("没有对应的 loop"));
+                };
+                blocks.last_mut().unwrap().statements.push(Statement {
+                    line,
+                    command: Command::Loop {
+                        count,
+                        variable,
+                        body,
+                    },
+                });
+                continue;
+            }
+            keyword => parse_command(keyword, &mut cursor)?,
+        };
+
+        blocks.last_mut().unwrap().statements.push(Statement {
+            line: cursor.line,
+            command,
+        });
+    }
+
+    let Block { header, statements } = blocks.pop().unwrap();
+    if let Some((line, ..)) = header {
+        return Err(Error::ScriptSyntax {
+            line,
+            message: "loop 缺少 end".to_string(),
+        });
+    }
+
+    Ok(Script { statements })
+}
+
+fn parse_command(keyword: &str, cursor: &mut Cursor) -> Result<Command> {
+    let command = match keyword {
+        "let" => {
+            let name = cursor.identifier("变量名")?;
+            cursor.expect("=")?;
+            let value = cursor.text("值")?;
+
+            Command::Let { name, value }
+        }
+        "find" => {
+            let name = cursor.identifier("窗口名")?;
+            let path = cursor.selector_path()?;
+
+            Command::Find { name, path }
+        }
+        "wait" => {
+            let timeout = match cursor.peek() {
+                Some(word) if word.starts_with(|c: char| c.is_ascii_digit()) => {
+                    cursor.duration()?
+                }
+                _ => DEFAULT_WAIT_TIMEOUT,
+            };
+            let name = cursor.identifier("窗口名")?;
+            let path = cursor.selector_path()?;
+
+            Command::Wait {
+                name,
+                path,
+                timeout,
+            }
+        }
+        "focus" => Command::Focus {
+            target: cursor.identifier("窗口名")?,
+        },
+        "type" => Command::Type {
+            target: cursor.identifier("窗口名")?,
+            text: cursor.text("文本")?,
+        },
+        "key" => {
+            let target = cursor.identifier("窗口名")?;
+            let chord = cursor.text("按键")?;
+            if let Some(chord) = chord.as_literal()
+                && parse_chord(&chord).is_none()
+            {
+                return Err(cursor.error(format!("无效的按键 {chord:?}")));
+            }
+
+            Command::Key { target, chord }
+        }
+        "click" => {
+            let target = cursor.identifier("窗口名")?;
+            let point = match cursor.peek() {
+                Some(_) => Some((cursor.number("x 坐标")?, cursor.number("y 坐标")?)),
+                None => None,
+            };
+
+            Command::Click { target, point }
+        }
+        "menu" => Command::Menu {
+            target: cursor.identifier("窗口名")?,
+            path: cursor.text("菜单路径")?,
+        },
+        "assert" => {
+            let target = cursor.identifier("窗口名")?;
+            let property = match cursor.expect_word("属性")? {
+                "text" => Property::Text,
+                "caption" => Property::Caption,
+                word => return Err(cursor.error(format!("未知的属性 {word:?}"))),
+            };
+            let comparison = match cursor.expect_word("比较方式")? {
+                "==" => Comparison::Equals,
+                "!=" => Comparison::NotEquals,
+                "contains" => Comparison::Contains,
+                "matches" => Comparison::Matches,
+                word => return Err(cursor.error(format!("未知的比较方式 {word:?}"))),
+            };
+            let expected = cursor.text("期望值")?;
+            if comparison == Comparison::Matches
+                && let Some(pattern) = expected.as_literal()
+                && let Err(e) = Regex::new(&pattern)
+            {
+                return Err(cursor.error(format!("无效的正则表达式 {pattern:?}: {e}")));
+            }
+
+            Command::Assert {
+                target,
+                property,
+                comparison,
+                expected,
+            }
+        }
+        "sleep" => Command::Sleep(cursor.duration()?),
+        keyword => return Err(cursor.error(format!("未知的命令 {keyword:?}"))),
+    };
+
+    cursor.finish()?;
+
+    Ok(command)
+}
+
+/// 一行脚本的解析位置
+struct Cursor<'a> {
+    line: usize,
+    rest: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(line: usize, text: &'a str) -> Self {
+        Self {
+            line,
+            rest: text.trim(),
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> Error {
+        Error::ScriptSyntax {
+            line: self.line,
+            message: message.into(),
+        }
+    }
+
+    /// 下一个以空白分隔的单词，不移动位置
+    fn peek(&self) -> Option<&'a str> {
+        self.rest.split_whitespace().next()
+    }
+
+    /// 读取下一个以空白分隔的单词
+    fn word(&mut self) -> Option<&'a str> {
+        let word = self.peek()?;
+        self.rest = self.rest[word.len()..].trim_start();
+
+        Some(word)
+    }
+
+    fn expect_word(&mut self, what: &str) -> Result<&'a str> {
+        self.word().ok_or_else(|| self.error(format!("缺少{what}")))
+    }
+
+    fn expect(&mut self, token: &str) -> Result<()> {
+        match self.word() {
+            Some(word) if word == token => Ok(()),
+            Some(word) => Err(self.error(format!("需要 {token:?}，实际为 {word:?}"))),
+            None => Err(self.error(format!("缺少 {token:?}"))),
+        }
+    }
+
+    fn identifier(&mut self, what: &str) -> Result<String> {
+        let word = self.expect_word(what)?;
+        if !is_identifier(word) {
+            return Err(self.error(format!("无效的{what} {word:?}")));
+        }
+
+        Ok(word.to_string())
+    }
+
+    fn number<T: std::str::FromStr>(&mut self, what: &str) -> Result<T> {
+        let word = self.expect_word(what)?;
+        word.parse()
+            .map_err(|_| self.error(format!("无效的{what} {word:?}")))
+    }
+
+    fn duration(&mut self) -> Result<Duration> {
+        let word = self.expect_word("时长")?;
+        parse_duration(word).ok_or_else(|| self.error(format!("无效的时长 {word:?}")))
+    }
+
+    /// 读取带引号的字符串或不含空白的单词
+    fn text(&mut self, what: &str) -> Result<Text> {
+        if !self.rest.starts_with('"') {
+            let word = self.expect_word(what)?;
+            return parse_text(word, false).map_err(|message| self.error(message));
+        }
+
+        let end = closing_quote(self.rest).ok_or_else(|| self.error("字符串缺少结尾的引号"))?;
+        let text = parse_text(&self.rest[1..end], true).map_err(|message| self.error(message))?;
+        self.rest = self.rest[end + 1..].trim_start();
+
+        Ok(text)
+    }
+
+    /// 读取 `= <选择器路径>`，路径占据行的剩余部分
+    fn selector_path(&mut self) -> Result<Text> {
+        self.expect("=")?;
+
+        let path = parse_text(self.rest, false).map_err(|message| self.error(message))?;
+        self.rest = "";
+
+        match path.as_literal() {
+            Some(literal) => match literal.parse::<SelectorPath>() {
+                Ok(_) => Ok(path),
+                Err(e) => Err(self.error(e.to_string())),
+            },
+            None => Ok(path),
+        }
+    }
+
+    /// 检查行已经结束
+    fn finish(&self) -> Result<()> {
+        match self.rest.is_empty() {
+            true => Ok(()),
+            false => Err(self.error(format!("多余的内容 {:?}", self.rest))),
+        }
+    }
+}
+
+fn is_identifier(word: &str) -> bool {
+    let mut chars = word.chars();
+
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// 解析时长：`500ms`、`2s`、`1.5s`，不带单位时为毫秒
+fn parse_duration(word: &str) -> Option<Duration> {
+    if let Some(ms) = word.strip_suffix("ms") {
+        return ms.parse().ok().map(Duration::from_millis);
+    }
+    if let Some(secs) = word.strip_suffix('s') {
+        return secs
+            .parse::<f64>()
+            .ok()
+            .and_then(|secs| Duration::try_from_secs_f64(secs).ok());
+    }
+
+    word.parse().ok().map(Duration::from_millis)
+}
+
+/// 以 `"` 开头的字符串中结尾引号的位置，跳过转义的引号
+fn closing_quote(s: &str) -> Option<usize> {
+    let mut chars = s.char_indices().skip(1);
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '"' => return Some(index),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// 拆分文本中的 `${变量}`，`escapes` 为真时处理 `\n`、`\t`、`\"`、`\\` 和 `\$`
+fn parse_text(s: &str, escapes: bool) -> std::result::Result<Text, String> {
+    fn push(segments: &mut Vec<Segment>, c: char) {
+        match segments.last_mut() {
+            Some(Segment::Literal(text)) => text.push(c),
+            _ => segments.push(Segment::Literal(c.to_string())),
+        }
+    }
+
+    let mut segments = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if escapes => match chars.next() {
+                Some('n') => push(&mut segments, '\n'),
+                Some('t') => push(&mut segments, '\t'),
+                Some(c @ ('"' | '\\' | '$')) => push(&mut segments, c),
+                Some(c) => return Err(format!("未知的转义字符 \\{c}")),
+                None => return Err("字符串以 \\ 结尾".to_string()),
+            },
+            '$' if chars.peek() == Some(&'{') => {
+                chars.next();
+
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => return Err(format!("变量 {name:?} 缺少 }}")),
+                    }
+                }
+                if !is_identifier(&name) {
+                    return Err(format!("无效的变量名 {name:?}"));
+                }
+                segments.push(Segment::Variable(name));
+            }
+            c => push(&mut segments, c),
+        }
+    }
+
+    Ok(Text(segments))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_one(line: &str) -> Command {
+        let mut script = parse(line).unwrap_or_else(|e| panic!("{line}: {e}"));
+        assert_eq!(script.statements.len(), 1, "{line}");
+        script.statements.remove(0).command
+    }
+
+    fn syntax_error(source: &str) -> (usize, String) {
+        match parse(source) {
+            Err(Error::ScriptSyntax { line, message }) => (line, message),
+            other => panic!("{source}: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(
+            parse_one(r#"let greeting = "hi ${name}\n""#),
+            Command::Let {
+                name: "greeting".to_string(),
+                value: Text(vec![
+                    Segment::Literal("hi ".to_string()),
+                    Segment::Variable("name".to_string()),
+                    Segment::Literal("\n".to_string()),
+                ]),
+            }
+        );
+        assert_eq!(
+            parse_one(r#"find dlg = #32770[caption="另存为"] > Edit"#),
+            Command::Find {
+                name: "dlg".to_string(),
+                path: Text::literal(r#"#32770[caption="另存为"] > Edit"#),
+            }
+        );
+        assert_eq!(
+            parse_one("wait 1.5s main = Notepad"),
+            Command::Wait {
+                name: "main".to_string(),
+                path: Text::literal("Notepad"),
+                timeout: Duration::from_millis(1500),
+            }
+        );
+        assert_eq!(
+            parse_one("wait main = Notepad"),
+            Command::Wait {
+                name: "main".to_string(),
+                path: Text::literal("Notepad"),
+                timeout: DEFAULT_WAIT_TIMEOUT,
+            }
+        );
+        assert_eq!(
+            parse_one("key main Ctrl+S"),
+            Command::Key {
+                target: "main".to_string(),
+                chord: Text::literal("Ctrl+S"),
+            }
+        );
+        assert_eq!(
+            parse_one("click main 10 -20"),
+            Command::Click {
+                target: "main".to_string(),
+                point: Some((10, -20)),
+            }
+        );
+        assert_eq!(
+            parse_one(r#"assert main caption matches "^无标题""#),
+            Command::Assert {
+                target: "main".to_string(),
+                property: Property::Caption,
+                comparison: Comparison::Matches,
+                expected: Text::literal("^无标题"),
+            }
+        );
+        assert_eq!(
+            parse_one("sleep 250"),
+            Command::Sleep(Duration::from_millis(250))
+        );
+    }
+
+    #[test]
+    fn nested_loops() {
+        let script = parse(
+            "# 注释\n\
+             loop 2 as i\n\
+             \x20   loop 3\n\
+             \x20       focus main\n\
+             \x20   end\n\
+             \n\
+             \x20   sleep 1s\n\
+             end\n\
+             focus main",
+        )
+        .unwrap();
+
+        assert_eq!(script.statements.len(), 2);
+        let Command::Loop {
+            count: 2,
+            variable: Some(variable),
+            body,
+        } = &script.statements[0].command
+        else {
+            panic!("{script:?}");
+        };
+        assert_eq!(variable, "i");
+        assert_eq!(script.statements[0].line, 2);
+        assert_eq!(body.len(), 2);
+        assert!(matches!(body[0].command, Command::Loop { count: 3, .. }));
+        assert_eq!(body[1].line, 7);
+        assert_eq!(script.statements[1].line, 9);
+    }
+
+    #[test]
+    fn errors() {
+        for (source, line, message) in [
+            ("jump main", 1, "未知的命令 \"jump\""),
+            ("focus", 1, "缺少窗口名"),
+            ("focus 1main", 1, "无效的窗口名 \"1main\""),
+            ("focus main now", 1, "多余的内容 \"now\""),
+            ("let x 1", 1, "需要 \"=\"，实际为 \"1\""),
+            ("let x = \"abc", 1, "字符串缺少结尾的引号"),
+            ("let x = \"\\q\"", 1, "未知的转义字符 \\q"),
+            ("let x = ${}", 1, "无效的变量名 \"\""),
+            ("let x = ${abc", 1, "变量 \"abc\" 缺少 }"),
+            ("find w = Edit[", 1, "无效的选择器: Edit[: 条件格式错误"),
+            ("key w Ctrl+Nope", 1, "无效的按键 \"Ctrl+Nope\""),
+            ("click w 10", 1, "缺少y 坐标"),
+            ("assert w size == 1", 1, "未知的属性 \"size\""),
+            ("sleep soon", 1, "无效的时长 \"soon\""),
+            ("sleep 1e300s", 1, "无效的时长 \"1e300s\""),
+            ("focus w\nend", 2, "没有对应的 loop"),
+            ("loop 2\nloop 3\nend", 1, "loop 缺少 end"),
+            ("loop -1\nend", 1, "无效的次数 \"-1\""),
+        ] {
+            assert_eq!(
+                syntax_error(source),
+                (line, message.to_string()),
+                "{source}"
+            );
+        }
+
+        let (_, message) = syntax_error(r#"assert w text matches "(""#);
+        assert!(message.starts_with("无效的正则表达式"), "{message}");
+    }
+
+    #[test]
+    fn example_script() {
+        let script = parse(include_str!("../../examples/notepad.poke")).unwrap();
+        assert_eq!(script.statements.len(), 10);
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let source = r#"let greeting = "hi ${name}\n"
+wait 1500ms main = #32770[caption="另存为"] > Edit
+find editor = Notepad > Edit
+loop 2 as i
+    type editor "say \"${i}\" \$5"
+    key editor Ctrl+S
+end
+click main 10 -20
+menu main "文件 > 退出"
+assert main caption != ""
+sleep 2s
+"#;
+        let script = parse(source).unwrap();
+        assert_eq!(script.to_string(), source);
+
+        let defaults = parse("wait  w  =  Notepad\ntype w hello").unwrap();
+        assert_eq!(defaults.to_string(), "wait w = Notepad\ntype w hello\n");
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("500ms"), Some(Duration::from_millis(500)));
+        assert_eq!(parse_duration("2s"), Some(Duration::from_secs(2)));
+        assert_eq!(parse_duration("0.25s"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_duration("100"), Some(Duration::from_millis(100)));
+
+        for word in ["", "s", "ms", "-1s", "1m", "fast", "1e300s", "infs", "NaNs"] {
+            assert_eq!(parse_duration(word), None, "{word}");
+        }
+    }
+}
@@ -0,0 +1,557 @@
+//! 以 [Rhai](https://rhai.rs) 编写自动化脚本（需要 `rhai` 特性）
+//!
+//! 适用于 `.poke` 脚本难以表达的逻辑，如按窗口标题分支、遍历列表项。
+//! 脚本只能通过下列函数访问桌面，不能读写文件、导入模块或启动进程；运算次数、调用深度和
+//! 字符串长度都有上限，死循环会以错误结束。
+//!
+//! ```text
+//! let main = wait("Notepad", 5000);
+//! if main.caption.starts_with("无标题") {
+//!     let editor = find_one("Notepad > Edit");
+//!     editor.type_text("hello");
+//!     editor.send([msg_key("Enter"), msg_char('!').repeat(3)]);
+//! }
+//! for dialog in find("#32770") {
+//!     print(dialog.caption);
+//! }
+//! ```
+//!
+//! | 函数 | 说明 |
+//! | --- | --- |
+//! | `find(path)` | 查找匹配的窗口，返回数组 |
+//! | `find_one(path)` | 返回第一个匹配的窗口，找不到时出错 |
+//! | `exists(path)` | 是否有匹配的窗口 |
+//! | `wait(path[, ms])` | 等待窗口出现，默认最多 10 秒 |
+//! | `wait_gone(path[, ms])` | 等待窗口全部关闭，默认最多 10 秒 |
+//! | `sleep(ms)` | 暂停 |
+//! | `selector(s)` | 解析选择器，可继续调用 `with_caption`、`with_pid` |
+//! | `msg_char(c)`、`msg_key(name)`、`msg_command(id)`、`msg_mouse_move(x, y)` | 构造消息，可调用 `repeat(n)` |
+//! | `text_messages(s)` | 逐字符的 `WM_CHAR` 消息数组 |
+//!
+//! `path` 可以是选择器路径字符串或 `selector()` 的结果。窗口的属性和方法：
+//! `class_name`、`caption`、`text`、`focus()`、`type_text(s)`、`key(chord)`、`click()`、
+//! `click(x, y)`、`menu(path)`、`send(msg)`、`send([msg, ...])`。
+
+use std::cell::{Ref, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+use ::rhai::{Array, Dynamic, Engine, EvalAltResult, ImmutableString, Scope};
+
+use crate::error::Error;
+use crate::keys::{parse_chord, vk_from_name};
+use crate::message::{Message, WindowMessage};
+use crate::prelude::Result;
+use crate::script::DEFAULT_WAIT_TIMEOUT;
+use crate::script::backend::{Backend, WindowHandle};
+use crate::selector::{Selector, SelectorPath};
+
+pub use ::rhai;
+
+/// 一次执行最多的运算次数
+const MAX_OPERATIONS: u64 = 10_000_000;
+
+/// 函数调用的最大深度
+const MAX_CALL_LEVELS: usize = 64;
+
+/// 字符串的最大长度（字节）
+const MAX_STRING_SIZE: usize = 1 << 20;
+
+type RhaiResult<T> = std::result::Result<T, Box<EvalAltResult>>;
+
+/// 注册的函数出错时保存原始错误，脚本结束后还原为 [`Error`]
+#[derive(Clone, Default)]
+struct LastError(Rc<RefCell<Option<Error>>>);
+
+impl LastError {
+    fn wrap<T>(&self, result: Result<T>) -> RhaiResult<T> {
+        result.map_err(|e| {
+            let message = e.to_string();
+            *self.0.borrow_mut() = Some(e);
+            message.into()
+        })
+    }
+
+    /// 取出与脚本错误对应的原始错误，脚本捕获过的旧错误不会被误用
+    fn take(&self, message: &str) -> Option<Error> {
+        self.0
+            .borrow_mut()
+            .take()
+            .filter(|e| e.to_string() == message)
+    }
+}
+
+/// 窗口选择器路径参数：字符串或 `selector()` 的结果
+fn to_path(path: &Dynamic) -> Result<SelectorPath> {
+    if let Some(selector) = path.clone().try_cast::<Selector>() {
+        return Ok(selector.into());
+    }
+
+    match path.clone().into_immutable_string() {
+        Ok(path) => path.parse(),
+        Err(found) => Err(Error::InvalidSelector(format!(
+            "需要字符串或选择器，实际为 {found}"
+        ))),
+    }
+}
+
+fn millis(ms: i64) -> Duration {
+    Duration::from_millis(ms.max(0) as u64)
+}
+
+/// 嵌入 Rhai 的脚本引擎
+///
+/// 所有注册的函数通过同一个 [`Backend`] 操作窗口，测试时可以使用
+/// [`FakeDesktop`](super::FakeDesktop)。
+///
+/// ```
+/// use winpoke::script::rhai::RhaiEngine;
+/// use winpoke::script::{FakeDesktop, FakeWindow};
+///
+/// let desktop = FakeDesktop::new().with_window(FakeWindow::new("Notepad", "无标题"));
+/// let mut engine = RhaiEngine::new(desktop);
+///
+/// let caption: String = engine.eval(r#"find_one("Notepad").caption"#)?;
+/// assert_eq!(caption, "无标题");
+/// # Ok::<(), winpoke::error::Error>(())
+/// ```
+pub struct RhaiEngine<B> {
+    engine: Engine,
+    scope: Scope<'static>,
+    backend: Rc<RefCell<B>>,
+    last_error: LastError,
+}
+
+impl<B: Backend + 'static> RhaiEngine<B> {
+    pub fn new(backend: B) -> Self {
+        let backend = Rc::new(RefCell::new(backend));
+        let last_error = LastError::default();
+
+        let mut engine = Engine::new();
+        // 只允许调用注册的函数，不能动态执行代码，也不能导入文件中的模块
+        engine.disable_symbol("eval");
+        engine.set_module_resolver(::rhai::module_resolvers::DummyModuleResolver::new());
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_call_levels(MAX_CALL_LEVELS);
+        engine.set_max_string_size(MAX_STRING_SIZE);
+
+        register_selectors(&mut engine, &last_error);
+        register_messages(&mut engine, &last_error);
+        register_desktop(&mut engine, &backend, &last_error);
+        register_window(&mut engine, &backend, &last_error);
+
+        Self {
+            engine,
+            scope: Scope::new(),
+            backend,
+            last_error,
+        }
+    }
+
+    /// 底层的 Rhai 引擎，可用于设置运算次数上限、重定向 `print` 等
+    pub fn engine_mut(&mut self) -> &mut Engine {
+        &mut self.engine
+    }
+
+    /// 预先设置脚本变量
+    pub fn with_variable(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.scope.push(name.into(), value.into());
+        self
+    }
+
+    pub fn backend(&self) -> Ref<'_, B> {
+        self.backend.borrow()
+    }
+
+    /// 执行脚本，顶层变量在多次执行之间保留
+    pub fn run(&mut self, source: &str) -> Result<()> {
+        self.eval::<Dynamic>(source).map(|_| ())
+    }
+
+    /// 执行脚本并返回最后一个表达式的值
+    pub fn eval<T: Clone + 'static>(&mut self, source: &str) -> Result<T> {
+        let ast = self
+            .engine
+            .compile_with_scope(&self.scope, source)
+            .map_err(|e| Error::ScriptSyntax {
+                line: e.position().line().unwrap_or(0),
+                message: e.err_type().to_string(),
+            })?;
+
+        self.last_error.0.borrow_mut().take();
+        self.engine
+            .eval_ast_with_scope(&mut self.scope, &ast)
+            .map_err(|e| self.script_failed(*e))
+    }
+
+    fn script_failed(&self, error: EvalAltResult) -> Error {
+        let line = error.position().line().unwrap_or(0);
+        let message = match error.unwrap_inner() {
+            EvalAltResult::ErrorRuntime(value, _) => value.to_string(),
+            inner => inner.to_string(),
+        };
+
+        let source = self
+            .last_error
+            .take(&message)
+            .unwrap_or(Error::Rhai(message));
+
+        Error::ScriptFailed {
+            line,
+            source: Box::new(source),
+        }
+    }
+}
+
+fn register_selectors(engine: &mut Engine, last_error: &LastError) {
+    engine.register_type_with_name::<Selector>("Selector");
+
+    let errors = last_error.clone();
+    engine.register_fn("selector", move |s: &str| {
+        errors.wrap(s.parse::<Selector>())
+    });
+    engine.register_fn("with_caption", |selector: &mut Selector, caption: &str| {
+        selector.clone().with_caption(caption)
+    });
+    let errors = last_error.clone();
+    engine.register_fn("with_pid", move |selector: &mut Selector, pid: i64| {
+        let pid =
+            u32::try_from(pid).map_err(|_| Error::InvalidSelector(format!("无效的进程 ID {pid}")));
+        errors.wrap(pid.map(|pid| selector.clone().with_pid(pid)))
+    });
+    engine.register_fn("to_string", |selector: &mut Selector| selector.to_string());
+}
+
+fn register_messages(engine: &mut Engine, last_error: &LastError) {
+    let message = |msg| Message { msg, count: 1 };
+
+    engine.register_type_with_name::<Message>("Message");
+    engine.register_fn("msg_char", move |c: char| message(WindowMessage::Char(c)));
+    engine.register_fn("msg_key", move |vk: i64| {
+        message(WindowMessage::KeyDown(vk as u32))
+    });
+    let errors = last_error.clone();
+    engine.register_fn("msg_key", move |name: &str| {
+        let vk = vk_from_name(name).ok_or_else(|| Error::InvalidHotkey(name.to_string()));
+        errors.wrap(vk.map(|vk| message(WindowMessage::KeyDown(vk))))
+    });
+    engine.register_fn("msg_command", move |id: i64| {
+        message(WindowMessage::Command(id as u32))
+    });
+    engine.register_fn("msg_mouse_move", move |x: i64, y: i64| {
+        message(WindowMessage::MouseMove(x as i32, y as i32))
+    });
+    engine.register_fn("repeat", |msg: &mut Message, count: i64| Message {
+        count: count.max(1) as u32,
+        ..msg.clone()
+    });
+    engine.register_fn("text_messages", move |text: &str| -> Array {
+        text.chars()
+            .map(|c| Dynamic::from(message(WindowMessage::Char(c))))
+            .collect()
+    });
+    engine.register_fn("to_string", |msg: &mut Message| {
+        format!("{:?} x{}", msg.msg, msg.count.max(1))
+    });
+}
+
+fn register_desktop<B: Backend + 'static>(
+    engine: &mut Engine,
+    backend: &Rc<RefCell<B>>,
+    last_error: &LastError,
+) {
+    let (b, errors) = (backend.clone(), last_error.clone());
+    engine.register_fn("find", move |path: Dynamic| -> RhaiResult<Array> {
+        let windows = errors.wrap(to_path(&path).and_then(|path| b.borrow_mut().find(&path)))?;
+        Ok(windows.into_iter().map(Dynamic::from).collect())
+    });
+
+    let (b, errors) = (backend.clone(), last_error.clone());
+    engine.register_fn("find_one", move |path: Dynamic| {
+        errors.wrap(to_path(&path).and_then(|path| {
+            b.borrow_mut()
+                .find(&path)?
+                .into_iter()
+                .next()
+                .ok_or(Error::WindowNotFound)
+        }))
+    });
+
+    let (b, errors) = (backend.clone(), last_error.clone());
+    engine.register_fn("exists", move |path: Dynamic| {
+        errors.wrap(to_path(&path).and_then(|path| Ok(!b.borrow_mut().find(&path)?.is_empty())))
+    });
+
+    for timeout in [None, Some(())] {
+        let (b, errors) = (backend.clone(), last_error.clone());
+        let wait = move |path: Dynamic, timeout: Duration| {
+            errors.wrap(to_path(&path).and_then(|path| b.borrow_mut().wait_for(&path, timeout)))
+        };
+        let (b, errors) = (backend.clone(), last_error.clone());
+        let wait_gone = move |path: Dynamic, timeout: Duration| {
+            errors.wrap(to_path(&path).and_then(|path| b.borrow_mut().wait_gone(&path, timeout)))
+        };
+
+        match timeout {
+            None => {
+                engine.register_fn("wait", move |path: Dynamic| {
+                    wait(path, DEFAULT_WAIT_TIMEOUT)
+                });
+                engine.register_fn("wait_gone", move |path: Dynamic| {
+                    wait_gone(path, DEFAULT_WAIT_TIMEOUT)
+                });
+            }
+            Some(()) => {
+                engine.register_fn("wait", move |path: Dynamic, ms: i64| wait(path, millis(ms)));
+                engine.register_fn("wait_gone", move |path: Dynamic, ms: i64| {
+                    wait_gone(path, millis(ms))
+                });
+            }
+        }
+    }
+
+    let b = backend.clone();
+    engine.register_fn("sleep", move |ms: i64| b.borrow_mut().sleep(millis(ms)));
+}
+
+fn register_window<B: Backend + 'static>(
+    engine: &mut Engine,
+    backend: &Rc<RefCell<B>>,
+    last_error: &LastError,
+) {
+    engine.register_type_with_name::<WindowHandle>("Window");
+    engine.register_get("class_name", |w: &mut WindowHandle| -> ImmutableString {
+        w.class_name.clone().into()
+    });
+    engine.register_get("id", |w: &mut WindowHandle| w.id as i64);
+    engine.register_fn("to_string", |w: &mut WindowHandle| w.to_string());
+
+    let (b, errors) = (backend.clone(), last_error.clone());
+    engine.register_get("caption", move |w: &mut WindowHandle| {
+        errors.wrap(b.borrow_mut().caption(w))
+    });
+
+    let (b, errors) = (backend.clone(), last_error.clone());
+    engine.register_get("text", move |w: &mut WindowHandle| {
+        errors.wrap(b.borrow_mut().text(w))
+    });
+
+    let (b, errors) = (backend.clone(), last_error.clone());
+    engine.register_fn("focus", move |w: &mut WindowHandle| {
+        errors.wrap(b.borrow_mut().focus(w))
+    });
+
+    let (b, errors) = (backend.clone(), last_error.clone());
+    engine.register_fn("type_text", move |w: &mut WindowHandle, text: &str| {
+        errors.wrap(b.borrow_mut().type_text(w, text))
+    });
+
+    let (b, errors) = (backend.clone(), last_error.clone());
+    engine.register_fn("key", move |w: &mut WindowHandle, chord: &str| {
+        let vks = parse_chord(chord).ok_or_else(|| Error::InvalidHotkey(chord.to_string()));
+        errors.wrap(vks.and_then(|vks| b.borrow_mut().key(w, &vks)))
+    });
+
+    let (b, errors) = (backend.clone(), last_error.clone());
+    engine.register_fn("click", move |w: &mut WindowHandle| {
+        errors.wrap(b.borrow_mut().click(w, None))
+    });
+
+    let (b, errors) = (backend.clone(), last_error.clone());
+    engine.register_fn("click", move |w: &mut WindowHandle, x: i64, y: i64| {
+        errors.wrap(b.borrow_mut().click(w, Some((x as i32, y as i32))))
+    });
+
+    let (b, errors) = (backend.clone(), last_error.clone());
+    engine.register_fn("menu", move |w: &mut WindowHandle, path: &str| {
+        errors.wrap(b.borrow_mut().menu(w, path))
+    });
+
+    let (b, errors) = (backend.clone(), last_error.clone());
+    engine.register_fn("send", move |w: &mut WindowHandle, msg: Message| {
+        errors.wrap(b.borrow_mut().send(w, &[msg]))
+    });
+
+    let (b, errors) = (backend.clone(), last_error.clone());
+    engine.register_fn("send", move |w: &mut WindowHandle, messages: Array| {
+        let messages: Result<Vec<Message>> = messages
+            .into_iter()
+            .map(|msg| {
+                let type_name = msg.type_name();
+                msg.try_cast::<Message>()
+                    .ok_or_else(|| Error::Rhai(format!("需要消息，实际为 {type_name}")))
+            })
+            .collect();
+        errors.wrap(messages.and_then(|messages| b.borrow_mut().send(w, &messages)))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::script::{DryRun, FakeDesktop, FakeWindow};
+
+    fn desktop() -> FakeDesktop {
+        FakeDesktop::new()
+            .with_window(
+                FakeWindow::new("Notepad", "无标题 - 记事本")
+                    .with_menu_item("文件 > 保存")
+                    .with_child(FakeWindow::new("Edit", "")),
+            )
+            .with_window(FakeWindow::new("#32770", "错误").with_pid(7))
+            .with_window(FakeWindow::new("#32770", "警告").with_pid(8))
+    }
+
+    fn failure(result: Result<()>) -> (usize, Error) {
+        match result {
+            Err(Error::ScriptFailed { line, source }) => (line, *source),
+            other => panic!("{other:?}"),
+        }
+    }
+
+    #[test]
+    fn conditionals_and_loops() {
+        let mut engine = RhaiEngine::new(desktop()).with_variable("greeting", "hi");
+        engine
+            .run(
+                r##"
+                let main = find_one("Notepad");
+                let editor = find_one("Notepad > Edit");
+                if main.caption.starts_with("无标题") {
+                    main.focus();
+                    editor.type_text(greeting);
+                }
+
+                let captions = [];
+                for dialog in find(selector("#32770").with_pid(8)) {
+                    captions.push(dialog.caption);
+                }
+                editor.type_text(` ${captions.len()}:${captions[0]}`);
+
+                editor.send([msg_char('!').repeat(2), msg_key("Enter")]);
+                editor.send(text_messages("ok"));
+                editor.key("Ctrl+S");
+                main.menu("文件 > 保存");
+                "##,
+            )
+            .unwrap();
+
+        let text: String = engine.eval(r#"find_one("Notepad > Edit").text"#).unwrap();
+        assert_eq!(text, "hi 1:警告!!ok");
+
+        let history = engine.backend().history().to_vec();
+        assert_eq!(history[0], r#"聚焦 Notepad[caption="无标题 - 记事本"]"#);
+        assert_eq!(history[3], "消息 Edit Char('!') x2");
+        assert_eq!(history[4], "消息 Edit KeyDown(13) x1");
+        assert_eq!(history[7], "按键 Edit Ctrl+S");
+        assert_eq!(history.len(), 9);
+    }
+
+    #[test]
+    fn wait_helpers() {
+        let mut desktop = desktop();
+        desktop.show_after(Duration::from_millis(250), FakeWindow::new("Late", "到了"));
+
+        let mut engine = RhaiEngine::new(desktop);
+        let caption: String = engine.eval(r#"wait("Late", 1000).caption"#).unwrap();
+        assert_eq!(caption, "到了");
+        assert_eq!(engine.backend().elapsed(), Duration::from_millis(300));
+
+        let (line, error) = failure(engine.run("sleep(50);\nwait(\"Never\", 200);"));
+        assert_eq!(line, 2);
+        assert!(matches!(error, Error::WindowNotFound), "{error:?}");
+        assert_eq!(engine.backend().elapsed(), Duration::from_millis(550));
+
+        let (_, error) = failure(engine.run(r#"wait_gone("Late", 100)"#));
+        assert!(matches!(error, Error::WaitTimeout(_)), "{error:?}");
+        assert!(
+            engine
+                .eval::<bool>(r#"exists("Late") && !exists("Nope")"#)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let mut engine = RhaiEngine::new(desktop());
+
+        assert!(matches!(
+            engine.run("let x = ;"),
+            Err(Error::ScriptSyntax { line: 1, .. })
+        ));
+
+        let (line, error) = failure(engine.run("let ok = 1;\nfind_one(\"Missing\");"));
+        assert_eq!(line, 2);
+        assert!(matches!(error, Error::WindowNotFound), "{error:?}");
+
+        let (_, error) = failure(engine.run(r#"find("Edit[")"#));
+        assert!(matches!(error, Error::InvalidSelector(_)), "{error:?}");
+
+        let (_, error) = failure(engine.run(r#"find(selector("Notepad").with_pid(-1))"#));
+        assert!(matches!(error, Error::InvalidSelector(_)), "{error:?}");
+
+        let (_, error) = failure(engine.run(r#"find_one("Notepad").key("Ctrl+Nope")"#));
+        assert!(matches!(error, Error::InvalidHotkey(_)), "{error:?}");
+
+        // 脚本捕获的错误不影响之后的错误
+        let (_, error) = failure(engine.run(
+            r#"try { find_one("Missing") } catch { }
+               throw "自定义错误";"#,
+        ));
+        assert!(
+            matches!(&error, Error::Rhai(m) if m == "自定义错误"),
+            "{error:?}"
+        );
+
+        assert!(matches!(
+            engine.run(r#"eval("1")"#),
+            Err(Error::ScriptSyntax { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn sandbox() {
+        let mut engine = RhaiEngine::new(desktop());
+
+        assert!(engine.run(r#"import "x" as y;"#).is_err());
+
+        // 磁盘上存在的模块也不能导入
+        let module = std::env::temp_dir().join("winpoke_sandbox.rhai");
+        std::fs::write(&module, "export const X = 1;").unwrap();
+        let path = module.with_extension("").display().to_string();
+        let result = engine.run(&format!("import {path:?} as m; m::X"));
+        std::fs::remove_file(module).unwrap();
+        assert!(result.is_err());
+        assert!(engine.run("loop {}").is_err());
+        assert!(engine.run("fn f(n) { f(n + 1) } f(0)").is_err());
+        assert!(engine.run(r#"let s = "x"; loop { s += s; }"#).is_err());
+    }
+
+    #[test]
+    fn example_script() {
+        let desktop = FakeDesktop::new().with_window(
+            FakeWindow::new("Notepad", "无标题 - 记事本")
+                .with_child(FakeWindow::new("RichEditD2DPT", "")),
+        );
+        let mut engine = RhaiEngine::new(DryRun::new(desktop)).with_variable("name", "poke");
+
+        // 假桌面上没有保存对话框
+        let (line, error) = failure(engine.run(include_str!("../../examples/notepad.rhai")));
+        assert_eq!(line, 20);
+        assert!(matches!(error, Error::WindowNotFound), "{error:?}");
+        assert_eq!(engine.backend().actions().len(), 5);
+    }
+
+    #[test]
+    fn dry_run() {
+        let mut engine = RhaiEngine::new(DryRun::new(desktop()));
+        engine
+            .run(r#"let e = find_one("* > Edit"); e.type_text("x"); sleep(1000); e.click(1, 2);"#)
+            .unwrap();
+
+        assert_eq!(
+            engine.backend().actions(),
+            [r#"输入 Edit "x""#, "单击 Edit (1, 2)"]
+        );
+    }
+}
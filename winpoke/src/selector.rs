@@ -0,0 +1,366 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::error::Error;
+#[cfg(windows)]
+use crate::window::WindowInfo;
+
+/// 窗口选择器
+///
+/// 按类名、标题和进程ID匹配窗口，未设置的条件匹配任意值。
+///
+/// 文本形式为 `类名[caption="标题"][pid=1234]`，类名写作 `*` 时匹配任意类名，例如：
+///
+/// ```text
+/// RegEdit_RegEdit
+/// *[pid=1234]
+/// #32770[caption="注册表编辑器"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Selector {
+    /// 窗口类名
+    pub class_name: Option<String>,
+
+    /// 窗口标题
+    pub caption: Option<String>,
+
+    /// 进程ID
+    pub pid: Option<u32>,
+}
+
+impl Selector {
+    /// 匹配任意窗口的选择器
+    pub fn any() -> Self {
+        Self::default()
+    }
+
+    /// 按类名匹配的选择器
+    pub fn class_name(class_name: impl Into<String>) -> Self {
+        Self {
+            class_name: Some(class_name.into()),
+            ..Default::default()
+        }
+    }
+
+    /// 按进程ID匹配的选择器
+    pub fn pid(pid: u32) -> Self {
+        Self {
+            pid: Some(pid),
+            ..Default::default()
+        }
+    }
+
+    /// 追加标题条件
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+
+    /// 追加进程ID条件
+    pub fn with_pid(mut self, pid: u32) -> Self {
+        self.pid = Some(pid);
+        self
+    }
+
+    /// 判断窗口是否匹配
+    #[cfg(windows)]
+    pub fn matches(&self, info: &WindowInfo) -> bool {
+        self.matches_parts(&info.class_name, &info.caption, info.pid)
+    }
+
+    /// 按窗口的各项属性判断是否匹配
+    pub fn matches_parts(&self, class_name: &str, caption: &str, pid: u32) -> bool {
+        self.class_name.as_deref().is_none_or(|c| c == class_name)
+            && self.caption.as_deref().is_none_or(|c| c == caption)
+            && self.pid.is_none_or(|p| p == pid)
+    }
+}
+
+impl Display for Selector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.class_name.as_deref().unwrap_or("*"))?;
+
+        if let Some(caption) = &self.caption {
+            write!(f, "[caption=\"{}\"]", escape(caption))?;
+        }
+        if let Some(pid) = self.pid {
+            write!(f, "[pid={pid}]")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Selector {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| Error::InvalidSelector(format!("{s}: {reason}"));
+
+        let trimmed = s.trim();
+        let (class_name, mut rest) = match trimmed.find('[') {
+            Some(index) => trimmed.split_at(index),
+            None => (trimmed, ""),
+        };
+
+        let class_name = class_name.trim();
+        let mut selector = Selector {
+            class_name: match class_name {
+                "" => return Err(invalid("缺少类名")),
+                "*" => None,
+                name => Some(name.to_string()),
+            },
+            ..Default::default()
+        };
+
+        while !rest.is_empty() {
+            let body = rest
+                .strip_prefix('[')
+                .ok_or_else(|| invalid("条件必须以 [ 开头"))?;
+            let (key, value, remain) =
+                split_attribute(body).ok_or_else(|| invalid("条件格式错误"))?;
+
+            match key {
+                "caption" => selector.caption = Some(value),
+                "pid" => selector.pid = Some(value.parse().map_err(|_| invalid("pid 必须为整数"))?),
+                _ => return Err(invalid("未知的条件")),
+            }
+
+            rest = remain.trim_start();
+        }
+
+        Ok(selector)
+    }
+}
+
+/// 由父到子逐级匹配的选择器路径
+///
+/// 文本形式为以 `>` 分隔的选择器，第一级匹配顶层窗口，之后每一级匹配上一级窗口的直接子窗口：
+///
+/// ```text
+/// RegEdit_RegEdit > SysTreeView32
+/// #32770[caption="另存为"] > ComboBoxEx32 > ComboBox > Edit
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SelectorPath(pub Vec<Selector>);
+
+impl SelectorPath {
+    /// 按各级窗口的(类名,标题)生成选择器路径
+    ///
+    /// 顶层窗口总是带上标题；子窗口只在标题不为空且不是用户输入的内容（如编辑框）时带上标题。
+    pub fn from_parts<'a>(parts: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let selectors = parts
+            .into_iter()
+            .enumerate()
+            .map(|(depth, (class_name, caption))| {
+                let selector = Selector::class_name(class_name);
+                if !caption.is_empty() && (depth == 0 || !is_input_class(class_name)) {
+                    selector.with_caption(caption)
+                } else {
+                    selector
+                }
+            })
+            .collect();
+
+        Self(selectors)
+    }
+}
+
+/// 标题即用户输入内容的控件类
+fn is_input_class(class_name: &str) -> bool {
+    const INPUT_CLASSES: &[&str] = &["Edit", "ComboBox", "ComboBoxEx32", "Scintilla"];
+
+    INPUT_CLASSES
+        .iter()
+        .any(|c| c.eq_ignore_ascii_case(class_name))
+        || class_name.to_ascii_lowercase().starts_with("richedit")
+}
+
+impl From<Selector> for SelectorPath {
+    fn from(selector: Selector) -> Self {
+        Self(vec![selector])
+    }
+}
+
+impl Display for SelectorPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, selector) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, " > ")?;
+            }
+            write!(f, "{selector}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for SelectorPath {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Err(Error::InvalidSelector(format!("{s}: 路径为空")));
+        }
+
+        split_path(s)
+            .into_iter()
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+/// 按 `>` 拆分选择器路径，忽略引号和方括号中的 `>`
+fn split_path(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut in_brackets = false;
+    let mut in_quotes = false;
+    let mut chars = s.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' if in_quotes => {
+                chars.next();
+            }
+            '"' if in_brackets => in_quotes = !in_quotes,
+            '[' if !in_quotes => in_brackets = true,
+            ']' if !in_quotes => in_brackets = false,
+            '>' if !in_brackets => {
+                parts.push(&s[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+
+    parts
+}
+
+/// 拆分 `key=value]...`，返回键、值和剩余部分
+fn split_attribute(body: &str) -> Option<(&str, String, &str)> {
+    let (key, value) = body.split_once('=')?;
+    let key = key.trim();
+    let value = value.trim_start();
+
+    match value.strip_prefix('"') {
+        Some(quoted) => {
+            let mut unescaped = String::new();
+            let mut chars = quoted.char_indices();
+
+            while let Some((index, c)) = chars.next() {
+                match c {
+                    '\\' => unescaped.push(chars.next()?.1),
+                    '"' => {
+                        let remain = quoted[index + 1..].trim_start().strip_prefix(']')?;
+                        return Some((key, unescaped, remain));
+                    }
+                    c => unescaped.push(c),
+                }
+            }
+
+            None
+        }
+        None => {
+            let (value, remain) = value.split_once(']')?;
+            Some((key, value.trim().to_string(), remain))
+        }
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_class_name() {
+        let selector: Selector = "RegEdit_RegEdit".parse().unwrap();
+        assert_eq!(selector, Selector::class_name("RegEdit_RegEdit"));
+    }
+
+    #[test]
+    fn parse_attributes() {
+        let selector: Selector = r#"#32770[caption="注册表 \"编辑器\""][pid=42]"#.parse().unwrap();
+        assert_eq!(
+            selector,
+            Selector::class_name("#32770")
+                .with_caption("注册表 \"编辑器\"")
+                .with_pid(42)
+        );
+    }
+
+    #[test]
+    fn parse_any_class() {
+        let selector: Selector = "*[pid=7]".parse().unwrap();
+        assert_eq!(selector, Selector::pid(7));
+    }
+
+    #[test]
+    fn parse_errors() {
+        for s in [
+            "",
+            "[pid=1]",
+            "Edit[pid=x]",
+            "Edit[title=a]",
+            "Edit[caption=\"a]",
+        ] {
+            assert!(s.parse::<Selector>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let selector = Selector::class_name("Edit")
+            .with_caption(r#"a\b"c"#)
+            .with_pid(3);
+        assert_eq!(selector.to_string().parse::<Selector>().unwrap(), selector);
+        assert_eq!(Selector::any().to_string(), "*");
+    }
+
+    #[test]
+    fn parse_path() {
+        let path: SelectorPath = r#"#32770[caption="a > b \" >"] > ComboBox>Edit"#.parse().unwrap();
+        assert_eq!(
+            path,
+            SelectorPath(vec![
+                Selector::class_name("#32770").with_caption(r#"a > b " >"#),
+                Selector::class_name("ComboBox"),
+                Selector::class_name("Edit"),
+            ])
+        );
+        assert_eq!(path.to_string().parse::<SelectorPath>().unwrap(), path);
+
+        for s in ["", " ", "Edit >", "> Edit", "A >> B"] {
+            assert!(s.parse::<SelectorPath>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn path_from_parts() {
+        let path = SelectorPath::from_parts([
+            ("Notepad", "无标题 - 记事本"),
+            ("RichEditD2DPT", "hello"),
+            ("Button", "确定"),
+            ("Static", ""),
+        ]);
+        assert_eq!(
+            path.to_string(),
+            r#"Notepad[caption="无标题 - 记事本"] > RichEditD2DPT > Button[caption="确定"] > Static"#
+        );
+    }
+
+    #[test]
+    fn matches_parts() {
+        let selector = Selector::class_name("Edit").with_pid(3);
+        assert!(selector.matches_parts("Edit", "任意标题", 3));
+        assert!(!selector.matches_parts("Edit", "", 4));
+        assert!(!selector.matches_parts("Button", "", 3));
+        assert!(Selector::any().matches_parts("Button", "", 3));
+    }
+}
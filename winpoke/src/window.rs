@@ -1,16 +1,30 @@
 pub mod active;
+pub mod event;
+mod highlight;
 pub(crate) mod info;
+pub(crate) mod message_loop;
+pub mod mouse;
 pub mod msg;
 pub(crate) mod style;
 
-use windows::Win32::Foundation::HWND;
+use std::time::Duration;
 
+use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+
+use crate::color::Color;
+use crate::error::Error;
+use crate::input::send_input_seq;
 use crate::prelude::Result;
-use crate::window::active::{open_process, set_focus, show_window, wait_for_input_idle};
-use crate::window::msg::{Message, send_message_seq};
+use crate::selector::{Selector, SelectorPath};
+use crate::window::active::{ProcessHandle, set_focus, show_window, wait_for_input_idle};
+use crate::window::highlight::highlight_rect;
+use crate::window::msg::{Delivery, Message, post_message, send_message_seq, send_message_timeout};
 use crate::window::style::WindowStyle;
 use info::*;
 
+/// 跨进程发送消息时默认的超时时间（毫秒）
+pub const DEFAULT_MESSAGE_TIMEOUT: u32 = 1000;
+
 #[derive(Debug, Default)]
 pub struct WindowInfo {
     /// 窗口句柄
@@ -57,6 +71,96 @@ impl WindowInfo {
         Ok(infos)
     }
 
+    /// 查找匹配选择器的**顶层**窗口
+    pub fn find(selector: &Selector) -> Result<Vec<Self>> {
+        let infos: Vec<WindowInfo> = enumerate_top_level_windows()?
+            .into_iter()
+            .flat_map(get_window_info)
+            .filter(|info| selector.matches(info))
+            .collect();
+
+        Ok(infos)
+    }
+
+    /// 屏幕坐标处最深层的可见窗口（通常是控件）
+    pub fn from_point(x: i32, y: i32) -> Result<Self> {
+        get_window_info(window_from_point(x, y)?)
+    }
+
+    /// 鼠标光标下最深层的可见窗口
+    pub fn from_cursor() -> Result<Self> {
+        let (x, y) = cursor_position()?;
+        Self::from_point(x, y)
+    }
+
+    /// 读取窗口句柄对应的窗口信息，句柄无效时返回错误
+    pub fn from_hwnd(hwnd: HWND) -> Result<Self> {
+        get_window_info(hwnd)
+    }
+
+    /// 按选择器路径逐级查找窗口，第一级匹配顶层窗口
+    pub fn find_path(path: &SelectorPath) -> Result<Vec<Self>> {
+        let Some((first, rest)) = path.0.split_first() else {
+            return Ok(Vec::new());
+        };
+
+        let mut current = Self::find(first)?;
+        for selector in rest {
+            let mut next = Vec::new();
+            for window in &current {
+                match window.get_child_windows() {
+                    Ok(children) => {
+                        next.extend(children.into_iter().filter(|child| selector.matches(child)))
+                    }
+                    Err(Error::WindowNotFound) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            current = next;
+        }
+
+        Ok(current)
+    }
+
+    /// 父窗口，顶层窗口返回 `None`
+    pub fn parent(&self) -> Result<Option<Self>> {
+        get_parent_window(self.hwnd)
+            .map(get_window_info)
+            .transpose()
+    }
+
+    /// 从顶层窗口到当前窗口（包含）的各级窗口
+    pub fn hierarchy(&self) -> Result<Vec<Self>> {
+        let mut windows = vec![get_window_info(self.hwnd)?];
+        while let Some(parent) = windows.last().unwrap().parent()? {
+            windows.push(parent);
+        }
+        windows.reverse();
+
+        Ok(windows)
+    }
+
+    /// 可以用 [`find_path`](Self::find_path) 重新定位到当前窗口的选择器路径
+    ///
+    /// 同级有多个相同类名和标题的窗口时路径不唯一，查找结果会包含多个窗口。
+    pub fn selector_path(&self) -> Result<SelectorPath> {
+        let hierarchy = self.hierarchy()?;
+
+        Ok(SelectorPath::from_parts(
+            hierarchy
+                .iter()
+                .map(|w| (w.class_name.as_str(), w.caption.as_str())),
+        ))
+    }
+
+    /// 在窗口外侧绘制高亮框，阻塞 `duration` 后移除
+    ///
+    /// 用于确认选择器匹配到的是哪个窗口。高亮框按查找时记录的 [`position`](Self::position)
+    /// 绘制，窗口之后移动过时需要重新查找。窗口大小为零时返回 [`Error::EmptyWindow`]。
+    pub fn highlight(&self, duration: Duration, color: Color) -> Result<()> {
+        highlight_rect(self.position, duration, color)
+    }
+
     /// 获取一级子窗口
     pub fn get_child_windows(&self) -> Result<Vec<WindowInfo>> {
         let infos: Vec<WindowInfo> = enum_child_window(self.hwnd)?
@@ -79,6 +183,37 @@ impl WindowInfo {
         Ok(infos)
     }
 
+    /// 读取窗口文本
+    ///
+    /// 通过 `WM_GETTEXT` 获取，可以读取其它进程中编辑框等控件的内容；
+    /// 而 [`caption`](Self::caption) 只是枚举时获取的窗口标题。
+    pub fn text(&self) -> Result<String> {
+        self.text_with_timeout(DEFAULT_MESSAGE_TIMEOUT)
+    }
+
+    /// 读取窗口文本，目标窗口在 `timeout` 毫秒内未响应时返回超时错误
+    pub fn text_with_timeout(&self, timeout: u32) -> Result<String> {
+        get_window_text(self.hwnd, timeout)
+    }
+
+    /// 发送消息并返回处理结果，目标窗口未在默认超时时间内响应时返回错误
+    pub(crate) fn send_raw(&self, msg: u32, wparam: usize, lparam: isize) -> Result<usize> {
+        send_message_timeout(
+            self.hwnd,
+            msg,
+            WPARAM(wparam),
+            LPARAM(lparam),
+            DEFAULT_MESSAGE_TIMEOUT,
+        )
+    }
+
+    /// 投递消息，不等待处理结果
+    ///
+    /// 用于可能弹出模态对话框的操作（如点击按钮），此时发送消息会一直阻塞到对话框关闭。
+    pub(crate) fn post_raw(&self, msg: u32, wparam: usize, lparam: isize) -> Result<()> {
+        post_message(self.hwnd, msg, WPARAM(wparam), LPARAM(lparam))
+    }
+
     /// 显示窗口
     pub fn show_window(&self) -> Result<()> {
         show_window(self.hwnd)
@@ -91,21 +226,43 @@ impl WindowInfo {
 
     /// 发送消息到窗口
     pub fn send_message_seq(&self, msg_seq: Vec<Message>) -> Result<()> {
-        wait_for_input_idle(open_process(self.pid)?, 500)?;
+        self.wait_for_idle()?;
 
         send_message_seq(self.hwnd, msg_seq)?;
 
         Ok(())
     }
 
+    /// 按指定的投递方式发送消息序列
+    ///
+    /// 使用 [`Delivery::Input`] 时输入会发送到前台窗口，调用前需要先 [`set_focus`](Self::set_focus)。
+    pub fn send_message_seq_with(&self, msg_seq: Vec<Message>, delivery: Delivery) -> Result<()> {
+        match delivery {
+            Delivery::Message => self.send_message_seq(msg_seq),
+            Delivery::Input => {
+                self.wait_for_idle()?;
+
+                send_input_seq(self.hwnd, msg_seq)
+            }
+        }
+    }
+
     /// 发送消息到窗口
     pub fn send_message(&self, msg: Message) -> Result<()> {
-        wait_for_input_idle(open_process(self.pid)?, 500)?;
+        self.wait_for_idle()?;
 
         send_message_seq(self.hwnd, vec![msg])?;
 
         Ok(())
     }
+
+    /// 等待窗口所属的进程处理完已有的输入，最多等待 500 毫秒
+    fn wait_for_idle(&self) -> Result<()> {
+        let process = ProcessHandle::open(self.pid)?;
+        wait_for_input_idle(*process, 500)?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -118,6 +275,15 @@ mod tests {
         dbg!(WindowInfo::find_by_class_name("RegEdit_RegEdit"));
     }
 
+    #[test]
+    fn test_text() {
+        let windows = WindowInfo::find_by_class_name("RegEdit_RegEdit").unwrap();
+        for window in windows {
+            let text = window.text().unwrap();
+            assert_eq!(window.caption, text);
+        }
+    }
+
     #[test]
     fn test_get_child_windows() {
         let windows = WindowInfo::find_by_class_name("RegEdit_RegEdit").unwrap();
@@ -3,19 +3,21 @@ use windows::Win32::System::Threading::WaitForInputIdle;
 use windows::Win32::UI::Input::KeyboardAndMouse::SetFocus;
 use windows::Win32::UI::WindowsAndMessaging::{SW_SHOW, SetForegroundWindow, ShowWindow};
 use windows::Win32::{
-    Foundation::HANDLE,
+    Foundation::{CloseHandle, HANDLE},
     System::Threading::{
         CreateProcessW, OpenProcess, PROCESS_ALL_ACCESS, PROCESS_CREATION_FLAGS,
         PROCESS_INFORMATION, STARTUPINFOW,
     },
 };
-use windows::core::{HSTRING, PWSTR};
+use windows::core::PWSTR;
 
 use crate::error::Error;
 use crate::prelude::Result;
 
+/// 启动进程，返回进程句柄，由调用方关闭
 pub fn create_process(cmd: impl AsRef<str>) -> Result<HANDLE> {
-    let regedit_path = HSTRING::from(cmd.as_ref());
+    // CreateProcessW 可能改写命令行，需要传入可写的缓冲区
+    let mut command_line: Vec<u16> = cmd.as_ref().encode_utf16().chain(Some(0)).collect();
 
     let startup_info = STARTUPINFOW {
         cb: std::mem::size_of::<STARTUPINFOW>() as u32,
@@ -26,7 +28,7 @@ pub fn create_process(cmd: impl AsRef<str>) -> Result<HANDLE> {
     unsafe {
         CreateProcessW(
             None,
-            Some(PWSTR(regedit_path.as_ptr() as *mut _)),
+            Some(PWSTR(command_line.as_mut_ptr())),
             None,
             None,
             false,
@@ -37,6 +39,7 @@ pub fn create_process(cmd: impl AsRef<str>) -> Result<HANDLE> {
             &mut process_info,
         )?
     };
+    let _ = unsafe { CloseHandle(process_info.hThread) };
 
     Ok(process_info.hProcess)
 }
@@ -47,6 +50,33 @@ pub fn open_process(pid: u32) -> Result<HANDLE> {
     Ok(handle)
 }
 
+/// 进程句柄，释放时关闭
+pub(crate) struct ProcessHandle(HANDLE);
+
+impl ProcessHandle {
+    pub(crate) fn open(pid: u32) -> Result<Self> {
+        Ok(Self(open_process(pid)?))
+    }
+}
+
+impl std::ops::Deref for ProcessHandle {
+    type Target = HANDLE;
+
+    fn deref(&self) -> &HANDLE {
+        &self.0
+    }
+}
+
+impl Drop for ProcessHandle {
+    fn drop(&mut self) {
+        let _ = unsafe { CloseHandle(self.0) };
+    }
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(hwnd = ?hwnd.0), err)
+)]
 pub(crate) fn set_focus(hwnd: HWND) -> Result<()> {
     unsafe { SetForegroundWindow(hwnd) }
         .as_bool()
@@ -58,6 +88,10 @@ pub(crate) fn set_focus(hwnd: HWND) -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(hwnd = ?hwnd.0), err)
+)]
 pub(crate) fn show_window(hwnd: HWND) -> Result<()> {
     unsafe { ShowWindow(hwnd, SW_SHOW) }
         .as_bool()
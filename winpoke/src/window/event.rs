@@ -0,0 +1,376 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::Accessibility::{HWINEVENTHOOK, SetWinEventHook, UnhookWinEvent};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CHILDID_SELF, EVENT_OBJECT_CREATE, EVENT_OBJECT_DESTROY, EVENT_OBJECT_FOCUS, EVENT_OBJECT_HIDE,
+    EVENT_OBJECT_LOCATIONCHANGE, EVENT_OBJECT_NAMECHANGE, EVENT_OBJECT_SHOW,
+    EVENT_SYSTEM_FOREGROUND, MSG, OBJID_CLIENT, OBJID_WINDOW, WINEVENT_OUTOFCONTEXT,
+    WINEVENT_SKIPOWNPROCESS,
+};
+
+use crate::error::Error;
+use crate::prelude::Result;
+use crate::selector::Selector;
+use crate::window::WindowInfo;
+use crate::window::info::{
+    get_window_caption, get_window_class_name, get_window_info, get_window_tid_and_pid,
+};
+use crate::window::message_loop::MessageLoop;
+
+/// 窗口事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WindowEventKind {
+    /// 窗口已创建
+    Created,
+    /// 窗口已销毁
+    Destroyed,
+    /// 窗口已显示
+    Shown,
+    /// 窗口已隐藏
+    Hidden,
+    /// 前台窗口已切换
+    ForegroundChanged,
+    /// 窗口标题已修改
+    NameChanged,
+    /// 窗口位置或大小已修改
+    LocationChanged,
+    /// 键盘焦点已切换
+    FocusChanged,
+}
+
+/// 窗口事件
+#[derive(Debug, Clone)]
+pub struct WindowEvent {
+    /// 事件类型
+    pub kind: WindowEventKind,
+
+    /// 产生事件的窗口句柄
+    pub hwnd: HWND,
+
+    /// 产生事件的线程ID
+    pub tid: u32,
+
+    /// 事件发生的时间（系统启动后的毫秒数）
+    pub time: u32,
+}
+
+// 窗口句柄只是系统范围内的标识，可以在线程间传递
+unsafe impl Send for WindowEvent {}
+
+impl WindowEvent {
+    /// 获取产生事件的窗口的信息，窗口已销毁时返回错误
+    pub fn window_info(&self) -> Result<WindowInfo> {
+        get_window_info(self.hwnd)
+    }
+}
+
+/// 将 `SetWinEventHook` 回调的参数解码为窗口事件类型
+///
+/// 只保留窗口本身的事件，光标、滚动条等子对象的事件返回 `None`。
+pub(crate) fn decode_event(event: u32, id_object: i32, id_child: i32) -> Option<WindowEventKind> {
+    if id_child != CHILDID_SELF as i32 {
+        return None;
+    }
+
+    let is_window = id_object == OBJID_WINDOW.0;
+
+    match event {
+        EVENT_SYSTEM_FOREGROUND if is_window => Some(WindowEventKind::ForegroundChanged),
+        EVENT_OBJECT_CREATE if is_window => Some(WindowEventKind::Created),
+        EVENT_OBJECT_DESTROY if is_window => Some(WindowEventKind::Destroyed),
+        EVENT_OBJECT_SHOW if is_window => Some(WindowEventKind::Shown),
+        EVENT_OBJECT_HIDE if is_window => Some(WindowEventKind::Hidden),
+        EVENT_OBJECT_NAMECHANGE if is_window => Some(WindowEventKind::NameChanged),
+        EVENT_OBJECT_LOCATIONCHANGE if is_window => Some(WindowEventKind::LocationChanged),
+        // 焦点事件通常由控件的工作区对象发出
+        EVENT_OBJECT_FOCUS if is_window || id_object == OBJID_CLIENT.0 => {
+            Some(WindowEventKind::FocusChanged)
+        }
+        _ => None,
+    }
+}
+
+/// 事件过滤器，记录已匹配过的窗口
+///
+/// 窗口销毁后无法再读取类名和标题，因此销毁事件按之前是否匹配过来判断。
+/// 只按进程筛选时钩子本身只收到该进程的事件，销毁事件总是接受，
+/// 订阅前就已存在的窗口销毁时也能报告。
+struct EventFilter {
+    selector: Selector,
+    matched: HashSet<isize>,
+}
+
+impl EventFilter {
+    fn accept(&mut self, kind: WindowEventKind, hwnd: HWND) -> bool {
+        if kind == WindowEventKind::Destroyed {
+            let pid_only = self.selector.class_name.is_none() && self.selector.caption.is_none();
+            return self.matched.remove(&(hwnd.0 as isize)) || pid_only;
+        }
+
+        let matched = get_window_tid_and_pid(hwnd).is_ok_and(|(_, pid)| {
+            self.selector.pid.is_none_or(|p| p == pid)
+                && self
+                    .selector
+                    .class_name
+                    .as_deref()
+                    .is_none_or(|class_name| {
+                        get_window_class_name(hwnd).is_ok_and(|name| name == class_name)
+                    })
+                && self.selector.caption.as_deref().is_none_or(|caption| {
+                    get_window_caption(hwnd).is_ok_and(|text| text == caption)
+                })
+        });
+
+        if matched {
+            self.matched.insert(hwnd.0 as isize);
+        }
+
+        matched
+    }
+}
+
+/// 事件钩子线程的上下文
+///
+/// `WINEVENTPROC` 没有用户数据参数，而使用 `WINEVENT_OUTOFCONTEXT` 时回调总是在
+/// 注册钩子的线程上执行，因此上下文保存在该线程的线程局部变量中。
+struct HookContext {
+    filter: EventFilter,
+    sender: Sender<WindowEvent>,
+}
+
+thread_local! {
+    static HOOK_CONTEXT: RefCell<Option<HookContext>> = const { RefCell::new(None) };
+}
+
+/// 在钩子线程上注销事件钩子并清除上下文
+struct HookGuard(Vec<HWINEVENTHOOK>);
+
+impl Drop for HookGuard {
+    fn drop(&mut self) {
+        for hook in &self.0 {
+            let _ = unsafe { UnhookWinEvent(*hook) };
+        }
+
+        HOOK_CONTEXT.with_borrow_mut(|context| *context = None);
+    }
+}
+
+/// 事件钩子回调（unsafe：C调用约定）
+unsafe extern "system" fn win_event_proc(
+    _hook: HWINEVENTHOOK,
+    event: u32,
+    hwnd: HWND,
+    id_object: i32,
+    id_child: i32,
+    event_thread: u32,
+    event_time: u32,
+) {
+    let Some(kind) = decode_event(event, id_object, id_child) else {
+        return;
+    };
+
+    HOOK_CONTEXT.with_borrow_mut(|context| {
+        let Some(context) = context else {
+            return;
+        };
+
+        if context.filter.accept(kind, hwnd) {
+            let _ = context.sender.send(WindowEvent {
+                kind,
+                hwnd,
+                tid: event_thread,
+                time: event_time,
+            });
+        }
+    });
+}
+
+/// 窗口事件订阅
+///
+/// 在后台线程中通过 `SetWinEventHook` 监听窗口事件，匹配选择器的事件通过通道送出。
+/// 可以当作阻塞迭代器使用，`Drop` 时注销钩子并结束后台线程。
+///
+/// ```no_run
+/// use winpoke::selector::Selector;
+/// use winpoke::window::event::{EventSubscription, WindowEventKind};
+///
+/// let events = EventSubscription::new(Selector::class_name("RegEdit_RegEdit")).unwrap();
+/// for event in events {
+///     if event.kind == WindowEventKind::Created {
+///         println!("注册表编辑器已启动: {:?}", event.hwnd);
+///         break;
+///     }
+/// }
+/// ```
+pub struct EventSubscription {
+    receiver: Receiver<WindowEvent>,
+    _message_loop: MessageLoop,
+}
+
+impl EventSubscription {
+    /// 订阅匹配选择器的窗口的事件
+    ///
+    /// 选择器指定了 `pid` 时由系统只投递该进程的事件，开销更小。
+    pub fn new(selector: Selector) -> Result<Self> {
+        let (sender, receiver) = mpsc::channel();
+
+        let message_loop = MessageLoop::spawn(move || {
+            let pid = selector.pid.unwrap_or(0);
+
+            HOOK_CONTEXT.with_borrow_mut(|context| {
+                *context = Some(HookContext {
+                    filter: EventFilter {
+                        selector,
+                        matched: HashSet::new(),
+                    },
+                    sender,
+                })
+            });
+
+            let mut guard = HookGuard(Vec::new());
+            for (min, max) in [
+                (EVENT_SYSTEM_FOREGROUND, EVENT_SYSTEM_FOREGROUND),
+                (EVENT_OBJECT_CREATE, EVENT_OBJECT_NAMECHANGE),
+            ] {
+                let hook = unsafe {
+                    SetWinEventHook(
+                        min,
+                        max,
+                        None,
+                        Some(win_event_proc),
+                        pid,
+                        0,
+                        WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
+                    )
+                };
+                if hook.is_invalid() {
+                    return Err(Error::SetWinEventHookFailed);
+                }
+                guard.0.push(hook);
+            }
+
+            // 处理函数持有钩子，消息循环退出时随之注销
+            Ok(move |_: &MSG| {
+                let _ = &guard;
+            })
+        })?;
+
+        Ok(Self {
+            receiver,
+            _message_loop: message_loop,
+        })
+    }
+
+    /// 订阅指定进程的所有窗口事件
+    pub fn for_pid(pid: u32) -> Result<Self> {
+        Self::new(Selector::pid(pid))
+    }
+
+    /// 阻塞等待下一个事件
+    pub fn recv(&self) -> Option<WindowEvent> {
+        self.receiver.recv().ok()
+    }
+
+    /// 等待下一个事件，超时返回 `None`
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<WindowEvent> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+
+    /// 获取已到达的事件，不阻塞
+    pub fn try_recv(&self) -> Option<WindowEvent> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+impl Iterator for EventSubscription {
+    type Item = WindowEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.recv()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use windows::Win32::UI::WindowsAndMessaging::{EVENT_OBJECT_VALUECHANGE, OBJID_CARET};
+
+    #[test]
+    fn decode_window_events() {
+        let cases = [
+            (EVENT_OBJECT_CREATE, WindowEventKind::Created),
+            (EVENT_OBJECT_DESTROY, WindowEventKind::Destroyed),
+            (EVENT_OBJECT_SHOW, WindowEventKind::Shown),
+            (EVENT_OBJECT_HIDE, WindowEventKind::Hidden),
+            (EVENT_SYSTEM_FOREGROUND, WindowEventKind::ForegroundChanged),
+            (EVENT_OBJECT_NAMECHANGE, WindowEventKind::NameChanged),
+            (
+                EVENT_OBJECT_LOCATIONCHANGE,
+                WindowEventKind::LocationChanged,
+            ),
+            (EVENT_OBJECT_FOCUS, WindowEventKind::FocusChanged),
+        ];
+
+        for (event, kind) in cases {
+            assert_eq!(decode_event(event, OBJID_WINDOW.0, 0), Some(kind));
+        }
+    }
+
+    #[test]
+    fn decode_ignores_child_objects() {
+        assert_eq!(decode_event(EVENT_OBJECT_CREATE, OBJID_WINDOW.0, 3), None);
+        assert_eq!(
+            decode_event(EVENT_OBJECT_LOCATIONCHANGE, OBJID_CARET.0, 0),
+            None
+        );
+        assert_eq!(decode_event(EVENT_OBJECT_SHOW, OBJID_CLIENT.0, 0), None);
+        assert_eq!(
+            decode_event(EVENT_OBJECT_VALUECHANGE, OBJID_WINDOW.0, 0),
+            None
+        );
+    }
+
+    #[test]
+    fn decode_client_focus() {
+        assert_eq!(
+            decode_event(EVENT_OBJECT_FOCUS, OBJID_CLIENT.0, 0),
+            Some(WindowEventKind::FocusChanged)
+        );
+    }
+
+    #[test]
+    fn destroyed_before_subscription() {
+        // 订阅前已存在的窗口没有经过其它事件，从未记录为匹配过
+        let existing = HWND(0x1234 as _);
+
+        let mut by_pid = EventFilter {
+            selector: Selector::pid(42),
+            matched: HashSet::new(),
+        };
+        assert!(by_pid.accept(WindowEventKind::Destroyed, existing));
+
+        let mut by_class = EventFilter {
+            selector: Selector::class_name("Notepad").with_pid(42),
+            matched: HashSet::new(),
+        };
+        assert!(!by_class.accept(WindowEventKind::Destroyed, existing));
+
+        by_class.matched.insert(existing.0 as isize);
+        assert!(by_class.accept(WindowEventKind::Destroyed, existing));
+        assert!(by_class.matched.is_empty());
+    }
+
+    #[test]
+    fn test_subscribe_regedit() {
+        let events = EventSubscription::new(Selector::class_name("RegEdit_RegEdit"))
+            .expect("订阅窗口事件失败");
+
+        while let Some(event) = events.recv_timeout(Duration::from_secs(1)) {
+            println!("{event:?}");
+        }
+    }
+}
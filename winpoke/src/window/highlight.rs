@@ -0,0 +1,161 @@
+use std::sync::OnceLock;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use windows::Win32::Foundation::{COLORREF, HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+    CombineRgn, CreateRectRgn, CreateSolidBrush, DeleteObject, FillRect, HBRUSH, HDC, RGN_DIFF,
+    SetWindowRgn,
+};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GWLP_USERDATA, GetClientRect,
+    GetWindowLongPtrW, LWA_ALPHA, MSG, PM_REMOVE, PeekMessageW, RegisterClassW, SW_SHOWNOACTIVATE,
+    SetLayeredWindowAttributes, SetWindowLongPtrW, ShowWindow, TranslateMessage, WM_ERASEBKGND,
+    WNDCLASSW, WS_EX_LAYERED, WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_EX_TRANSPARENT,
+    WS_POPUP,
+};
+use windows::core::{PCWSTR, w};
+
+use crate::color::Color;
+use crate::error::Error;
+use crate::prelude::Result;
+
+/// 高亮框窗口的类名
+const HIGHLIGHT_CLASS: PCWSTR = w!("WinpokeHighlight");
+
+/// 高亮框的线宽（像素）
+const BORDER_WIDTH: i32 = 3;
+
+/// 高亮框的不透明度
+const ALPHA: u8 = 220;
+
+/// 显示期间泵送消息的间隔
+const PUMP_INTERVAL: Duration = Duration::from_millis(15);
+
+/// 注册高亮框的窗口类，整个进程只注册一次
+fn register_class() -> Result<()> {
+    static REGISTERED: OnceLock<windows::core::Result<()>> = OnceLock::new();
+
+    let registered = REGISTERED.get_or_init(|| {
+        let instance = unsafe { GetModuleHandleW(None) }?;
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(wnd_proc),
+            hInstance: instance.into(),
+            lpszClassName: HIGHLIGHT_CLASS,
+            ..Default::default()
+        };
+
+        match unsafe { RegisterClassW(&class) } {
+            0 => Err(windows::core::Error::from_win32()),
+            _ => Ok(()),
+        }
+    });
+
+    registered.clone().map_err(Into::into)
+}
+
+/// 用 `GWLP_USERDATA` 中保存的画刷填充窗口，窗口区域已裁成边框
+extern "system" fn wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if msg == WM_ERASEBKGND {
+        let brush = HBRUSH(unsafe { GetWindowLongPtrW(hwnd, GWLP_USERDATA) } as _);
+        let mut rect = RECT::default();
+        unsafe {
+            let _ = GetClientRect(hwnd, &mut rect);
+            FillRect(HDC(wparam.0 as _), &rect, brush);
+        }
+
+        return LRESULT(1);
+    }
+
+    unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+}
+
+/// 在屏幕矩形(上,右,下,左)外侧绘制高亮框，阻塞 `duration` 后移除
+///
+/// 高亮框是置顶、不激活、鼠标可穿透的分层窗口，不影响目标窗口的焦点和输入。
+/// 矩形大小为零时返回 [`Error::EmptyWindow`]。
+pub(crate) fn highlight_rect(
+    (top, right, bottom, left): (i32, i32, i32, i32),
+    duration: Duration,
+    color: Color,
+) -> Result<()> {
+    register_class()?;
+
+    let (x, y) = (left - BORDER_WIDTH, top - BORDER_WIDTH);
+    let width = right - left + BORDER_WIDTH * 2;
+    let height = bottom - top + BORDER_WIDTH * 2;
+    if width <= BORDER_WIDTH * 2 || height <= BORDER_WIDTH * 2 {
+        return Err(Error::EmptyWindow);
+    }
+
+    let hwnd = unsafe {
+        CreateWindowExW(
+            WS_EX_LAYERED | WS_EX_TRANSPARENT | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE,
+            HIGHLIGHT_CLASS,
+            PCWSTR::null(),
+            WS_POPUP,
+            x,
+            y,
+            width,
+            height,
+            None,
+            None,
+            None,
+            None,
+        )
+    }?;
+
+    let brush = unsafe { CreateSolidBrush(COLORREF(color.to_colorref())) };
+    let shown = show_frame(hwnd, brush, width, height, duration);
+
+    unsafe {
+        let _ = DestroyWindow(hwnd);
+        let _ = DeleteObject(brush.into());
+    }
+
+    shown
+}
+
+fn show_frame(
+    hwnd: HWND,
+    brush: HBRUSH,
+    width: i32,
+    height: i32,
+    duration: Duration,
+) -> Result<()> {
+    unsafe {
+        SetWindowLongPtrW(hwnd, GWLP_USERDATA, brush.0 as isize);
+        SetLayeredWindowAttributes(hwnd, COLORREF(0), ALPHA, LWA_ALPHA)?;
+
+        // 挖掉中间部分只保留边框，设置成功后区域归系统所有，不需要释放
+        let frame = CreateRectRgn(0, 0, width, height);
+        let inner = CreateRectRgn(
+            BORDER_WIDTH,
+            BORDER_WIDTH,
+            width - BORDER_WIDTH,
+            height - BORDER_WIDTH,
+        );
+        CombineRgn(Some(frame), Some(frame), Some(inner), RGN_DIFF);
+        let _ = DeleteObject(inner.into());
+        SetWindowRgn(hwnd, Some(frame), false);
+
+        let _ = ShowWindow(hwnd, SW_SHOWNOACTIVATE);
+    }
+
+    // 窗口属于当前线程，显示期间需要泵送消息才能完成绘制；只取高亮框的消息，
+    // 不替调用方处理线程上其它窗口的消息
+    let started = Instant::now();
+    let mut msg = MSG::default();
+    while started.elapsed() < duration {
+        while unsafe { PeekMessageW(&mut msg, Some(hwnd), 0, 0, PM_REMOVE) }.as_bool() {
+            unsafe {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        }
+        sleep(PUMP_INTERVAL.min(duration.saturating_sub(started.elapsed())));
+    }
+
+    Ok(())
+}
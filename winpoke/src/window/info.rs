@@ -5,14 +5,25 @@ use crate::prelude::Result;
 use crate::window::style::WindowStyle;
 
 use super::*;
-use windows::Win32::Foundation::{HWND, LPARAM};
+use crate::window::msg::send_message_timeout;
+use windows::Win32::Foundation::{HWND, LPARAM, POINT, SetLastError, WIN32_ERROR, WPARAM};
+use windows::Win32::Graphics::Gdi::ScreenToClient;
 use windows::Win32::UI::WindowsAndMessaging::{
-    EnumWindows, FindWindowExW, GetClassNameW, GetWindowInfo, GetWindowTextW,
-    GetWindowThreadProcessId, WINDOWINFO, WS_ACTIVECAPTION,
+    CWP_SKIPINVISIBLE, CWP_SKIPTRANSPARENT, ChildWindowFromPointEx, EnumWindows, FindWindowExW,
+    GA_PARENT, GetAncestor, GetClassNameW, GetCursorPos, GetDesktopWindow, GetWindowInfo,
+    GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId, WINDOWINFO, WM_GETTEXT,
+    WM_GETTEXTLENGTH, WS_ACTIVECAPTION, WindowFromPoint,
 };
-use windows::core::{BOOL, HSTRING};
+use windows::core::{self, BOOL, HSTRING};
+
+/// 窗口类名的最大长度（不含结尾的 NUL）
+const MAX_CLASS_NAME_LEN: usize = 256;
 
 /// 安全枚举所有顶级窗口并返回窗口句柄列表
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, err(level = "debug"))
+)]
 pub(crate) fn enumerate_top_level_windows() -> Result<Vec<HWND>> {
     let mut hwnds = Vec::new();
 
@@ -39,6 +50,11 @@ extern "system" fn enum_func(hwnd: HWND, lparam: LPARAM) -> BOOL {
     BOOL(1) // 继续枚举
 }
 
+// 没有子窗口时也返回 WindowNotFound，调用方通常视为正常情况，因此错误只记为 debug
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(parent = ?parent.0), err(level = "debug"))
+)]
 pub(crate) fn enum_child_window(parent: HWND) -> Result<Vec<HWND>> {
     let mut pre_child = unsafe { FindWindowExW(Some(parent), None, None, None) }
         .map_err(|_| Error::WindowNotFound)?;
@@ -55,6 +71,10 @@ pub(crate) fn enum_child_window(parent: HWND) -> Result<Vec<HWND>> {
     Ok(child_windows)
 }
 
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(parent = ?parent.0, class_name = class_name.as_ref()), err(level = "debug"))
+)]
 pub(crate) fn enum_child_window_with_class_name(
     parent: HWND,
     class_name: impl AsRef<str>,
@@ -90,24 +110,136 @@ pub(crate) fn get_window_tid_and_pid(hwnd: HWND) -> Result<(u32, u32)> {
     Ok((tid, pid))
 }
 
+/// 清空线程的 last error，用于区分“结果为空”和“调用失败”
+fn clear_last_error() {
+    unsafe { SetLastError(WIN32_ERROR(0)) };
+}
+
+/// API 返回 0 时根据 last error 判断是空结果还是调用失败
+fn empty_or_last_error() -> Result<String> {
+    let error = core::Error::from_win32();
+
+    if error.code().is_ok() {
+        Ok(String::new())
+    } else {
+        Err(error.into())
+    }
+}
+
 /// 通过窗口句柄获取窗口标题
+///
+/// 先通过 `GetWindowTextLengthW` 获取长度再分配缓冲区，标题不会被截断；
+/// 标题为空时返回空字符串，调用失败时返回错误。
+///
+/// 对其它进程的控件只能取到其标题，控件内容请使用 [`get_window_text`]。
 pub(crate) fn get_window_caption(hwnd: HWND) -> Result<String> {
-    let mut buffer = [0u16; 255];
+    clear_last_error();
+    let len = unsafe { GetWindowTextLengthW(hwnd) };
+    if len == 0 {
+        return empty_or_last_error();
+    }
 
+    // 标题可能在两次调用之间变长，多留一位给结尾的 NUL
+    let mut buffer = vec![0u16; len as usize + 1];
+
+    clear_last_error();
     let len = unsafe { GetWindowTextW(hwnd, &mut buffer) };
+    if len == 0 {
+        return empty_or_last_error();
+    }
 
     Ok(String::from_utf16_lossy(&buffer[..len as usize]))
 }
 
+/// 通过 `WM_GETTEXT` 获取窗口文本
+///
+/// 与 [`get_window_caption`] 不同，该消息会发送到目标窗口，因此可以读取
+/// 其它进程中编辑框等控件的内容。目标窗口在 `timeout` 毫秒内未响应时返回
+/// [`Error::MessageTimeout`]。
+pub(crate) fn get_window_text(hwnd: HWND, timeout: u32) -> Result<String> {
+    let len = send_message_timeout(hwnd, WM_GETTEXTLENGTH, WPARAM(0), LPARAM(0), timeout)?;
+    if len == 0 {
+        return Ok(String::new());
+    }
+
+    // WM_GETTEXTLENGTH 的结果可能大于实际长度（如 DBCS 文本），多留一位给结尾的 NUL
+    let mut buffer = vec![0u16; len + 1];
+
+    let len = send_message_timeout(
+        hwnd,
+        WM_GETTEXT,
+        WPARAM(buffer.len()),
+        LPARAM(buffer.as_mut_ptr() as isize),
+        timeout,
+    )?;
+
+    Ok(String::from_utf16_lossy(&buffer[..len.min(buffer.len())]))
+}
+
 /// 通过窗口句柄获取窗口类名
 pub(crate) fn get_window_class_name(hwnd: HWND) -> Result<String> {
-    let mut buffer = [0u16; 512];
+    let mut buffer = [0u16; MAX_CLASS_NAME_LEN + 1];
+
+    // 窗口类名不可能为空，返回 0 即表示调用失败
     let len = unsafe { GetClassNameW(hwnd, &mut buffer) };
+    if len == 0 {
+        return Err(core::Error::from_win32().into());
+    }
 
     Ok(String::from_utf16_lossy(&buffer[..len as usize]))
 }
 
+/// 屏幕坐标处最深层的可见窗口
+///
+/// `WindowFromPoint` 会跳过禁用的控件并可能停在分组框等容器上，
+/// 因此再用 `ChildWindowFromPointEx` 逐级向下查找。
+pub(crate) fn window_from_point(x: i32, y: i32) -> Result<HWND> {
+    let screen = POINT { x, y };
+    let mut hwnd = unsafe { WindowFromPoint(screen) };
+    if hwnd.is_invalid() {
+        return Err(Error::WindowNotFound);
+    }
+
+    loop {
+        let mut client = screen;
+        if !unsafe { ScreenToClient(hwnd, &mut client) }.as_bool() {
+            break;
+        }
+
+        let child = unsafe {
+            ChildWindowFromPointEx(hwnd, client, CWP_SKIPINVISIBLE | CWP_SKIPTRANSPARENT)
+        };
+        if child.is_invalid() || child == hwnd {
+            break;
+        }
+        hwnd = child;
+    }
+
+    Ok(hwnd)
+}
+
+/// 鼠标光标的屏幕坐标
+pub(crate) fn cursor_position() -> Result<(i32, i32)> {
+    let mut point = POINT::default();
+    unsafe { GetCursorPos(&mut point) }?;
+
+    Ok((point.x, point.y))
+}
+
+/// 父窗口，顶层窗口返回 `None`
+pub(crate) fn get_parent_window(hwnd: HWND) -> Option<HWND> {
+    // GetParent 对顶层窗口返回所有者窗口，GetAncestor(GA_PARENT) 才是真正的父窗口
+    let parent = unsafe { GetAncestor(hwnd, GA_PARENT) };
+    let desktop = unsafe { GetDesktopWindow() };
+
+    (!parent.is_invalid() && parent != desktop).then_some(parent)
+}
+
 /// 通过窗口句柄获取窗口信息
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(hwnd = ?hwnd.0), err(level = "debug"))
+)]
 pub(crate) fn get_window_info(hwnd: HWND) -> Result<WindowInfo> {
     let mut info = WINDOWINFO {
         cbSize: std::mem::size_of::<WINDOWINFO>() as u32,
@@ -182,6 +314,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_get_window_text() {
+        let hwnd = unsafe { FindWindowW(&HSTRING::from("RegEdit_RegEdit"), None) }
+            .expect("找不到指定窗口");
+        let caption = get_window_caption(hwnd).expect("获取窗口标题失败");
+        let text = get_window_text(hwnd, 1000).expect("获取窗口文本失败");
+
+        assert_eq!(caption, text);
+    }
+
     #[test]
     fn test_print_all_child_windows() {
         let hwnd = unsafe { FindWindowW(&HSTRING::from("RegEdit_RegEdit"), None) }
@@ -0,0 +1,96 @@
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+
+use windows::Win32::Foundation::{LPARAM, WPARAM};
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::WindowsAndMessaging::{
+    DispatchMessageW, GetMessageW, MSG, PM_NOREMOVE, PeekMessageW, PostThreadMessageW,
+    TranslateMessage, WM_QUIT,
+};
+
+use crate::error::Error;
+use crate::prelude::Result;
+
+/// 运行消息循环的后台线程
+///
+/// 钩子、热键等 API 的回调只会在注册它们的线程的消息循环中被调用，
+/// 因此需要一个专门的线程来注册并泵送消息。`Drop` 时退出消息循环并等待线程结束。
+pub(crate) struct MessageLoop {
+    tid: u32,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MessageLoop {
+    /// 启动消息循环线程
+    ///
+    /// `setup` 在新线程中、消息队列创建之后执行，其返回的处理函数会收到每一条
+    /// 线程消息（`hwnd` 为空的消息）；处理函数在消息循环退出时被释放，
+    /// 可以借此持有需要在同一线程注销的资源。
+    pub(crate) fn spawn<S, H>(setup: S) -> Result<Self>
+    where
+        S: FnOnce() -> Result<H> + Send + 'static,
+        H: FnMut(&MSG) + 'static,
+    {
+        let (init_tx, init_rx) = mpsc::channel();
+
+        let handle = std::thread::spawn(move || {
+            let mut msg = MSG::default();
+
+            // 先创建线程的消息队列，之后 PostThreadMessageW 才能投递成功
+            let _ = unsafe { PeekMessageW(&mut msg, None, 0, 0, PM_NOREMOVE) };
+
+            let mut handler = match setup() {
+                Ok(handler) => handler,
+                Err(e) => {
+                    let _ = init_tx.send(Err(e));
+                    return;
+                }
+            };
+            let _ = init_tx.send(Ok(unsafe { GetCurrentThreadId() }));
+
+            // GetMessageW 在收到 WM_QUIT 时返回 0，出错时返回 -1
+            while unsafe { GetMessageW(&mut msg, None, 0, 0) }.0 > 0 {
+                if msg.hwnd.is_invalid() {
+                    handler(&msg);
+                }
+
+                unsafe {
+                    let _ = TranslateMessage(&msg);
+                    DispatchMessageW(&msg);
+                }
+            }
+        });
+
+        match init_rx.recv() {
+            Ok(Ok(tid)) => Ok(Self {
+                tid,
+                handle: Some(handle),
+            }),
+            Ok(Err(e)) => {
+                let _ = handle.join();
+                Err(e)
+            }
+            Err(_) => {
+                let _ = handle.join();
+                Err(Error::MessageLoopExited)
+            }
+        }
+    }
+
+    /// 向消息循环线程投递一条线程消息
+    pub(crate) fn post(&self, msg: u32, wparam: WPARAM, lparam: LPARAM) -> Result<()> {
+        unsafe { PostThreadMessageW(self.tid, msg, wparam, lparam) }?;
+
+        Ok(())
+    }
+}
+
+impl Drop for MessageLoop {
+    fn drop(&mut self) {
+        let _ = self.post(WM_QUIT, WPARAM(0), LPARAM(0));
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
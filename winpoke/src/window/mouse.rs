@@ -0,0 +1,300 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use windows::Win32::System::SystemServices::{MK_LBUTTON, MK_MBUTTON, MK_RBUTTON};
+use windows::Win32::UI::HiDpi::GetDpiForWindow;
+use windows::Win32::UI::WindowsAndMessaging::{
+    USER_DEFAULT_SCREEN_DPI, WHEEL_DELTA, WM_LBUTTONDBLCLK, WM_LBUTTONDOWN, WM_LBUTTONUP,
+    WM_MBUTTONDBLCLK, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEMOVE, WM_MOUSEWHEEL, WM_RBUTTONDBLCLK,
+    WM_RBUTTONDOWN, WM_RBUTTONUP,
+};
+
+use crate::input::{self, MouseButton};
+use crate::prelude::Result;
+use crate::window::WindowInfo;
+use crate::window::msg::{Delivery, make_point_lparam, send_message};
+
+/// 拖动时插入的中间移动事件数量
+const DRAG_STEPS: u32 = 10;
+
+/// 拖动时相邻两次移动的间隔
+const DRAG_INTERVAL: Duration = Duration::from_millis(10);
+
+/// 按窗口 DPI 将逻辑坐标（96 DPI）换算为物理像素
+pub(crate) fn scale_for_dpi((x, y): (i32, i32), dpi: u32) -> (i32, i32) {
+    let base = USER_DEFAULT_SCREEN_DPI as i64;
+    let scale = |value: i32| {
+        let scaled = value as i64 * dpi as i64;
+
+        // 向远离零的方向四舍五入
+        let rounded = scaled + scaled.signum() * base / 2;
+        (rounded / base) as i32
+    };
+
+    (scale(x), scale(y))
+}
+
+/// 将工作区坐标换算为屏幕坐标
+///
+/// `client_position` 为 [`WindowInfo::client_position`]，即工作区在屏幕上的(上,右,下,左)。
+pub(crate) fn client_to_screen(
+    (x, y): (i32, i32),
+    (top, _, _, left): (i32, i32, i32, i32),
+) -> (i32, i32) {
+    (left + x, top + y)
+}
+
+/// 工作区的中心点（工作区坐标）
+pub(crate) fn client_center((top, right, bottom, left): (i32, i32, i32, i32)) -> (i32, i32) {
+    ((right - left) / 2, (bottom - top) / 2)
+}
+
+/// 在两点之间等距插入 `steps` 个点，包含终点、不包含起点
+pub(crate) fn interpolate(from: (i32, i32), to: (i32, i32), steps: u32) -> Vec<(i32, i32)> {
+    let steps = steps.max(1) as i64;
+    let lerp = |a: i32, b: i32, i: i64| (a as i64 + (b as i64 - a as i64) * i / steps) as i32;
+
+    (1..=steps)
+        .map(|i| (lerp(from.0, to.0, i), lerp(from.1, to.1, i)))
+        .collect()
+}
+
+/// 鼠标按键对应的(按下,松开,双击)消息和 `wParam` 标志
+fn button_messages(button: MouseButton) -> (u32, u32, u32, u32) {
+    match button {
+        MouseButton::Left => (WM_LBUTTONDOWN, WM_LBUTTONUP, WM_LBUTTONDBLCLK, MK_LBUTTON.0),
+        MouseButton::Right => (WM_RBUTTONDOWN, WM_RBUTTONUP, WM_RBUTTONDBLCLK, MK_RBUTTON.0),
+        MouseButton::Middle => (WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MBUTTONDBLCLK, MK_MBUTTON.0),
+    }
+}
+
+/// 以工作区坐标操作窗口的鼠标
+///
+/// 坐标为相对窗口工作区左上角的逻辑坐标（96 DPI），会按窗口的 DPI 换算为物理像素；
+/// 坐标已是物理像素（如从控件消息取得的矩形）时使用 [`physical`](Self::physical)。
+/// 通过 [`WindowInfo::mouse`] 获取。
+pub struct Mouse<'a> {
+    window: &'a WindowInfo,
+    delivery: Delivery,
+    scale: bool,
+}
+
+impl Mouse<'_> {
+    /// 坐标已是物理像素，不再按 DPI 换算
+    pub fn physical(mut self) -> Self {
+        self.scale = false;
+        self
+    }
+
+    /// 逻辑坐标换算为物理工作区坐标
+    fn to_physical(&self, point: (i32, i32)) -> (i32, i32) {
+        if !self.scale {
+            return point;
+        }
+
+        // 窗口无效时返回 0，按默认 DPI 处理
+        let dpi = match unsafe { GetDpiForWindow(self.window.hwnd) } {
+            0 => USER_DEFAULT_SCREEN_DPI,
+            dpi => dpi,
+        };
+
+        scale_for_dpi(point, dpi)
+    }
+
+    fn screen(&self, point: (i32, i32)) -> (i32, i32) {
+        client_to_screen(self.to_physical(point), self.window.client_position)
+    }
+
+    fn post(&self, msg: u32, wparam: u32, (x, y): (i32, i32)) -> Result<()> {
+        send_message(
+            self.window.hwnd,
+            msg,
+            Some(wparam),
+            Some(make_point_lparam(x, y)),
+            1,
+        )
+    }
+
+    /// 单击
+    pub fn click(&self, point: (i32, i32), button: MouseButton) -> Result<()> {
+        match self.delivery {
+            Delivery::Message => {
+                let point = self.to_physical(point);
+                let (down, up, _, flag) = button_messages(button);
+
+                self.post(WM_MOUSEMOVE, 0, point)?;
+                self.post(down, flag, point)?;
+                self.post(up, 0, point)
+            }
+            Delivery::Input => {
+                let (x, y) = self.screen(point);
+
+                input::mouse_move_to(x, y)?;
+                input::mouse_click(button)
+            }
+        }
+    }
+
+    /// 双击
+    pub fn double_click(&self, point: (i32, i32), button: MouseButton) -> Result<()> {
+        match self.delivery {
+            Delivery::Message => {
+                let point = self.to_physical(point);
+                let (down, up, double, flag) = button_messages(button);
+
+                // 与系统生成的双击消息顺序一致：按下、松开、双击、松开
+                self.post(WM_MOUSEMOVE, 0, point)?;
+                self.post(down, flag, point)?;
+                self.post(up, 0, point)?;
+                self.post(double, flag, point)?;
+                self.post(up, 0, point)
+            }
+            Delivery::Input => {
+                let (x, y) = self.screen(point);
+
+                input::mouse_move_to(x, y)?;
+                input::mouse_click(button)?;
+                input::mouse_click(button)
+            }
+        }
+    }
+
+    /// 按住左键从 `from` 拖动到 `to`
+    pub fn drag(&self, from: (i32, i32), to: (i32, i32)) -> Result<()> {
+        let (down, up, _, flag) = button_messages(MouseButton::Left);
+
+        match self.delivery {
+            Delivery::Message => {
+                let from = self.to_physical(from);
+                let to = self.to_physical(to);
+
+                self.post(WM_MOUSEMOVE, 0, from)?;
+                self.post(down, flag, from)?;
+                for point in interpolate(from, to, DRAG_STEPS) {
+                    sleep(DRAG_INTERVAL);
+                    self.post(WM_MOUSEMOVE, flag, point)?;
+                }
+                self.post(up, 0, to)
+            }
+            Delivery::Input => {
+                let from = self.screen(from);
+                let to = self.screen(to);
+
+                input::mouse_move_to(from.0, from.1)?;
+                input::mouse_down(MouseButton::Left)?;
+                for (x, y) in interpolate(from, to, DRAG_STEPS) {
+                    sleep(DRAG_INTERVAL);
+                    input::mouse_move_to(x, y)?;
+                }
+                input::mouse_up(MouseButton::Left)
+            }
+        }
+    }
+
+    /// 在工作区中心滚动鼠标滚轮，`delta` 为滚动的格数，正数向上
+    pub fn scroll(&self, delta: i32) -> Result<()> {
+        let center = client_center(self.window.client_position);
+        let (x, y) = client_to_screen(center, self.window.client_position);
+        let wheel = delta * WHEEL_DELTA as i32;
+
+        match self.delivery {
+            Delivery::Message => {
+                // WM_MOUSEWHEEL 的坐标为屏幕坐标，滚动量位于 wParam 的高 16 位
+                let wparam = (wheel as i16 as u16 as u32) << 16;
+
+                send_message(
+                    self.window.hwnd,
+                    WM_MOUSEWHEEL,
+                    Some(wparam),
+                    Some(make_point_lparam(x, y)),
+                    1,
+                )
+            }
+            Delivery::Input => {
+                input::mouse_move_to(x, y)?;
+                input::mouse_wheel(wheel)
+            }
+        }
+    }
+}
+
+impl WindowInfo {
+    /// 以指定的投递方式操作窗口的鼠标
+    ///
+    /// [`Delivery::Input`] 会真实移动鼠标指针，需要窗口可见且未被遮挡。
+    pub fn mouse(&self, delivery: Delivery) -> Mouse<'_> {
+        Mouse {
+            window: self,
+            delivery,
+            scale: true,
+        }
+    }
+
+    /// 通过窗口消息在工作区坐标处单击
+    pub fn click(&self, point: (i32, i32), button: MouseButton) -> Result<()> {
+        self.mouse(Delivery::Message).click(point, button)
+    }
+
+    /// 通过窗口消息在工作区坐标处双击
+    pub fn double_click(&self, point: (i32, i32), button: MouseButton) -> Result<()> {
+        self.mouse(Delivery::Message).double_click(point, button)
+    }
+
+    /// 通过窗口消息从 `from` 拖动到 `to`
+    pub fn drag(&self, from: (i32, i32), to: (i32, i32)) -> Result<()> {
+        self.mouse(Delivery::Message).drag(from, to)
+    }
+
+    /// 通过窗口消息滚动鼠标滚轮
+    pub fn scroll(&self, delta: i32) -> Result<()> {
+        self.mouse(Delivery::Message).scroll(delta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dpi_scaling() {
+        assert_eq!(scale_for_dpi((10, 20), 96), (10, 20));
+        assert_eq!(scale_for_dpi((10, 20), 144), (15, 30));
+        assert_eq!(scale_for_dpi((11, 1), 120), (14, 1));
+        assert_eq!(scale_for_dpi((-11, 0), 120), (-14, 0));
+    }
+
+    #[test]
+    fn client_to_screen_offsets() {
+        // 工作区位于屏幕 (100, 50) 处
+        let client = (50, 900, 650, 100);
+        assert_eq!(client_to_screen((0, 0), client), (100, 50));
+        assert_eq!(client_to_screen((10, 20), client), (110, 70));
+
+        // 副屏在主屏左侧时屏幕坐标为负
+        let client = (-300, -1000, 200, -1600);
+        assert_eq!(client_to_screen((5, 5), client), (-1595, -295));
+    }
+
+    #[test]
+    fn center() {
+        assert_eq!(client_center((50, 900, 650, 100)), (400, 300));
+    }
+
+    #[test]
+    fn interpolation() {
+        assert_eq!(
+            interpolate((0, 0), (10, -20), 5),
+            vec![(2, -4), (4, -8), (6, -12), (8, -16), (10, -20)]
+        );
+        assert_eq!(interpolate((3, 3), (7, 9), 0), vec![(7, 9)]);
+    }
+
+    #[test]
+    fn test_click_regedit() {
+        let windows = WindowInfo::find_by_class_name("RegEdit_RegEdit").unwrap();
+        for window in windows {
+            window.click((10, 10), MouseButton::Left).unwrap();
+            window.scroll(-1).unwrap();
+        }
+    }
+}
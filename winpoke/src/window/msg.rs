@@ -1,26 +1,67 @@
-use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
-use windows::Win32::UI::WindowsAndMessaging::{SendMessageW, WM_CHAR, WM_COMMAND, WM_KEYDOWN};
+#[cfg(feature = "tracing")]
+mod names;
 
+use windows::Win32::Foundation::{ERROR_TIMEOUT, GetLastError, HWND, LPARAM, WPARAM};
+use windows::Win32::UI::WindowsAndMessaging::{
+    PostMessageW, SMTO_ABORTIFHUNG, SendMessageTimeoutW, SendMessageW, WM_CHAR, WM_COMMAND,
+    WM_KEYDOWN, WM_MOUSEMOVE,
+};
+use windows::core;
+
+use crate::error::Error;
 use crate::prelude::Result;
 
-#[derive(Debug, Default)]
-pub struct Message {
-    pub msg: WindowMessage,
-    pub count: u32,
+pub use crate::message::{Message, WindowMessage};
+
+/// 消息序列的投递方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Delivery {
+    /// 通过 `SendMessageW` 直接发送到窗口，不需要窗口位于前台
+    #[default]
+    Message,
+
+    /// 通过 `SendInput` 注入真实的键盘鼠标输入，
+    /// 适用于忽略窗口消息的程序（DirectX、Chromium、WPF 等），需要窗口位于前台
+    Input,
 }
 
-#[derive(Debug)]
-pub enum WindowMessage {
-    KeyDown(u32),
-    MouseMove(i32, i32),
-    Char(char),
-    Command(u32),
+/// 将坐标打包为鼠标消息的 `lParam`（低位为 x，高位为 y）
+pub(crate) fn make_point_lparam(x: i32, y: i32) -> u32 {
+    (x as u16 as u32) | ((y as u16 as u32) << 16)
 }
 
-impl Default for WindowMessage {
-    fn default() -> Self {
-        WindowMessage::KeyDown(0)
-    }
+/// 执行一次消息调用
+///
+/// 启用 `tracing` 特性时以 `winpoke::message` 为目标记录一条 debug 事件，
+/// 包含窗口句柄、消息名称、参数、结果和耗时。
+fn traced<T: std::fmt::Debug>(
+    api: &'static str,
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    call: impl FnOnce() -> T,
+) -> T {
+    #[cfg(not(feature = "tracing"))]
+    let _ = (api, hwnd, msg, wparam, lparam);
+    #[cfg(feature = "tracing")]
+    let start = std::time::Instant::now();
+
+    let result = call();
+
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        target: "winpoke::message",
+        api,
+        hwnd = ?hwnd.0,
+        msg = %names::message_name(msg),
+        wparam = wparam.0,
+        lparam = lparam.0,
+        result = ?result,
+        duration = ?start.elapsed(),
+    );
+
+    result
 }
 
 pub(crate) fn send_message(
@@ -31,15 +72,66 @@ pub(crate) fn send_message(
     count: u32,
 ) -> Result<()> {
     for _ in 0..count.max(1) {
-        let wparam = wparam.map(|w| WPARAM(w as usize));
-        let lparam = lparam.map(|l| LPARAM(l as isize));
+        let wparam = WPARAM(wparam.unwrap_or_default() as usize);
+        let lparam = LPARAM(lparam.unwrap_or_default() as isize);
 
-        unsafe { SendMessageW(hwnd, msg, wparam, lparam) };
+        traced("SendMessageW", hwnd, msg, wparam, lparam, || {
+            unsafe { SendMessageW(hwnd, msg, Some(wparam), Some(lparam)) }.0
+        });
     }
 
     Ok(())
 }
 
+/// 发送消息并等待结果，目标窗口在 `timeout` 毫秒内未处理或已挂起时返回
+/// [`Error::MessageTimeout`]
+pub(crate) fn send_message_timeout(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    timeout: u32,
+) -> Result<usize> {
+    traced("SendMessageTimeoutW", hwnd, msg, wparam, lparam, || {
+        let mut result = 0usize;
+
+        let ret = unsafe {
+            SendMessageTimeoutW(
+                hwnd,
+                msg,
+                wparam,
+                lparam,
+                SMTO_ABORTIFHUNG,
+                timeout,
+                Some(&mut result),
+            )
+        };
+
+        if ret.0 == 0 {
+            return match unsafe { GetLastError() } {
+                // 目标窗口挂起时 last error 可能为 0，同样视为超时
+                error if error == ERROR_TIMEOUT || error.0 == 0 => Err(Error::MessageTimeout),
+                _ => Err(core::Error::from_win32().into()),
+            };
+        }
+
+        Ok(result)
+    })
+}
+
+/// 投递消息后立即返回，不等待目标窗口处理
+pub(crate) fn post_message(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> Result<()> {
+    traced("PostMessageW", hwnd, msg, wparam, lparam, || unsafe {
+        PostMessageW(Some(hwnd), msg, wparam, lparam)
+    })?;
+
+    Ok(())
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(hwnd = ?hwnd.0, messages = msg_seq.len()), err)
+)]
 pub(crate) fn send_message_seq(hwnd: HWND, msg_seq: Vec<Message>) -> Result<()> {
     for message in msg_seq {
         match message.msg {
@@ -56,7 +148,13 @@ pub(crate) fn send_message_seq(hwnd: HWND, msg_seq: Vec<Message>) -> Result<()>
             WindowMessage::KeyDown(virtual_key) => {
                 send_message(hwnd, WM_KEYDOWN, Some(virtual_key), None, message.count)?
             }
-            WindowMessage::MouseMove(_, _) => todo!(),
+            WindowMessage::MouseMove(x, y) => send_message(
+                hwnd,
+                WM_MOUSEMOVE,
+                Some(0),
+                Some(make_point_lparam(x, y)),
+                message.count,
+            )?,
         }
     }
 
@@ -71,6 +169,14 @@ mod tests {
     use windows::Win32::UI::Input::KeyboardAndMouse::VK_LEFT;
 
     #[test]
+    fn point_lparam() {
+        assert_eq!(make_point_lparam(0x12, 0x34), 0x0034_0012);
+        // 负坐标按 16 位补码打包
+        assert_eq!(make_point_lparam(-1, 2), 0x0002_FFFF);
+    }
+
+    #[test]
+    #[allow(clippy::filter_next)]
     fn test_send_message() -> Result<()> {
         let windows = WindowInfo::find_by_class_name("RegEdit_RegEdit") // "RegEdit_RegEdit"
             .expect("找不到指定窗口");
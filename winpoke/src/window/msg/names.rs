@@ -0,0 +1,98 @@
+use windows::Win32::UI::Controls::{
+    EM_GETSEL, EM_REPLACESEL, EM_SETSEL, HDM_GETITEMCOUNT, HDM_GETITEMW, LVM_ENSUREVISIBLE,
+    LVM_GETHEADER, LVM_GETITEMCOUNT, LVM_GETITEMRECT, LVM_GETITEMSTATE, LVM_GETITEMTEXTW,
+    LVM_GETNEXTITEM, LVM_SETITEMSTATE, TVM_ENSUREVISIBLE, TVM_EXPAND, TVM_GETITEMW,
+    TVM_GETNEXTITEM, TVM_SELECTITEM,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    BM_CLICK, BM_GETCHECK, BM_SETCHECK, CB_FINDSTRINGEXACT, CB_GETCOUNT, CB_GETCURSEL,
+    CB_GETLBTEXT, CB_GETLBTEXTLEN, CB_SETCURSEL, LB_FINDSTRINGEXACT, LB_GETCOUNT, LB_GETCURSEL,
+    LB_GETTEXT, LB_GETTEXTLEN, LB_SETCURSEL, WM_CHAR, WM_CLOSE, WM_COMMAND, WM_GETTEXT,
+    WM_GETTEXTLENGTH, WM_KEYDOWN, WM_KEYUP, WM_LBUTTONDBLCLK, WM_LBUTTONDOWN, WM_LBUTTONUP,
+    WM_MBUTTONDBLCLK, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEMOVE, WM_MOUSEWHEEL, WM_RBUTTONDBLCLK,
+    WM_RBUTTONDOWN, WM_RBUTTONUP, WM_SETTEXT, WM_SYSKEYDOWN, WM_SYSKEYUP,
+};
+
+macro_rules! names {
+    ($($name:ident),* $(,)?) => {
+        &[$(($name, stringify!($name))),*]
+    };
+}
+
+/// 本库发送的消息及其名称
+const MESSAGE_NAMES: &[(u32, &str)] = names![
+    WM_SETTEXT,
+    WM_GETTEXT,
+    WM_GETTEXTLENGTH,
+    WM_CLOSE,
+    WM_KEYDOWN,
+    WM_KEYUP,
+    WM_CHAR,
+    WM_SYSKEYDOWN,
+    WM_SYSKEYUP,
+    WM_COMMAND,
+    WM_MOUSEMOVE,
+    WM_LBUTTONDOWN,
+    WM_LBUTTONUP,
+    WM_LBUTTONDBLCLK,
+    WM_RBUTTONDOWN,
+    WM_RBUTTONUP,
+    WM_RBUTTONDBLCLK,
+    WM_MBUTTONDOWN,
+    WM_MBUTTONUP,
+    WM_MBUTTONDBLCLK,
+    WM_MOUSEWHEEL,
+    EM_GETSEL,
+    EM_SETSEL,
+    EM_REPLACESEL,
+    BM_GETCHECK,
+    BM_SETCHECK,
+    BM_CLICK,
+    CB_GETCOUNT,
+    CB_GETCURSEL,
+    CB_GETLBTEXT,
+    CB_GETLBTEXTLEN,
+    CB_SETCURSEL,
+    CB_FINDSTRINGEXACT,
+    LB_SETCURSEL,
+    LB_GETCURSEL,
+    LB_GETTEXT,
+    LB_GETTEXTLEN,
+    LB_GETCOUNT,
+    LB_FINDSTRINGEXACT,
+    HDM_GETITEMCOUNT,
+    HDM_GETITEMW,
+    LVM_GETITEMCOUNT,
+    LVM_GETNEXTITEM,
+    LVM_GETITEMRECT,
+    LVM_ENSUREVISIBLE,
+    LVM_GETHEADER,
+    LVM_SETITEMSTATE,
+    LVM_GETITEMSTATE,
+    LVM_GETITEMTEXTW,
+    TVM_EXPAND,
+    TVM_GETNEXTITEM,
+    TVM_SELECTITEM,
+    TVM_ENSUREVISIBLE,
+    TVM_GETITEMW,
+];
+
+/// 日志中显示的消息名称，未知的消息显示为十六进制
+pub(crate) fn message_name(msg: u32) -> String {
+    MESSAGE_NAMES
+        .iter()
+        .find(|&&(code, _)| code == msg)
+        .map_or_else(|| format!("0x{msg:04X}"), |(_, name)| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names() {
+        assert_eq!(message_name(WM_CHAR), "WM_CHAR");
+        assert_eq!(message_name(LVM_GETITEMTEXTW), "LVM_GETITEMTEXTW");
+        assert_eq!(message_name(0x8001), "0x8001");
+    }
+}
@@ -65,4 +65,4 @@ pub(crate) const EX_STYLE_MAP: &[(u32, &str)] = style_map!(
     WS_EX_LAYOUTRTL,
     WS_EX_COMPOSITED,
     WS_EX_NOACTIVATE
-);
\ No newline at end of file
+);